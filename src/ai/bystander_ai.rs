@@ -0,0 +1,39 @@
+use specs::prelude::*;
+
+use super::super::BystanderAi;
+use super::{flee_step, take_turns, wander_step, Action, MobData};
+use rltk::{Point, RandomNumberGenerator};
+
+pub struct BystanderAI {}
+
+impl<'a> System<'a> for BystanderAI {
+    type SystemData = (
+        MobData<'a>,
+        ReadStorage<'a, BystanderAi>,
+        WriteExpect<'a, RandomNumberGenerator>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut mobs, bystander_ai, mut rng) = data;
+
+        take_turns(&mut mobs, &bystander_ai, |mob, map, _ai| {
+            let outlook = mob.look_around();
+            // Bystanders never fight, so they keep clear of trouble or potter about
+            let trouble: Vec<Point> = outlook
+                .threats
+                .iter()
+                .chain(outlook.enemies.iter())
+                .copied()
+                .collect();
+            let step = if trouble.is_empty() {
+                wander_step(map, mob.here, &mut rng)
+            } else {
+                flee_step(map, mob.here, &trouble)
+            };
+            match step {
+                Some(idx) => Action::MoveTo(idx),
+                None => Action::Wait,
+            }
+        });
+    }
+}
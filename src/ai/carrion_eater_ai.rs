@@ -0,0 +1,87 @@
+use specs::prelude::*;
+
+use super::super::{
+    initiative_system::EAT_COST, CarrionEaterAi, Corpse, GameLog, LogCategory, Name, Renderable,
+};
+use super::{melee_action, step_towards, take_turns, Action, MobData};
+use rltk::{DistanceAlg, Point};
+use std::collections::HashSet;
+
+pub struct CarrionEaterAI {}
+
+impl<'a> System<'a> for CarrionEaterAI {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        MobData<'a>,
+        ReadStorage<'a, CarrionEaterAi>,
+        ReadStorage<'a, Corpse>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Renderable>,
+        WriteExpect<'a, GameLog>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut mobs, carrion_eater_ai, corpses, names, renderables, mut gamelog) = data;
+
+        let mut remains: Vec<(Entity, Point)> = (&mobs.entities, &corpses, &mobs.positions)
+            .join()
+            .map(|(corpse, _, pos)| (corpse, Point::new(pos.x, pos.y)))
+            .collect();
+        let mut meals: Vec<(Entity, Entity)> = Vec::new();
+
+        take_turns(&mut mobs, &carrion_eater_ai, |mob, map, _ai| {
+            let outlook = mob.look_around();
+
+            // Anything close enough to bite, or frightening enough to run from, comes before
+            // a meal
+            let meal = if outlook.adjacent_target().is_none() && outlook.threats.is_empty() {
+                let visible: HashSet<Point> = mob.viewshed.visible_tiles.iter().copied().collect();
+                remains
+                    .iter()
+                    .filter(|(_, corpse_pos)| visible.contains(corpse_pos))
+                    .min_by(|(_, a), (_, b)| {
+                        let a = DistanceAlg::Pythagoras.distance2d(mob.here, *a);
+                        let b = DistanceAlg::Pythagoras.distance2d(mob.here, *b);
+                        a.total_cmp(&b)
+                    })
+                    .copied()
+            } else {
+                None
+            };
+
+            match meal {
+                Some((corpse, corpse_pos)) if corpse_pos == mob.here => {
+                    remains.retain(|(other, _)| *other != corpse);
+                    meals.push((mob.entity, corpse));
+                    if let (Some(name), Some(corpse_name)) =
+                        (names.get(mob.entity), names.get(corpse))
+                    {
+                        gamelog
+                            .entry(LogCategory::Combat)
+                            .name(&name.name, renderables.get(mob.entity))
+                            .append(" devours the ")
+                            .name(&corpse_name.name, renderables.get(corpse))
+                            .append(".")
+                            .log();
+                    }
+                    Action::Spent(EAT_COST)
+                }
+                Some((_, corpse_pos)) => match step_towards(map, mob.here, corpse_pos) {
+                    Some(idx) => Action::MoveTo(idx),
+                    None => melee_action(map, mob.here, &outlook),
+                },
+                None => melee_action(map, mob.here, &outlook),
+            }
+        });
+
+        // A good meal heals every wound
+        for (eater, corpse) in meals {
+            mobs.entities
+                .delete(corpse)
+                .expect("Unable to delete corpse");
+            if let Some(stats) = mobs.combat_stats.get_mut(eater) {
+                stats.hp = stats.max_hp;
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use specs::prelude::*;
+
+use super::super::{Confusion, ParticleBuilder, Player};
+use super::{take_turns, Action, MobData};
+
+/// Confused mobs lose their turns, whatever their behaviour, until the confusion wears off.
+/// Runs before the behaviour systems, so they only see the monsters still free to act.
+pub struct ConfusedAI {}
+
+impl<'a> System<'a> for ConfusedAI {
+    type SystemData = (
+        MobData<'a>,
+        ReadStorage<'a, Player>,
+        WriteStorage<'a, Confusion>,
+        WriteExpect<'a, ParticleBuilder>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut mobs, player, mut confused, mut particle_builder) = data;
+
+        let mut recovered: Vec<Entity> = Vec::new();
+        take_turns(
+            &mut mobs,
+            (!&player, &mut confused),
+            |mob, _map, (_player, confusion)| {
+                confusion.duration -= 1;
+                if confusion.duration < 1 {
+                    recovered.push(mob.entity);
+                }
+
+                particle_builder.request(
+                    mob.here.x,
+                    mob.here.y,
+                    rltk::RGB::named(rltk::MAGENTA),
+                    rltk::RGB::named(rltk::BLACK),
+                    rltk::to_cp437('?'),
+                    200.0,
+                );
+                Action::Wait
+            },
+        );
+
+        for entity in recovered {
+            confused.remove(entity);
+        }
+    }
+}
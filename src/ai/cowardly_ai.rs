@@ -0,0 +1,27 @@
+use specs::prelude::*;
+
+use super::super::CowardlyAi;
+use super::{melee_action, take_turns, MobData};
+
+pub struct CowardlyAI {}
+
+impl<'a> System<'a> for CowardlyAI {
+    type SystemData = (MobData<'a>, ReadStorage<'a, CowardlyAi>);
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut mobs, cowardly_ai) = data;
+
+        take_turns(&mut mobs, &cowardly_ai, |mob, map, ai| {
+            let mut outlook = mob.look_around();
+            // Badly hurt, it stops picking fights and runs from them instead
+            if let Some(stats) = mob.stats {
+                if stats.hp * 100 < stats.max_hp * ai.flee_below {
+                    let enemies = std::mem::take(&mut outlook.enemies);
+                    outlook.threats.extend(enemies);
+                    outlook.target = None;
+                }
+            }
+            melee_action(map, mob.here, &outlook)
+        });
+    }
+}
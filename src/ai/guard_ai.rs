@@ -0,0 +1,22 @@
+use specs::prelude::*;
+
+use super::super::GuardAi;
+use super::{take_turns, Action, MobData};
+
+pub struct GuardAI {}
+
+impl<'a> System<'a> for GuardAI {
+    type SystemData = (MobData<'a>, ReadStorage<'a, GuardAi>);
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut mobs, guard_ai) = data;
+
+        // A guard never leaves its post, neither to give chase nor to run
+        take_turns(&mut mobs, &guard_ai, |mob, _map, _ai| {
+            match mob.look_around().adjacent_target() {
+                Some(target) => Action::Attack(target),
+                None => Action::Wait,
+            }
+        });
+    }
+}
@@ -0,0 +1,18 @@
+use specs::prelude::*;
+
+use super::super::MeleeAi;
+use super::{melee_action, take_turns, MobData};
+
+pub struct MeleeAI {}
+
+impl<'a> System<'a> for MeleeAI {
+    type SystemData = (MobData<'a>, ReadStorage<'a, MeleeAi>);
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut mobs, melee_ai) = data;
+
+        take_turns(&mut mobs, &melee_ai, |mob, map, _ai| {
+            melee_action(map, mob.here, &mob.look_around())
+        });
+    }
+}
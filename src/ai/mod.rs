@@ -0,0 +1,418 @@
+use super::{
+    initiative_system::{spend_energy, ATTACK_COST, MOVE_COST, WAIT_COST},
+    raws::{faction_reaction, RawMaster, Reaction, PLAYER_FACTION, RAWS},
+    CombatStats, Energy, EntityMoved, Faction, Grudge, Map, MyTurn, Position, RunState, Viewshed,
+    WantsToMelee,
+};
+use rltk::{DistanceAlg, Point, RandomNumberGenerator};
+use specs::prelude::*;
+use specs::shred::ResourceId;
+use specs::storage::MaskedStorage;
+use std::collections::HashSet;
+use std::ops::Deref;
+
+mod bystander_ai;
+mod carrion_eater_ai;
+mod confused_ai;
+mod cowardly_ai;
+mod guard_ai;
+mod melee_ai;
+mod ranged_ai;
+mod vendor_ai;
+pub use bystander_ai::BystanderAI;
+pub use carrion_eater_ai::CarrionEaterAI;
+pub use confused_ai::ConfusedAI;
+pub use cowardly_ai::CowardlyAI;
+pub use guard_ai::GuardAI;
+pub use melee_ai::MeleeAI;
+pub use ranged_ai::RangedAI;
+pub use vendor_ai::VendorAI;
+
+/// What every behaviour system reads and writes to play out its mobs' turns.
+pub struct MobData<'a> {
+    pub map: WriteExpect<'a, Map>,
+    pub player_entity: ReadExpect<'a, Entity>,
+    pub runstate: ReadExpect<'a, RunState>,
+    pub entities: Entities<'a>,
+    pub viewsheds: WriteStorage<'a, Viewshed>,
+    pub positions: WriteStorage<'a, Position>,
+    pub wants_to_melee: WriteStorage<'a, WantsToMelee>,
+    pub entity_moved: WriteStorage<'a, EntityMoved>,
+    pub energy: WriteStorage<'a, Energy>,
+    pub turns: WriteStorage<'a, MyTurn>,
+    pub factions: ReadStorage<'a, Faction>,
+    pub grudges: ReadStorage<'a, Grudge>,
+    pub combat_stats: WriteStorage<'a, CombatStats>,
+}
+
+type MobStorages<'a> = (
+    WriteExpect<'a, Map>,
+    ReadExpect<'a, Entity>,
+    ReadExpect<'a, RunState>,
+    Entities<'a>,
+    WriteStorage<'a, Viewshed>,
+    WriteStorage<'a, Position>,
+    WriteStorage<'a, WantsToMelee>,
+    WriteStorage<'a, EntityMoved>,
+    WriteStorage<'a, Energy>,
+    WriteStorage<'a, MyTurn>,
+    ReadStorage<'a, Faction>,
+    ReadStorage<'a, Grudge>,
+    WriteStorage<'a, CombatStats>,
+);
+
+impl<'a> SystemData<'a> for MobData<'a> {
+    fn setup(world: &mut World) {
+        MobStorages::setup(world);
+    }
+
+    fn fetch(world: &'a World) -> Self {
+        let (
+            map,
+            player_entity,
+            runstate,
+            entities,
+            viewsheds,
+            positions,
+            wants_to_melee,
+            entity_moved,
+            energy,
+            turns,
+            factions,
+            grudges,
+            combat_stats,
+        ) = MobStorages::fetch(world);
+        MobData {
+            map,
+            player_entity,
+            runstate,
+            entities,
+            viewsheds,
+            positions,
+            wants_to_melee,
+            entity_moved,
+            energy,
+            turns,
+            factions,
+            grudges,
+            combat_stats,
+        }
+    }
+
+    fn reads() -> Vec<ResourceId> {
+        MobStorages::reads()
+    }
+
+    fn writes() -> Vec<ResourceId> {
+        MobStorages::writes()
+    }
+}
+
+/// A mob whose turn it is, as its behaviour sees it.
+pub struct Mob<'m> {
+    pub entity: Entity,
+    pub here: Point,
+    pub viewshed: &'m Viewshed,
+    pub stats: Option<&'m CombatStats>,
+    faction: Option<&'m Faction>,
+    grudge: Option<&'m Grudge>,
+    others: &'m [Combatant],
+    raws: &'m RawMaster,
+}
+
+impl Mob<'_> {
+    /// What the mob makes of the combatants it can see.
+    pub fn look_around(&self) -> Outlook {
+        look_around(
+            self.raws,
+            self.entity,
+            self.here,
+            self.viewshed,
+            self.faction,
+            self.grudge,
+            self.others,
+        )
+    }
+}
+
+/// Plays out the turn of every mob joined by `behaviour` whose turn it is: `decide` picks
+/// what each one does, which is then carried out and paid for with its energy.
+pub fn take_turns<J, F>(mobs: &mut MobData, behaviour: J, mut decide: F)
+where
+    J: Join,
+    F: FnMut(&Mob, &mut Map, J::Type) -> Action,
+{
+    if *mobs.runstate != RunState::Ticking {
+        return;
+    }
+
+    let others = combatants(
+        &mobs.entities,
+        &mobs.positions,
+        &mobs.combat_stats,
+        &mobs.factions,
+        *mobs.player_entity,
+    );
+    let raws = RAWS.lock().unwrap();
+
+    let mut acted: Vec<(Entity, i32)> = Vec::new();
+    for (entity, viewshed, pos, _turn, ai) in (
+        &mobs.entities,
+        &mut mobs.viewsheds,
+        &mut mobs.positions,
+        &mobs.turns,
+        behaviour,
+    )
+        .join()
+    {
+        let mob = Mob {
+            entity,
+            here: Point::new(pos.x, pos.y),
+            viewshed,
+            stats: mobs.combat_stats.get(entity),
+            faction: mobs.factions.get(entity),
+            grudge: mobs.grudges.get(entity),
+            others: &others,
+            raws: &raws,
+        };
+        let action = decide(&mob, &mut mobs.map, ai);
+        let cost = perform(
+            action,
+            entity,
+            pos,
+            viewshed,
+            &mut mobs.map,
+            &mut mobs.wants_to_melee,
+            &mut mobs.entity_moved,
+        );
+        acted.push((entity, cost));
+    }
+
+    for (entity, cost) in acted {
+        spend_energy(&mut mobs.energy, &mut mobs.turns, entity, cost);
+    }
+}
+
+/// Someone who can be fought or fled from, as they stood when the system started.
+pub struct Combatant {
+    pub entity: Entity,
+    pub pos: Point,
+    pub faction: String,
+}
+
+/// Everyone with combat stats and a faction. The player counts as the player faction even
+/// without a `Faction`, and others without one are left out.
+pub fn combatants<P, C>(
+    entities: &Entities,
+    positions: &Storage<Position, P>,
+    combat_stats: &Storage<CombatStats, C>,
+    factions: &ReadStorage<Faction>,
+    player_entity: Entity,
+) -> Vec<Combatant>
+where
+    P: Deref<Target = MaskedStorage<Position>>,
+    C: Deref<Target = MaskedStorage<CombatStats>>,
+{
+    (entities, positions, combat_stats)
+        .join()
+        .filter_map(|(entity, pos, _)| {
+            let faction = match factions.get(entity) {
+                Some(faction) => faction.name.clone(),
+                None if entity == player_entity => PLAYER_FACTION.to_string(),
+                None => return None,
+            };
+            Some(Combatant {
+                entity,
+                pos: Point::new(pos.x, pos.y),
+                faction,
+            })
+        })
+        .collect()
+}
+
+/// What a mob makes of the combatants it can see.
+#[derive(Default)]
+pub struct Outlook {
+    /// The nearest of those it would attack, with how far away they are.
+    pub target: Option<(Entity, Point, f32)>,
+    /// Where everyone it would attack stands.
+    pub enemies: Vec<Point>,
+    /// Where everyone it would run from stands.
+    pub threats: Vec<Point>,
+}
+
+impl Outlook {
+    /// The target, if it is close enough to hit.
+    pub fn adjacent_target(&self) -> Option<Entity> {
+        self.target
+            .filter(|(_, _, distance)| *distance < 1.5)
+            .map(|(target, _, _)| target)
+    }
+}
+
+pub fn look_around(
+    raws: &RawMaster,
+    entity: Entity,
+    here: Point,
+    viewshed: &Viewshed,
+    faction: Option<&Faction>,
+    grudge: Option<&Grudge>,
+    combatants: &[Combatant],
+) -> Outlook {
+    let visible: HashSet<Point> = viewshed.visible_tiles.iter().copied().collect();
+    let mut outlook = Outlook::default();
+    for other in combatants.iter() {
+        if other.entity == entity || !visible.contains(&other.pos) {
+            continue;
+        }
+        let reaction = match faction {
+            // Whoever hurt it last is fought, whatever its faction thinks of them
+            _ if grudge.is_some_and(|grudge| grudge.against == other.entity) => Reaction::Attack,
+            Some(mine) => faction_reaction(raws, &mine.name, &other.faction),
+            // Monsters from before there were factions only hunt the player
+            None if other.faction == PLAYER_FACTION => Reaction::Attack,
+            None => Reaction::Ignore,
+        };
+        let distance = DistanceAlg::Pythagoras.distance2d(here, other.pos);
+        match reaction {
+            Reaction::Attack => {
+                outlook.enemies.push(other.pos);
+                if outlook
+                    .target
+                    .map_or(true, |(_, _, nearest)| distance < nearest)
+                {
+                    outlook.target = Some((other.entity, other.pos, distance));
+                }
+            }
+            Reaction::Flee => outlook.threats.push(other.pos),
+            Reaction::Ignore => {}
+        }
+    }
+    outlook
+}
+
+/// What a mob does with its turn.
+pub enum Action {
+    Wait,
+    Attack(Entity),
+    MoveTo(usize),
+    /// Something the behaviour has already seen to itself, costing this much energy.
+    Spent(i32),
+}
+
+/// The turn of a mob that fights hand to hand: hit an adjacent target, otherwise run from any
+/// threats, otherwise close in on the target.
+pub fn melee_action(map: &mut Map, here: Point, outlook: &Outlook) -> Action {
+    if let Some(target) = outlook.adjacent_target() {
+        return Action::Attack(target);
+    }
+    let step = if !outlook.threats.is_empty() {
+        flee_step(map, here, &outlook.threats)
+    } else if let Some((_, target_pos, _)) = outlook.target {
+        step_towards(map, here, target_pos)
+    } else {
+        None
+    };
+    match step {
+        Some(idx) => Action::MoveTo(idx),
+        None => Action::Wait,
+    }
+}
+
+/// Carries out an attack or a move, returning the energy it cost.
+pub fn perform(
+    action: Action,
+    entity: Entity,
+    pos: &mut Position,
+    viewshed: &mut Viewshed,
+    map: &mut Map,
+    wants_to_melee: &mut WriteStorage<WantsToMelee>,
+    entity_moved: &mut WriteStorage<EntityMoved>,
+) -> i32 {
+    match action {
+        Action::Wait => WAIT_COST,
+        Action::Spent(cost) => cost,
+        Action::Attack(target) => {
+            wants_to_melee
+                .insert(entity, WantsToMelee { target })
+                .expect("Unable to insert attack!");
+            ATTACK_COST
+        }
+        // Someone else may have stepped in first, or be standing on what was being made for
+        Action::MoveTo(idx) if map.blocked[idx] => WAIT_COST,
+        Action::MoveTo(idx) => {
+            let old_idx = map.xy_idx(pos.x, pos.y);
+            map.blocked[old_idx] = false;
+            pos.x = idx as i32 % map.width;
+            pos.y = idx as i32 / map.width;
+            map.blocked[idx] = true;
+            viewshed.dirty = true;
+
+            entity_moved
+                .insert(entity, EntityMoved {})
+                .expect("Unable to insert move marker");
+            MOVE_COST
+        }
+    }
+}
+
+/// The first step of the way from `from` to `to`, if there is one.
+pub fn step_towards(map: &mut Map, from: Point, to: Point) -> Option<usize> {
+    // Other mobs block their own tile, which would leave the path nowhere to end
+    let to_idx = map.xy_idx(to.x, to.y);
+    let to_blocked = map.blocked[to_idx];
+    map.blocked[to_idx] = false;
+    let path = rltk::a_star_search(map.xy_idx(from.x, from.y) as i32, to_idx as i32, &*map);
+    map.blocked[to_idx] = to_blocked;
+    if path.success && path.steps.len() > 1 {
+        Some(path.steps[1])
+    } else {
+        None
+    }
+}
+
+/// The neighbouring tile that gets furthest from everything being fled from, if any is
+/// better than standing still.
+pub fn flee_step(map: &Map, here: Point, threats: &[Point]) -> Option<usize> {
+    let safety = |p: Point| -> f32 {
+        threats
+            .iter()
+            .map(|threat| DistanceAlg::Pythagoras.distance2d(p, *threat))
+            .sum()
+    };
+    let mut best: (f32, Option<usize>) = (safety(here), None);
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            let neighbour = Point::new(here.x + dx, here.y + dy);
+            if let Some(idx) = open_tile(map, neighbour) {
+                let score = safety(neighbour);
+                if score > best.0 {
+                    best = (score, Some(idx));
+                }
+            }
+        }
+    }
+    best.1
+}
+
+/// A random neighbouring tile to amble onto, or `None` to stay put for a turn.
+pub fn wander_step(map: &Map, here: Point, rng: &mut RandomNumberGenerator) -> Option<usize> {
+    let dx = rng.roll_dice(1, 3) - 2;
+    let dy = rng.roll_dice(1, 3) - 2;
+    if dx == 0 && dy == 0 {
+        return None;
+    }
+    open_tile(map, Point::new(here.x + dx, here.y + dy))
+}
+
+/// The index of `p`, if it is on the map and free to step onto.
+fn open_tile(map: &Map, p: Point) -> Option<usize> {
+    if p.x < 0 || p.x >= map.width || p.y < 0 || p.y >= map.height {
+        return None;
+    }
+    let idx = map.xy_idx(p.x, p.y);
+    if !map.tiles[idx].is_walkable() || map.blocked[idx] {
+        return None;
+    }
+    Some(idx)
+}
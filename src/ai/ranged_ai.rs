@@ -0,0 +1,55 @@
+use specs::prelude::*;
+
+use super::super::{
+    effects::{EffectQueue, EffectType, Targets},
+    initiative_system::ATTACK_COST,
+    RangedAi,
+};
+use super::{flee_step, melee_action, step_towards, take_turns, Action, MobData};
+
+pub struct RangedAI {}
+
+impl<'a> System<'a> for RangedAI {
+    type SystemData = (
+        MobData<'a>,
+        ReadStorage<'a, RangedAi>,
+        WriteExpect<'a, EffectQueue>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut mobs, ranged_ai, mut effects) = data;
+
+        take_turns(&mut mobs, &ranged_ai, |mob, map, ai| {
+            let outlook = mob.look_around();
+            let mut shoot = |target: Entity| {
+                effects.add(
+                    Some(mob.entity),
+                    EffectType::Damage { amount: ai.damage },
+                    Targets::Single { target },
+                );
+                Action::Spent(ATTACK_COST)
+            };
+
+            match outlook.target {
+                Some((target, target_pos, distance)) if outlook.threats.is_empty() => {
+                    if distance < ai.min_distance as f32 {
+                        // Too close for comfort: back away, and only fight it out when cornered
+                        match flee_step(map, mob.here, &[target_pos]) {
+                            Some(idx) => Action::MoveTo(idx),
+                            None if distance < 1.5 => Action::Attack(target),
+                            None => shoot(target),
+                        }
+                    } else if distance <= ai.range as f32 {
+                        shoot(target)
+                    } else {
+                        match step_towards(map, mob.here, target_pos) {
+                            Some(idx) => Action::MoveTo(idx),
+                            None => Action::Wait,
+                        }
+                    }
+                }
+                _ => melee_action(map, mob.here, &outlook),
+            }
+        });
+    }
+}
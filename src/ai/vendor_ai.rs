@@ -0,0 +1,17 @@
+use specs::prelude::*;
+
+use super::super::VendorAi;
+use super::{take_turns, Action, MobData};
+
+pub struct VendorAI {}
+
+impl<'a> System<'a> for VendorAI {
+    type SystemData = (MobData<'a>, ReadStorage<'a, VendorAi>);
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut mobs, vendor_ai) = data;
+
+        // Vendors stay behind their counters whatever happens, so their turns pass idly
+        take_turns(&mut mobs, &vendor_ai, |_mob, _map, _ai| Action::Wait);
+    }
+}
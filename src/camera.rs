@@ -1,5 +1,5 @@
-use super::{Hidden, Map, Position, Renderable, TileType};
 use rltk::{FontCharType, Point, Rltk, RGB};
+use rogue::{Hidden, Map, Position, Renderable, TileType};
 use specs::prelude::*;
 
 const SHOW_BOUNDS: bool = true;
@@ -10,14 +10,12 @@ pub fn render_camera(ecs: &World, ctx: &mut Rltk) {
     let map_width = map.width - 1;
     let map_height = map.height - 1;
 
-    let mut y = 0;
-    for ty in min_y..max_y {
-        let mut x = 0;
-        for tx in min_x..max_x {
+    for (y, ty) in (0..).zip(min_y..max_y) {
+        for (x, tx) in (0..).zip(min_x..max_x) {
             if tx > 0 && tx < map_width && ty > 0 && ty < map_height {
                 let idx = map.xy_idx(tx, ty);
                 if map.revealed_tiles[idx] {
-                    let (glyph, fg, bg) = get_tile_glyph(idx, &*map);
+                    let (glyph, fg, bg) = get_tile_glyph(idx, &map);
                     ctx.set(x, y, fg, bg, glyph);
                 }
             } else if SHOW_BOUNDS {
@@ -29,9 +27,7 @@ pub fn render_camera(ecs: &World, ctx: &mut Rltk) {
                     rltk::to_cp437('#'),
                 );
             }
-            x += 1;
         }
-        y += 1;
     }
 
     let positions = ecs.read_storage::<Position>();
@@ -42,7 +38,7 @@ pub fn render_camera(ecs: &World, ctx: &mut Rltk) {
     let mut data = (&positions, &renderables, !&hidden)
         .join()
         .collect::<Vec<_>>();
-    data.sort_by(|&a, &b| b.1.render_order.cmp(&a.1.render_order));
+    data.sort_by_key(|a| std::cmp::Reverse(a.1.render_order));
     for (pos, render, _hidden) in data.iter() {
         let idx = map.xy_idx(pos.x, pos.y);
         if map.visible_tiles[idx] {
@@ -97,13 +93,17 @@ fn get_tile_glyph(idx: usize, map: &Map) -> (FontCharType, RGB, RGB) {
         TileType::Wall => {
             let x = idx as i32 % map.width;
             let y = idx as i32 / map.width;
-            glyph = wall_glyph(&*map, x, y);
+            glyph = wall_glyph(map, x, y);
             fg = RGB::from_f32(0.0, 1.0, 0.0);
         }
         TileType::DownStairs => {
             glyph = rltk::to_cp437('>');
             fg = RGB::from_f32(0.0, 1.0, 1.0);
         }
+        TileType::UpStairs => {
+            glyph = rltk::to_cp437('<');
+            fg = RGB::from_f32(0.0, 1.0, 1.0);
+        }
         TileType::Bridge => {
             glyph = rltk::to_cp437(':');
             fg = RGB::named(rltk::CHOCOLATE);
@@ -138,7 +138,7 @@ fn get_tile_glyph(idx: usize, map: &Map) -> (FontCharType, RGB, RGB) {
 }
 
 fn wall_glyph(map: &Map, x: i32, y: i32) -> FontCharType {
-    if x < 1 || x > map.width - 2 || y < 1 || y > map.height - 2 as i32 {
+    if x < 1 || x > map.width - 2 || y < 1 || y > map.height - 2_i32 {
         return 35;
     }
     let mut mask: u8 = 0;
@@ -197,14 +197,12 @@ pub fn render_debug_map(map: &Map, ctx: &mut Rltk) {
     let map_width = map.width - 1;
     let map_height = map.height - 1;
 
-    let mut y = 0;
-    for ty in min_y..max_y {
-        let mut x = 0;
-        for tx in min_x..max_x {
+    for (y, ty) in (0..).zip(min_y..max_y) {
+        for (x, tx) in (0..).zip(min_x..max_x) {
             if tx > 0 && tx < map_width && ty > 0 && ty < map_height {
                 let idx = map.xy_idx(tx, ty);
                 if map.revealed_tiles[idx] {
-                    let (glyph, fg, bg) = get_tile_glyph(idx, &*map);
+                    let (glyph, fg, bg) = get_tile_glyph(idx, map);
                     ctx.set(x, y, fg, bg, glyph);
                 }
             } else if SHOW_BOUNDS {
@@ -216,8 +214,6 @@ pub fn render_debug_map(map: &Map, ctx: &mut Rltk) {
                     rltk::to_cp437('·'),
                 );
             }
-            x += 1;
         }
-        y += 1;
     }
 }
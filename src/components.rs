@@ -1,50 +1,108 @@
-use super::map::Map;
+use super::map::{DungeonMaster, Map};
+use super::GameLog;
 use rltk::RGB;
 use serde::{Deserialize, Serialize};
 use specs::error::NoError;
 use specs::{prelude::*, saveload::ConvertSaveload, saveload::Marker, saveload::SimpleMarker};
 use specs_derive::*;
 
+/// Every component the game uses, in one place. `saved_components!` and
+/// `transient_components!` pass their list of types to another macro, after its own
+/// arguments and a `;`, so registration, saving and loading all work from the same lists.
+///
+/// Saved components are written to save files and read back. Transient ones are never
+/// saved, because they only last for a turn or are rebuilt after a load: turn markers,
+/// the intents systems act on within the turn, and particles.
+macro_rules! saved_components {
+    ($callback:ident ! ( $($args:tt)* )) => {
+        $callback!(
+            $($args)*;
+            SerializationHelper,
+            Position,
+            OtherLevelPosition,
+            Renderable,
+            Player,
+            Viewshed,
+            Monster,
+            Name,
+            BlocksTile,
+            CombatStats,
+            LastDamage,
+            Item,
+            Potion,
+            InBackpack,
+            Consumable,
+            ProvidesHealing,
+            Ranged,
+            InflictsDamage,
+            MagicMapper,
+            AreaOfEffect,
+            Confusion,
+            Equippable,
+            Equipped,
+            MeleePowerBonus,
+            DefenseBonus,
+            Hidden,
+            EntryTrigger,
+            SingleActivation,
+            BlocksVisibility,
+            Door,
+            Energy,
+            SpeedModifier,
+            LootTable,
+            Faction,
+            MeleeAi,
+            RangedAi,
+            CowardlyAi,
+            GuardAi,
+            BystanderAi,
+            VendorAi,
+            CarrionEaterAi,
+            Corpse,
+            Grudge
+        )
+    };
+}
+
+macro_rules! transient_components {
+    ($callback:ident ! ( $($args:tt)* )) => {
+        $callback!(
+            $($args)*;
+            MyTurn,
+            WantsToMelee,
+            WantsToPickupItem,
+            WantsToUseItem,
+            WantsToRemoveItem,
+            WantsToDropItem,
+            EntityMoved,
+            ParticleLifetime
+        )
+    };
+}
+
+pub(crate) use saved_components;
+
+macro_rules! register_individually {
+    ($ecs:expr; $( $type:ty ),*) => {
+        $( $ecs.register::<$type>(); )*
+    };
+}
+
+macro_rules! component_names {
+    (; $( $type:ty ),*) => {
+        &[ $( stringify!($type) ),* ]
+    };
+}
+
+/// The names of the components written to save files, as they are keyed there.
+pub const SAVED_COMPONENTS: &[&str] = saved_components!(component_names!());
+/// The names of the components deliberately left out of save files.
+pub const TRANSIENT_COMPONENTS: &[&str] = transient_components!(component_names!());
+
 pub fn register(ecs: &mut World) {
-    // Ser/Deser
     ecs.register::<SimpleMarker<SerializeMe>>();
-    ecs.register::<SerializationHelper>();
-    // Game Components
-    ecs.register::<Position>();
-    ecs.register::<Renderable>();
-    ecs.register::<Player>();
-    ecs.register::<Viewshed>();
-    ecs.register::<Monster>();
-    ecs.register::<Name>();
-    ecs.register::<BlocksTile>();
-    ecs.register::<CombatStats>();
-    ecs.register::<WantsToMelee>();
-    ecs.register::<SufferDamage>();
-    ecs.register::<Item>();
-    ecs.register::<Potion>();
-    ecs.register::<WantsToPickupItem>();
-    ecs.register::<InBackpack>();
-    ecs.register::<WantsToUseItem>();
-    ecs.register::<WantsToRemoveItem>();
-    ecs.register::<WantsToDropItem>();
-    ecs.register::<Consumable>();
-    ecs.register::<ProvidesHealing>();
-    ecs.register::<Ranged>();
-    ecs.register::<InflictsDamage>();
-    ecs.register::<MagicMapper>();
-    ecs.register::<AreaOfEffect>();
-    ecs.register::<Confusion>();
-    ecs.register::<Equippable>();
-    ecs.register::<Equipped>();
-    ecs.register::<MeleePowerBonus>();
-    ecs.register::<DefenseBonus>();
-    ecs.register::<Hidden>();
-    ecs.register::<EntryTrigger>();
-    ecs.register::<EntityMoved>();
-    ecs.register::<SingleActivation>();
-    ecs.register::<ParticleLifetime>();
-    ecs.register::<BlocksVisibility>();
-    ecs.register::<Door>();
+    saved_components!(register_individually!(ecs));
+    transient_components!(register_individually!(ecs));
 }
 
 // ********************************************************************************
@@ -55,6 +113,11 @@ pub struct SerializeMe;
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct SerializationHelper {
     pub map: Map,
+    pub seed: u64,
+    pub rng: rltk::RandomNumberGenerator,
+    pub turn: i32,
+    pub log: GameLog,
+    pub dungeon: DungeonMaster,
 }
 
 // ********************************************************************************
@@ -67,6 +130,15 @@ pub struct Position {
     pub y: i32,
 }
 
+/// Where an entity is on a level the player has left. It takes the place of `Position`
+/// until the player returns, so the entity is left alone in the meantime.
+#[derive(Component, ConvertSaveload, Clone, Copy)]
+pub struct OtherLevelPosition {
+    pub x: i32,
+    pub y: i32,
+    pub depth: i32,
+}
+
 #[derive(Component, ConvertSaveload)]
 pub struct Renderable {
     pub glyph: rltk::FontCharType,
@@ -122,24 +194,6 @@ pub struct WantsToRemoveItem {
     pub item: Entity,
 }
 
-#[derive(Component, Debug, ConvertSaveload)]
-pub struct SufferDamage {
-    pub amount: Vec<i32>,
-}
-
-impl SufferDamage {
-    pub fn new_damage(store: &mut WriteStorage<SufferDamage>, victim: Entity, amount: i32) {
-        if let Some(suffering) = store.get_mut(victim) {
-            suffering.amount.push(amount);
-        } else {
-            let dmg = SufferDamage {
-                amount: vec![amount],
-            };
-            store.insert(victim, dmg).expect("Unable to insert damage");
-        }
-    }
-}
-
 #[derive(Component, Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Item {}
 
@@ -148,6 +202,14 @@ pub struct Potion {
     pub heal_amount: i32,
 }
 
+/// What last hurt an entity, kept so a death can be explained afterwards.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct LastDamage {
+    /// The name of whatever dealt the damage, or `None` if nothing did.
+    pub source: Option<String>,
+    pub amount: i32,
+}
+
 #[derive(Component, Debug, Clone, ConvertSaveload)]
 pub struct InBackpack {
     pub owner: Entity,
@@ -244,3 +306,79 @@ pub struct SingleActivation {}
 pub struct ParticleLifetime {
     pub lifetime_ms: f32,
 }
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Energy {
+    pub current: i32,
+    pub speed: i32,
+}
+
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct MyTurn {}
+
+/// Scales an entity's speed for a number of its turns: above 100 percent hastes, below slows.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct SpeedModifier {
+    pub percent: i32,
+    pub duration: i32,
+}
+
+/// The raws loot table rolled for what this entity drops when it dies.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct LootTable {
+    pub table: String,
+}
+
+/// Which side an entity is on; the raws say how each faction treats the others.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Faction {
+    pub name: String,
+}
+
+/// Chases down whatever its faction attacks and fights it hand to hand.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct MeleeAi {}
+
+/// Shoots at what it attacks from up to `range` tiles away, backing off whenever its target
+/// comes closer than `min_distance`.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct RangedAi {
+    pub range: i32,
+    pub damage: i32,
+    pub min_distance: i32,
+}
+
+/// Fights like a melee mob until its hit points drop below `flee_below` percent, then runs
+/// from everything it would have fought.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct CowardlyAi {
+    pub flee_below: i32,
+}
+
+/// Holds its ground, attacking only what comes next to it.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct GuardAi {}
+
+/// Wanders about harmlessly, keeping clear of anyone its faction would fight or fears.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct BystanderAi {}
+
+/// Minds its stall: never moves and never fights.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct VendorAi {}
+
+/// Goes for any remains it can see and eats them, fighting like a melee mob otherwise.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct CarrionEaterAi {}
+
+/// The remains of a dead mob, left where it fell until the game clock reaches `rots_at`.
+#[derive(Component, Debug, Serialize, Deserialize, Clone)]
+pub struct Corpse {
+    pub rots_at: i32,
+}
+
+/// Whoever last hurt this entity. It fights them back, whatever its faction makes of them.
+#[derive(Component, Debug, Clone, ConvertSaveload)]
+pub struct Grudge {
+    pub against: Entity,
+}
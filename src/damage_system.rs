@@ -1,34 +1,29 @@
+use crate::raws::{get_item_drop, spawn_named_item, SpawnType, RAWS};
 use crate::RunState;
+use rltk::{RandomNumberGenerator, RGB};
 use specs::prelude::*;
+use specs::saveload::{MarkedBuilder, SimpleMarker};
 
-use super::{CombatStats, GameLog, Name, Player, SufferDamage};
+use super::{
+    CombatStats, Corpse, GameClock, GameLog, Grudge, LogCategory, LootTable, Map, Name, Player,
+    Position, Renderable, SerializeMe,
+};
 
-pub struct DamageSystem {}
-
-impl<'a> System<'a> for DamageSystem {
-    type SystemData = (
-        WriteStorage<'a, CombatStats>,
-        WriteStorage<'a, SufferDamage>,
-    );
-
-    fn run(&mut self, data: Self::SystemData) {
-        let (mut stats, mut damage) = data;
-
-        for (mut stats, damage) in (&mut stats, &damage).join() {
-            stats.hp -= damage.amount.iter().sum::<i32>();
-        }
-
-        damage.clear();
-    }
-}
+/// How many turns a corpse lies around before it rots away.
+pub const CORPSE_LIFETIME: i32 = 200;
 
 pub fn delete_the_dead(ecs: &mut World) {
     let mut dead: Vec<Entity> = Vec::new();
+    let mut drops: Vec<(String, Position)> = Vec::new();
+    let mut corpses: Vec<(String, RGB, Position)> = Vec::new();
     // Using a scope to make the borrow checker happy
     {
         let combat_stats = ecs.read_storage::<CombatStats>();
+        let positions = ecs.read_storage::<Position>();
+        let loot_tables = ecs.read_storage::<LootTable>();
         let players = ecs.read_storage::<Player>();
         let names = ecs.read_storage::<Name>();
+        let renderables = ecs.read_storage::<Renderable>();
         let entities = ecs.entities();
         let mut log = ecs.write_resource::<GameLog>();
         for (entity, stats) in (&entities, &combat_stats).join() {
@@ -38,7 +33,22 @@ pub fn delete_the_dead(ecs: &mut World) {
                     None => {
                         let victim_name = names.get(entity);
                         if let Some(victim_name) = victim_name {
-                            log.entries.push(format!("{} has died.", &victim_name.name));
+                            log.entry(LogCategory::Combat)
+                                .name(&victim_name.name, renderables.get(entity))
+                                .append(" has died.")
+                                .log();
+                        }
+                        if let (Some(pos), Some(name), Some(render)) = (
+                            positions.get(entity),
+                            names.get(entity),
+                            renderables.get(entity),
+                        ) {
+                            corpses.push((name.name.clone(), render.fg, *pos));
+                        }
+                        if let (Some(pos), Some(loot)) =
+                            (positions.get(entity), loot_tables.get(entity))
+                        {
+                            drops.push((loot.table.clone(), *pos));
                         }
                         dead.push(entity)
                     }
@@ -54,4 +64,70 @@ pub fn delete_the_dead(ecs: &mut World) {
     for victim in dead {
         ecs.delete_entity(victim).expect("Unable to delete");
     }
+    rot_corpses(ecs);
+    forget_grudges(ecs);
+
+    // The dead leave their remains, and drop their loot, where they fell
+    let rots_at = ecs.fetch::<GameClock>().turn + CORPSE_LIFETIME;
+    for (name, fg, pos) in corpses {
+        ecs.create_entity()
+            .with(pos)
+            .with(Name {
+                name: format!("{} corpse", name),
+            })
+            .with(Renderable {
+                glyph: rltk::to_cp437('%'),
+                fg,
+                bg: RGB::named(rltk::BLACK),
+                render_order: 3,
+            })
+            .with(Corpse { rots_at })
+            .marked::<SimpleMarker<SerializeMe>>()
+            .build();
+    }
+
+    let depth = ecs.fetch::<Map>().depth;
+    for (table, pos) in drops {
+        let drop = get_item_drop(
+            &RAWS.lock().unwrap(),
+            &mut ecs.write_resource::<RandomNumberGenerator>(),
+            &table,
+            depth,
+        );
+        if let Some(item) = drop {
+            spawn_named_item(
+                &RAWS.lock().unwrap(),
+                ecs.create_entity().marked::<SimpleMarker<SerializeMe>>(),
+                &item,
+                SpawnType::AtPosition { x: pos.x, y: pos.y },
+            );
+        }
+    }
+}
+
+/// Removes every corpse whose time is up, including those on levels the player has left, so
+/// they don't pile up in the save.
+fn rot_corpses(ecs: &mut World) {
+    let turn = ecs.fetch::<GameClock>().turn;
+    let rotten: Vec<Entity> = (&ecs.entities(), &ecs.read_storage::<Corpse>())
+        .join()
+        .filter(|(_, corpse)| corpse.rots_at <= turn)
+        .map(|(entity, _)| entity)
+        .collect();
+    ecs.delete_entities(&rotten)
+        .expect("Unable to delete rotten corpses");
+}
+
+/// Drops grudges held against the dead, which would leave nothing for a save to refer to.
+fn forget_grudges(ecs: &mut World) {
+    let entities = ecs.entities();
+    let mut grudges = ecs.write_storage::<Grudge>();
+    let settled: Vec<Entity> = (&entities, &grudges)
+        .join()
+        .filter(|(_, grudge)| !entities.is_alive(grudge.against))
+        .map(|(entity, _)| entity)
+        .collect();
+    for entity in settled {
+        grudges.remove(entity);
+    }
 }
@@ -0,0 +1,128 @@
+use super::{
+    ai::{
+        BystanderAI, CarrionEaterAI, ConfusedAI, CowardlyAI, GuardAI, MeleeAI, RangedAI, VendorAI,
+    },
+    effects::EffectSystem,
+    initiative_system::InitiativeSystem,
+    map_indexing_system::MapIndexingSystem,
+    melee_combat_system::MeleeCombatSystem,
+    particle_system::ParticleSpawnSystem,
+    visibility_system::VisibilitySystem,
+    InventorySystem, ItemDropSystem, ItemRemoveSystem, ItemUseSystem, TriggerSystem,
+};
+use specs::prelude::*;
+
+/// A system as it was registered with the dispatcher, kept so the schedule can be inspected.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct RegisteredSystem {
+    pub name: &'static str,
+    pub dependencies: Vec<&'static str>,
+}
+
+/// Collects systems along with their dependencies before building the dispatcher.
+/// Systems that don't depend on each other and share no writable storage may run in parallel.
+pub struct SystemsBuilder {
+    builder: DispatcherBuilder<'static, 'static>,
+    systems: Vec<RegisteredSystem>,
+}
+
+impl Default for SystemsBuilder {
+    fn default() -> Self {
+        SystemsBuilder::new()
+    }
+}
+
+impl SystemsBuilder {
+    pub fn new() -> SystemsBuilder {
+        SystemsBuilder {
+            builder: DispatcherBuilder::new(),
+            systems: Vec::new(),
+        }
+    }
+
+    pub fn with<S>(mut self, system: S, name: &'static str, dependencies: &[&'static str]) -> Self
+    where
+        S: for<'a> System<'a> + Send + 'static,
+    {
+        self.builder.add(system, name, dependencies);
+        self.systems.push(RegisteredSystem {
+            name,
+            dependencies: dependencies.to_vec(),
+        });
+        self
+    }
+
+    pub fn build(self, ecs: &mut World) -> Systems {
+        let mut dispatcher = self.builder.build();
+        dispatcher.setup(ecs);
+        Systems {
+            dispatcher,
+            systems: self.systems,
+        }
+    }
+}
+
+/// The game's systems, built once and run every turn.
+pub struct Systems {
+    dispatcher: Dispatcher<'static, 'static>,
+    systems: Vec<RegisteredSystem>,
+}
+
+impl Systems {
+    pub fn run(&mut self, ecs: &mut World) {
+        self.dispatcher.dispatch(ecs);
+        ecs.maintain();
+    }
+
+    /// The registered systems, in registration order. Every system appears after its dependencies.
+    pub fn registered(&self) -> &[RegisteredSystem] {
+        &self.systems
+    }
+}
+
+/// The systems that run monsters' turns, one for each behaviour, which everything acting on
+/// what monsters decided waits for.
+pub const AI_SYSTEMS: &[&str] = &[
+    "melee_ai",
+    "ranged_ai",
+    "cowardly_ai",
+    "guard_ai",
+    "bystander_ai",
+    "vendor_ai",
+    "carrion_eater_ai",
+];
+
+pub fn game_systems() -> SystemsBuilder {
+    SystemsBuilder::new()
+        .with(InitiativeSystem {}, "initiative", &[])
+        .with(VisibilitySystem {}, "visibility", &[])
+        .with(ConfusedAI {}, "confused_ai", &["initiative", "visibility"])
+        .with(MeleeAI {}, "melee_ai", &["confused_ai"])
+        .with(RangedAI {}, "ranged_ai", &["confused_ai"])
+        .with(CowardlyAI {}, "cowardly_ai", &["confused_ai"])
+        .with(GuardAI {}, "guard_ai", &["confused_ai"])
+        .with(BystanderAI {}, "bystander_ai", &["confused_ai"])
+        .with(VendorAI {}, "vendor_ai", &["confused_ai"])
+        .with(CarrionEaterAI {}, "carrion_eater_ai", &["confused_ai"])
+        .with(TriggerSystem {}, "triggers", AI_SYSTEMS)
+        .with(MapIndexingSystem {}, "map_indexing", &["triggers"])
+        .with(MeleeCombatSystem {}, "melee_combat", AI_SYSTEMS)
+        .with(InventorySystem {}, "pickup_items", &["melee_combat"])
+        .with(
+            ItemUseSystem {},
+            "use_items",
+            &["map_indexing", "pickup_items"],
+        )
+        .with(ItemDropSystem {}, "drop_items", &["use_items"])
+        .with(ItemRemoveSystem {}, "remove_items", &["use_items"])
+        .with(
+            EffectSystem {},
+            "effects",
+            &["triggers", "melee_combat", "use_items"],
+        )
+        .with(
+            ParticleSpawnSystem {},
+            "particles",
+            &["confused_ai", "effects"],
+        )
+}
@@ -0,0 +1,371 @@
+use super::{
+    CombatStats, Confusion, Energy, GameLog, Grudge, InflictsDamage, LastDamage, LogCategory,
+    MagicMapper, Map, Name, ParticleBuilder, Position, ProvidesHealing, Renderable, RunState,
+    SpeedModifier,
+};
+use rltk::RGB;
+use specs::prelude::*;
+use std::collections::VecDeque;
+
+pub enum EffectType {
+    Damage {
+        amount: i32,
+    },
+    Healing {
+        amount: i32,
+    },
+    Confusion {
+        turns: i32,
+    },
+    SpeedModifier {
+        percent: i32,
+        duration: i32,
+    },
+    MagicMapping,
+    Particle {
+        glyph: rltk::FontCharType,
+        fg: RGB,
+        bg: RGB,
+        lifetime: f32,
+    },
+    /// Applies every effect component of a used item.
+    ItemUse {
+        item: Entity,
+    },
+    /// Applies every effect component of a trap or other entry trigger.
+    TriggerFire {
+        trigger: Entity,
+    },
+}
+
+#[derive(Clone)]
+pub enum Targets {
+    Single { target: Entity },
+    Tile { tile_idx: usize },
+    Tiles { tiles: Vec<usize> },
+}
+
+pub struct Effect {
+    pub creator: Option<Entity>,
+    pub effect_type: EffectType,
+    pub targets: Targets,
+}
+
+/// Effects waiting to be applied. Systems queue effects here instead of changing stats
+/// themselves, and the `EffectSystem` applies them all in one place.
+pub struct EffectQueue {
+    effects: VecDeque<Effect>,
+}
+
+impl Default for EffectQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EffectQueue {
+    pub fn new() -> EffectQueue {
+        EffectQueue {
+            effects: VecDeque::new(),
+        }
+    }
+
+    pub fn add(&mut self, creator: Option<Entity>, effect_type: EffectType, targets: Targets) {
+        self.effects.push_back(Effect {
+            creator,
+            effect_type,
+            targets,
+        });
+    }
+}
+
+pub struct EffectSystem {}
+
+impl<'a> System<'a> for EffectSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        ReadExpect<'a, Map>,
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, RunState>,
+        WriteExpect<'a, GameLog>,
+        WriteExpect<'a, ParticleBuilder>,
+        WriteExpect<'a, EffectQueue>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Renderable>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Energy>,
+        WriteStorage<'a, CombatStats>,
+        WriteStorage<'a, Confusion>,
+        WriteStorage<'a, SpeedModifier>,
+        ReadStorage<'a, InflictsDamage>,
+        ReadStorage<'a, ProvidesHealing>,
+        ReadStorage<'a, MagicMapper>,
+        WriteStorage<'a, LastDamage>,
+        WriteStorage<'a, Grudge>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            map,
+            player_entity,
+            mut runstate,
+            mut gamelog,
+            mut particle_builder,
+            mut queue,
+            names,
+            renderables,
+            positions,
+            energy,
+            mut combat_stats,
+            mut confusion,
+            mut speed_modifiers,
+            inflicts_damage,
+            healing,
+            magic_mapper,
+            mut last_damage,
+            mut grudges,
+        ) = data;
+
+        while let Some(effect) = queue.effects.pop_front() {
+            match effect.effect_type {
+                EffectType::ItemUse { item: source }
+                | EffectType::TriggerFire { trigger: source } => {
+                    // Expand the source into the individual effects it carries
+                    if let Some(damage) = inflicts_damage.get(source) {
+                        queue.add(
+                            effect.creator,
+                            EffectType::Damage {
+                                amount: damage.damage,
+                            },
+                            effect.targets.clone(),
+                        );
+                    }
+                    if let Some(heal) = healing.get(source) {
+                        queue.add(
+                            effect.creator,
+                            EffectType::Healing {
+                                amount: heal.heal_amount,
+                            },
+                            effect.targets.clone(),
+                        );
+                    }
+                    if let Some(confuse) = confusion.get(source) {
+                        queue.add(
+                            effect.creator,
+                            EffectType::Confusion {
+                                turns: confuse.duration,
+                            },
+                            effect.targets.clone(),
+                        );
+                    }
+                    if let Some(modifier) = speed_modifiers.get(source) {
+                        queue.add(
+                            effect.creator,
+                            EffectType::SpeedModifier {
+                                percent: modifier.percent,
+                                duration: modifier.duration,
+                            },
+                            effect.targets.clone(),
+                        );
+                    }
+                    if magic_mapper.get(source).is_some() {
+                        queue.add(
+                            effect.creator,
+                            EffectType::MagicMapping,
+                            effect.targets.clone(),
+                        );
+                    }
+                }
+                EffectType::Particle {
+                    glyph,
+                    fg,
+                    bg,
+                    lifetime,
+                } => {
+                    for (x, y) in target_tiles(&effect.targets, &map, &positions) {
+                        particle_builder.request(x, y, fg, bg, glyph, lifetime);
+                    }
+                }
+                EffectType::MagicMapping => {
+                    *runstate = RunState::MagicMapReveal { row: 0 };
+                    if effect.creator == Some(*player_entity) {
+                        gamelog
+                            .entry(LogCategory::Item)
+                            .append("The map is revealed to you!")
+                            .log();
+                    }
+                }
+                EffectType::Damage { amount } => {
+                    for target in target_entities(&effect.targets, &map) {
+                        if let Some(stats) = combat_stats.get_mut(target) {
+                            stats.hp -= amount;
+                            if let Some(attacker) = effect.creator.filter(|c| *c != target) {
+                                grudges
+                                    .insert(target, Grudge { against: attacker })
+                                    .expect("Unable to record grudge");
+                            }
+                            last_damage
+                                .insert(
+                                    target,
+                                    LastDamage {
+                                        source: effect
+                                            .creator
+                                            .and_then(|c| names.get(c))
+                                            .map(|n| n.name.clone()),
+                                        amount,
+                                    },
+                                )
+                                .expect("Unable to record damage");
+                            let target_name = name_of(&names, target);
+                            let target_render = renderables.get(target);
+                            match effect.creator.and_then(|c| names.get(c).map(|n| (c, n))) {
+                                Some((creator, creator_name)) => gamelog
+                                    .entry(LogCategory::Combat)
+                                    .name(&creator_name.name, renderables.get(creator))
+                                    .append(" hits ")
+                                    .name(&target_name, target_render)
+                                    .append(", for ")
+                                    .damage(amount)
+                                    .append(" hp.")
+                                    .log(),
+                                None => gamelog
+                                    .entry(LogCategory::Combat)
+                                    .name(&target_name, target_render)
+                                    .append(" takes ")
+                                    .damage(amount)
+                                    .append(" damage.")
+                                    .log(),
+                            }
+                            particle_at(
+                                &mut particle_builder,
+                                &positions,
+                                target,
+                                RGB::named(rltk::ORANGE),
+                                rltk::to_cp437('‼'),
+                            );
+                        }
+                    }
+                }
+                EffectType::Healing { amount } => {
+                    for target in target_entities(&effect.targets, &map) {
+                        if let Some(stats) = combat_stats.get_mut(target) {
+                            stats.hp = i32::min(stats.max_hp, stats.hp + amount);
+                            gamelog
+                                .entry(LogCategory::Item)
+                                .name(&name_of(&names, target), renderables.get(target))
+                                .append(" recovers ")
+                                .healing(amount)
+                                .append(" hp.")
+                                .log();
+                            particle_at(
+                                &mut particle_builder,
+                                &positions,
+                                target,
+                                RGB::named(rltk::GREEN),
+                                rltk::to_cp437('♥'),
+                            );
+                        }
+                    }
+                }
+                EffectType::Confusion { turns } => {
+                    for target in target_entities(&effect.targets, &map) {
+                        if combat_stats.get(target).is_some() {
+                            confusion
+                                .insert(target, Confusion { duration: turns })
+                                .expect("Unable to apply confusion");
+                            gamelog
+                                .entry(LogCategory::Combat)
+                                .name(&name_of(&names, target), renderables.get(target))
+                                .append(" is ")
+                                .colored(RGB::named(rltk::MAGENTA), "confused")
+                                .append(".")
+                                .log();
+                            particle_at(
+                                &mut particle_builder,
+                                &positions,
+                                target,
+                                RGB::named(rltk::MAGENTA),
+                                rltk::to_cp437('?'),
+                            );
+                        }
+                    }
+                }
+                EffectType::SpeedModifier { percent, duration } => {
+                    for target in target_entities(&effect.targets, &map) {
+                        if energy.get(target).is_some() {
+                            speed_modifiers
+                                .insert(target, SpeedModifier { percent, duration })
+                                .expect("Unable to apply speed modifier");
+                            gamelog
+                                .entry(LogCategory::Combat)
+                                .name(&name_of(&names, target), renderables.get(target))
+                                .append(" ")
+                                .colored(
+                                    RGB::named(rltk::YELLOW),
+                                    if percent > 100 {
+                                        "speeds up"
+                                    } else {
+                                        "slows down"
+                                    },
+                                )
+                                .append(".")
+                                .log();
+                            particle_at(
+                                &mut particle_builder,
+                                &positions,
+                                target,
+                                RGB::named(rltk::YELLOW),
+                                rltk::to_cp437('»'),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn target_entities(targets: &Targets, map: &Map) -> Vec<Entity> {
+    match targets {
+        Targets::Single { target } => vec![*target],
+        Targets::Tile { tile_idx } => map.tile_content[*tile_idx].clone(),
+        Targets::Tiles { tiles } => tiles
+            .iter()
+            .flat_map(|idx| map.tile_content[*idx].iter().cloned())
+            .collect(),
+    }
+}
+
+fn target_tiles(
+    targets: &Targets,
+    map: &Map,
+    positions: &ReadStorage<Position>,
+) -> Vec<(i32, i32)> {
+    let tile = |idx: &usize| (*idx as i32 % map.width, *idx as i32 / map.width);
+    match targets {
+        Targets::Single { target } => positions
+            .get(*target)
+            .map(|pos| vec![(pos.x, pos.y)])
+            .unwrap_or_default(),
+        Targets::Tile { tile_idx } => vec![tile(tile_idx)],
+        Targets::Tiles { tiles } => tiles.iter().map(tile).collect(),
+    }
+}
+
+fn name_of(names: &ReadStorage<Name>, entity: Entity) -> String {
+    names
+        .get(entity)
+        .map_or("Something".to_string(), |name| name.name.clone())
+}
+
+fn particle_at(
+    particle_builder: &mut ParticleBuilder,
+    positions: &ReadStorage<Position>,
+    entity: Entity,
+    fg: RGB,
+    glyph: rltk::FontCharType,
+) {
+    if let Some(pos) = positions.get(entity) {
+        particle_builder.request(pos.x, pos.y, fg, RGB::named(rltk::BLACK), glyph, 200.0);
+    }
+}
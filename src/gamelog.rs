@@ -1,3 +1,115 @@
+use super::Renderable;
+use rltk::RGB;
+use serde::{Deserialize, Serialize};
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum LogCategory {
+    Combat,
+    Item,
+    System,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LogFragment {
+    pub color: RGB,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub turn: i32,
+    pub category: LogCategory,
+    pub fragments: Vec<LogFragment>,
+}
+
+impl LogEntry {
+    /// The entry without its colours.
+    pub fn text(&self) -> String {
+        self.fragments.iter().map(|f| f.text.as_str()).collect()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GameLog {
-    pub entries: Vec<String>,
+    pub entries: Vec<LogEntry>,
+    /// The turn stamped on new entries, kept in step with the `GameClock` by the initiative system.
+    pub turn: i32,
+}
+
+impl Default for GameLog {
+    fn default() -> Self {
+        GameLog::new()
+    }
+}
+
+impl GameLog {
+    pub fn new() -> GameLog {
+        GameLog {
+            entries: Vec::new(),
+            turn: 0,
+        }
+    }
+
+    /// Entries in `category` (or any category, if `None`) whose text contains `search`,
+    /// ignoring case. Oldest first.
+    pub fn filtered(&self, category: Option<LogCategory>, search: &str) -> Vec<&LogEntry> {
+        let search = search.to_lowercase();
+        self.entries
+            .iter()
+            .filter(|entry| category.map_or(true, |c| entry.category == c))
+            .filter(|entry| search.is_empty() || entry.text().to_lowercase().contains(&search))
+            .collect()
+    }
+
+    /// Starts a new entry. Nothing is logged until `LogBuilder::log` is called.
+    pub fn entry(&mut self, category: LogCategory) -> LogBuilder<'_> {
+        LogBuilder {
+            log: self,
+            category,
+            fragments: Vec::new(),
+        }
+    }
+}
+
+pub struct LogBuilder<'a> {
+    log: &'a mut GameLog,
+    category: LogCategory,
+    fragments: Vec<LogFragment>,
+}
+
+impl<'a> LogBuilder<'a> {
+    pub fn append<T: ToString>(self, text: T) -> Self {
+        self.colored(RGB::named(rltk::WHITE), text)
+    }
+
+    pub fn colored<T: ToString>(mut self, color: RGB, text: T) -> Self {
+        self.fragments.push(LogFragment {
+            color,
+            text: text.to_string(),
+        });
+        self
+    }
+
+    /// An entity's name, drawn in the entity's own colour when it has one.
+    pub fn name(self, name: &str, renderable: Option<&Renderable>) -> Self {
+        let color = renderable.map_or(RGB::named(rltk::WHITE), |r| r.fg);
+        self.colored(color, name)
+    }
+
+    pub fn damage(self, amount: i32) -> Self {
+        self.colored(RGB::named(rltk::RED), amount)
+    }
+
+    pub fn healing(self, amount: i32) -> Self {
+        self.colored(RGB::named(rltk::GREEN), amount)
+    }
+
+    pub fn log(self) {
+        let turn = self.log.turn;
+        self.log.entries.push(LogEntry {
+            turn,
+            category: self.category,
+            fragments: self.fragments,
+        });
+    }
 }
@@ -1,10 +1,12 @@
-use crate::saveload_system::does_save_exist;
 use rltk::{Point, Rltk, VirtualKeyCode, RGB};
+use rogue::keymap::{key_name, Action, Keymap};
+use rogue::saveload_system::{does_save_exist, SaveSlot};
 use specs::prelude::*;
 
-use super::{
-    get_screen_bounds, CombatStats, Equipped, GameLog, Hidden, InBackpack, Map, Name, Player,
-    Position, RunState, State, Viewshed,
+use super::camera::get_screen_bounds;
+use rogue::{
+    CombatStats, Equipped, GameLog, GameSeed, Hidden, InBackpack, LogCategory, LogEntry,
+    MainMenuSelection, Map, Name, Player, Position, RunState, State, Viewshed,
 };
 
 #[derive(PartialEq, Copy, Clone)]
@@ -14,13 +16,6 @@ pub enum ItemMenuResult {
     Selected,
 }
 
-#[derive(PartialEq, Copy, Clone)]
-pub enum MainMenuSelection {
-    NewGame,
-    LoadGame,
-    Quit,
-}
-
 #[derive(PartialEq, Copy, Clone)]
 pub enum MainMenuResult {
     NoSeleciton { selected: MainMenuSelection },
@@ -44,7 +39,7 @@ pub fn show_inventory(gs: &mut State, ctx: &mut Rltk) -> (ItemMenuResult, Option
         .filter(|item| item.0.owner == *player_entity);
     let count = inventory.count();
 
-    let mut y = (25 - (count / 2)) as i32;
+    let y = (25 - (count / 2)) as i32;
     ctx.draw_box(
         15,
         y - 2,
@@ -69,11 +64,12 @@ pub fn show_inventory(gs: &mut State, ctx: &mut Rltk) -> (ItemMenuResult, Option
     );
 
     let mut equippable: Vec<Entity> = Vec::new();
-    let mut j = 0;
-    for (entity, _pack, name) in (&entities, &backpack, &names)
+    for (j, (entity, _pack, name)) in (&entities, &backpack, &names)
         .join()
         .filter(|item| item.1.owner == *player_entity)
+        .enumerate()
     {
+        let y = y + j as i32;
         ctx.set(
             17,
             y,
@@ -96,10 +92,8 @@ pub fn show_inventory(gs: &mut State, ctx: &mut Rltk) -> (ItemMenuResult, Option
             rltk::to_cp437(')'),
         );
 
-        ctx.print(21, y, &name.name.to_string());
+        ctx.print(21, y, name.name.to_string());
         equippable.push(entity);
-        y += 1;
-        j += 1;
     }
 
     match ctx.key {
@@ -120,6 +114,21 @@ pub fn show_inventory(gs: &mut State, ctx: &mut Rltk) -> (ItemMenuResult, Option
     }
 }
 
+/// Prints a log entry fragment by fragment, each in its own colour.
+fn print_log_entry(ctx: &mut Rltk, x: i32, y: i32, entry: &LogEntry) {
+    let mut x = x;
+    for fragment in entry.fragments.iter() {
+        ctx.print_color(
+            x,
+            y,
+            fragment.color,
+            RGB::named(rltk::BLACK),
+            &fragment.text,
+        );
+        x += fragment.text.chars().count() as i32;
+    }
+}
+
 pub fn draw_ui(ecs: &World, ctx: &mut Rltk) {
     ctx.draw_box(
         0,
@@ -154,12 +163,8 @@ pub fn draw_ui(ecs: &World, ctx: &mut Rltk) {
 
         let log = ecs.fetch::<GameLog>();
 
-        let mut y = 44;
-        for s in log.entries.iter().rev() {
-            if y < 49 {
-                ctx.print(2, y, s);
-            }
-            y += 1;
+        for (y, entry) in (44..49).zip(log.entries.iter().rev()) {
+            print_log_entry(ctx, 2, y, entry);
         }
     }
     let map = ecs.fetch::<Map>();
@@ -172,14 +177,23 @@ pub fn draw_ui(ecs: &World, ctx: &mut Rltk) {
         &depth,
     );
 
+    let seed = format!(" Seed: {} ", ecs.fetch::<GameSeed>().seed);
+    ctx.print_color(
+        78 - seed.len() as i32,
+        49,
+        RGB::named(rltk::GREY),
+        RGB::named(rltk::BLACK),
+        &seed,
+    );
+
     let mouse_pos = ctx.mouse_pos();
     ctx.set_bg(mouse_pos.0, mouse_pos.1, RGB::named(rltk::MAGENTA));
 
-    draw_tooltips(&ecs, ctx);
+    draw_tooltips(ecs, ctx);
 }
 
-pub fn main_menu(gs: &mut State, ctx: &mut Rltk) -> MainMenuResult {
-    let save_exists = does_save_exist();
+pub fn main_menu(gs: &mut State, ctx: &mut Rltk, error: Option<&str>) -> MainMenuResult {
+    let save_exists = does_save_exist(&gs.ecs);
     let runstate = gs.ecs.fetch::<RunState>();
 
     ctx.print_color_centered(
@@ -238,6 +252,10 @@ pub fn main_menu(gs: &mut State, ctx: &mut Rltk) -> MainMenuResult {
             ctx.print_color_centered(26, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), "Quit");
         }
 
+        if let Some(error) = error {
+            print_error(ctx, 30, error);
+        }
+
         match ctx.key {
             None => {
                 return MainMenuResult::NoSeleciton {
@@ -297,7 +315,7 @@ pub fn main_menu(gs: &mut State, ctx: &mut Rltk) -> MainMenuResult {
     }
 }
 
-pub fn game_over(ctx: &mut Rltk) -> GameOverResult {
+pub fn game_over(ctx: &mut Rltk, morgue_path: Option<&str>) -> GameOverResult {
     ctx.print_color_centered(
         15,
         RGB::named(rltk::YELLOW),
@@ -310,6 +328,14 @@ pub fn game_over(ctx: &mut Rltk) -> GameOverResult {
         RGB::named(rltk::BLACK),
         "You fall to the darkness deep within.",
     );
+    if let Some(path) = morgue_path {
+        ctx.print_color_centered(
+            18,
+            RGB::named(rltk::GREY),
+            RGB::named(rltk::BLACK),
+            format!("Your story was recorded in {}", path),
+        );
+    }
 
     ctx.print_color_centered(
         20,
@@ -324,6 +350,69 @@ pub fn game_over(ctx: &mut Rltk) -> GameOverResult {
     }
 }
 
+#[derive(PartialEq, Copy, Clone)]
+pub enum RecoverCheckpointResult {
+    NoResponse,
+    Recover,
+    Discard,
+}
+
+/// Asks whether to pick up from the checkpoint left by a game that didn't shut down cleanly.
+pub fn recover_checkpoint(ctx: &mut Rltk, checkpoint: &SaveSlot) -> RecoverCheckpointResult {
+    ctx.print_color_centered(
+        15,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        "The last game didn't shut down cleanly.",
+    );
+    match &checkpoint.metadata {
+        Ok(metadata) => {
+            ctx.print_color_centered(
+                17,
+                RGB::named(rltk::WHITE),
+                RGB::named(rltk::BLACK),
+                format!(
+                    "{}: {}, depth {}, turn {}",
+                    checkpoint.name, metadata.character_name, metadata.depth, metadata.turn
+                ),
+            );
+            ctx.print_color_centered(
+                18,
+                RGB::named(rltk::GREY),
+                RGB::named(rltk::BLACK),
+                format!("Checkpoint saved {}", format_timestamp(metadata.timestamp)),
+            );
+        }
+        Err(e) => print_error(ctx, 17, &format!("Its checkpoint can't be loaded: {}", e)),
+    }
+
+    if checkpoint.metadata.is_err() {
+        ctx.print_color_centered(
+            21,
+            RGB::named(rltk::MAGENTA),
+            RGB::named(rltk::BLACK),
+            "Press any key to start a new game.",
+        );
+        return match ctx.key {
+            None => RecoverCheckpointResult::NoResponse,
+            Some(_) => RecoverCheckpointResult::Discard,
+        };
+    }
+
+    ctx.print_color_centered(
+        21,
+        RGB::named(rltk::MAGENTA),
+        RGB::named(rltk::BLACK),
+        "Recover from the checkpoint? (y/n)",
+    );
+
+    match ctx.key {
+        Some(VirtualKeyCode::Y) => RecoverCheckpointResult::Recover,
+        Some(VirtualKeyCode::N) | Some(VirtualKeyCode::Escape) => RecoverCheckpointResult::Discard,
+        _ => RecoverCheckpointResult::NoResponse,
+    }
+}
+
 pub fn draw_tooltips(ecs: &World, ctx: &mut Rltk) {
     let (min_x, _max_x, min_y, _max_y) = get_screen_bounds(ecs, ctx);
     let map = ecs.fetch::<Map>();
@@ -367,8 +456,7 @@ pub fn draw_tooltips(ecs: &World, ctx: &mut Rltk) {
         if mouse_pos.0 > 40 {
             let arrow_pos = Point::new(mouse_pos.0 - 2, mouse_pos.1);
             let left_x = mouse_pos.0 - width;
-            let mut y = mouse_pos.1;
-            for s in tooltip.iter() {
+            for (y, s) in (mouse_pos.1..).zip(tooltip.iter()) {
                 ctx.print_color(
                     left_x,
                     y,
@@ -383,23 +471,21 @@ pub fn draw_tooltips(ecs: &World, ctx: &mut Rltk) {
                         y,
                         RGB::named(rltk::WHITE),
                         RGB::named(rltk::GREY),
-                        &" ".to_string(),
+                        " ".to_string(),
                     );
                 }
-                y += 1;
             }
             ctx.print_color(
                 arrow_pos.x,
                 arrow_pos.y,
                 RGB::named(rltk::WHITE),
                 RGB::named(rltk::GREY),
-                &"->".to_string(),
+                "->".to_string(),
             );
         } else {
             let arrow_pos = Point::new(mouse_pos.0 + 1, mouse_pos.1);
             let left_x = mouse_pos.0 + 3;
-            let mut y = mouse_pos.1;
-            for s in tooltip.iter() {
+            for (y, s) in (mouse_pos.1..).zip(tooltip.iter()) {
                 ctx.print_color(
                     left_x + 1,
                     y,
@@ -414,17 +500,16 @@ pub fn draw_tooltips(ecs: &World, ctx: &mut Rltk) {
                         y,
                         RGB::named(rltk::WHITE),
                         RGB::named(rltk::GREY),
-                        &" ".to_string(),
+                        " ".to_string(),
                     );
                 }
-                y += 1;
             }
             ctx.print_color(
                 arrow_pos.x,
                 arrow_pos.y,
                 RGB::named(rltk::WHITE),
                 RGB::named(rltk::GREY),
-                &"<-".to_string(),
+                "<-".to_string(),
             );
         }
     }
@@ -441,7 +526,7 @@ pub fn drop_item_menu(gs: &mut State, ctx: &mut Rltk) -> (ItemMenuResult, Option
         .filter(|item| item.0.owner == *player_entity);
     let count = inventory.count();
 
-    let mut y = (25 - (count / 2)) as i32;
+    let y = (25 - (count / 2)) as i32;
     ctx.draw_box(
         15,
         y - 2,
@@ -466,11 +551,12 @@ pub fn drop_item_menu(gs: &mut State, ctx: &mut Rltk) -> (ItemMenuResult, Option
     );
 
     let mut equippable: Vec<Entity> = Vec::new();
-    let mut j = 0;
-    for (entity, _pack, name) in (&entities, &backpack, &names)
+    for (j, (entity, _pack, name)) in (&entities, &backpack, &names)
         .join()
         .filter(|item| item.1.owner == *player_entity)
+        .enumerate()
     {
+        let y = y + j as i32;
         ctx.set(
             17,
             y,
@@ -493,10 +579,8 @@ pub fn drop_item_menu(gs: &mut State, ctx: &mut Rltk) -> (ItemMenuResult, Option
             rltk::to_cp437(')'),
         );
 
-        ctx.print(21, y, &name.name.to_string());
+        ctx.print(21, y, name.name.to_string());
         equippable.push(entity);
-        y += 1;
-        j += 1;
     }
 
     match ctx.key {
@@ -606,7 +690,7 @@ pub fn remove_item_menu(gs: &mut State, ctx: &mut Rltk) -> (ItemMenuResult, Opti
         .filter(|item| item.0.owner == *player_entity);
     let count = inventory.count();
 
-    let mut y = (25 - (count / 2)) as i32;
+    let y = (25 - (count / 2)) as i32;
     ctx.draw_box(
         15,
         y - 2,
@@ -631,11 +715,12 @@ pub fn remove_item_menu(gs: &mut State, ctx: &mut Rltk) -> (ItemMenuResult, Opti
     );
 
     let mut equippable: Vec<Entity> = Vec::new();
-    let mut j = 0;
-    for (entity, _pack, name) in (&entities, &backpack, &names)
+    for (j, (entity, _pack, name)) in (&entities, &backpack, &names)
         .join()
         .filter(|item| item.1.owner == *player_entity)
+        .enumerate()
     {
+        let y = y + j as i32;
         ctx.set(
             17,
             y,
@@ -658,10 +743,8 @@ pub fn remove_item_menu(gs: &mut State, ctx: &mut Rltk) -> (ItemMenuResult, Opti
             rltk::to_cp437(')'),
         );
 
-        ctx.print(21, y, &name.name.to_string());
+        ctx.print(21, y, name.name.to_string());
         equippable.push(entity);
-        y += 1;
-        j += 1;
     }
 
     match ctx.key {
@@ -681,3 +764,554 @@ pub fn remove_item_menu(gs: &mut State, ctx: &mut Rltk) -> (ItemMenuResult, Opti
         },
     }
 }
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum LogViewerResult {
+    NoResponse,
+    Close,
+}
+
+/// What the message history screen is showing: how far it is scrolled back from the newest
+/// entry, and which entries it is filtered down to.
+#[derive(Default)]
+pub struct LogViewer {
+    pub scroll: usize,
+    pub category: Option<LogCategory>,
+    pub search: String,
+    pub typing: bool,
+}
+
+const LOG_VIEW_TOP: i32 = 3;
+const LOG_VIEW_ROWS: usize = 43;
+
+pub fn show_log(ecs: &World, ctx: &mut Rltk, viewer: &mut LogViewer) -> LogViewerResult {
+    let log = ecs.fetch::<GameLog>();
+    let entries = log.filtered(viewer.category, &viewer.search);
+    let max_scroll = entries.len().saturating_sub(LOG_VIEW_ROWS);
+    viewer.scroll = usize::min(viewer.scroll, max_scroll);
+
+    ctx.cls();
+    ctx.draw_box(
+        0,
+        0,
+        79,
+        49,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+    );
+    let category_name = match viewer.category {
+        None => "All",
+        Some(LogCategory::Combat) => "Combat",
+        Some(LogCategory::Item) => "Item",
+        Some(LogCategory::System) => "System",
+    };
+    ctx.print_color(
+        2,
+        0,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        format!(" Message History - {} ", category_name),
+    );
+    let search_color = if viewer.typing {
+        RGB::named(rltk::MAGENTA)
+    } else {
+        RGB::named(rltk::GREY)
+    };
+    ctx.print_color(
+        2,
+        1,
+        search_color,
+        RGB::named(rltk::BLACK),
+        format!(
+            "Search: {}{}",
+            viewer.search,
+            if viewer.typing { "_" } else { "" }
+        ),
+    );
+
+    let last = entries.len() - viewer.scroll;
+    let first = last.saturating_sub(LOG_VIEW_ROWS);
+    for (y, entry) in (LOG_VIEW_TOP..).zip(entries[first..last].iter()) {
+        let stamp = format!("{:>5} ", entry.turn);
+        ctx.print_color(
+            1,
+            y,
+            RGB::named(rltk::GREY),
+            RGB::named(rltk::BLACK),
+            &stamp,
+        );
+        print_log_entry(ctx, 1 + stamp.len() as i32, y, entry);
+    }
+
+    ctx.print_color(
+        2,
+        49,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        " Up/Down/PgUp/PgDn: scroll  Tab: category  /: search  Escape: close ",
+    );
+
+    std::mem::drop(entries);
+    std::mem::drop(log);
+
+    let key = match ctx.key {
+        None => return LogViewerResult::NoResponse,
+        Some(key) => key,
+    };
+
+    if viewer.typing {
+        match key {
+            VirtualKeyCode::Return | VirtualKeyCode::Escape => viewer.typing = false,
+            VirtualKeyCode::Back => {
+                viewer.search.pop();
+            }
+            VirtualKeyCode::Space => viewer.search.push(' '),
+            _ => {
+                if let Some(c) = key_to_char(key) {
+                    viewer.search.push(c);
+                }
+            }
+        }
+        viewer.scroll = 0;
+        return LogViewerResult::NoResponse;
+    }
+
+    match key {
+        VirtualKeyCode::Escape => return LogViewerResult::Close,
+        VirtualKeyCode::Up => viewer.scroll = usize::min(viewer.scroll + 1, max_scroll),
+        VirtualKeyCode::Down => viewer.scroll = viewer.scroll.saturating_sub(1),
+        VirtualKeyCode::PageUp => {
+            viewer.scroll = usize::min(viewer.scroll + LOG_VIEW_ROWS, max_scroll)
+        }
+        VirtualKeyCode::PageDown => viewer.scroll = viewer.scroll.saturating_sub(LOG_VIEW_ROWS),
+        VirtualKeyCode::Home => viewer.scroll = max_scroll,
+        VirtualKeyCode::End => viewer.scroll = 0,
+        VirtualKeyCode::Tab => {
+            viewer.category = match viewer.category {
+                None => Some(LogCategory::Combat),
+                Some(LogCategory::Combat) => Some(LogCategory::Item),
+                Some(LogCategory::Item) => Some(LogCategory::System),
+                Some(LogCategory::System) => None,
+            };
+            viewer.scroll = 0;
+        }
+        VirtualKeyCode::Slash => viewer.typing = true,
+        _ => {}
+    }
+    LogViewerResult::NoResponse
+}
+
+fn key_to_char(key: VirtualKeyCode) -> Option<char> {
+    let letter = rltk::letter_to_option(key);
+    if letter >= 0 {
+        return Some((b'a' + letter as u8) as char);
+    }
+    match key {
+        VirtualKeyCode::Key0 => Some('0'),
+        VirtualKeyCode::Key1 => Some('1'),
+        VirtualKeyCode::Key2 => Some('2'),
+        VirtualKeyCode::Key3 => Some('3'),
+        VirtualKeyCode::Key4 => Some('4'),
+        VirtualKeyCode::Key5 => Some('5'),
+        VirtualKeyCode::Key6 => Some('6'),
+        VirtualKeyCode::Key7 => Some('7'),
+        VirtualKeyCode::Key8 => Some('8'),
+        VirtualKeyCode::Key9 => Some('9'),
+        _ => None,
+    }
+}
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum KeybindingsResult {
+    NoResponse,
+    Close,
+}
+
+/// The rebind screen's cursor, and whether it is waiting for a key to bind.
+#[derive(Default)]
+pub struct KeybindingEditor {
+    pub selection: usize,
+    pub capturing: bool,
+    /// Why the last change was refused, shown until the next key press.
+    pub notice: Option<&'static str>,
+}
+
+pub fn show_keybindings(
+    ctx: &mut Rltk,
+    keymap: &mut Keymap,
+    editor: &mut KeybindingEditor,
+) -> KeybindingsResult {
+    ctx.cls();
+    ctx.draw_box(
+        0,
+        0,
+        79,
+        49,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+    );
+    ctx.print_color(
+        2,
+        0,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        " Keybindings ",
+    );
+
+    for (i, action) in Action::ALL.iter().enumerate() {
+        let y = 3 + i as i32;
+        let fg = if i == editor.selection {
+            RGB::named(rltk::MAGENTA)
+        } else {
+            RGB::named(rltk::WHITE)
+        };
+        ctx.print_color(2, y, fg, RGB::named(rltk::BLACK), action.description());
+        let keys = if i == editor.selection && editor.capturing {
+            "Press a key...".to_string()
+        } else {
+            keymap
+                .keys(*action)
+                .iter()
+                .filter_map(|key| key_name(*key))
+                .collect::<Vec<&str>>()
+                .join(", ")
+        };
+        ctx.print_color(26, y, fg, RGB::named(rltk::BLACK), keys);
+    }
+
+    if let Some(notice) = editor.notice {
+        ctx.print_color(
+            2,
+            4 + Action::ALL.len() as i32,
+            RGB::named(rltk::RED),
+            RGB::named(rltk::BLACK),
+            notice,
+        );
+    }
+
+    ctx.print_color(
+        2,
+        49,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        " Up/Down: select  Enter: add key  Delete: clear  Escape: save and close ",
+    );
+
+    let key = match ctx.key {
+        None => return KeybindingsResult::NoResponse,
+        Some(key) => key,
+    };
+    let action = Action::ALL[editor.selection];
+    editor.notice = None;
+
+    if editor.capturing {
+        editor.capturing = false;
+        if key != VirtualKeyCode::Escape && key_name(key).is_some() && !keymap.rebind(action, key) {
+            editor.notice = Some("That is the only key for an action that must keep one.");
+        }
+        return KeybindingsResult::NoResponse;
+    }
+
+    match key {
+        VirtualKeyCode::Escape => return KeybindingsResult::Close,
+        VirtualKeyCode::Up => {
+            editor.selection = (editor.selection + Action::ALL.len() - 1) % Action::ALL.len()
+        }
+        VirtualKeyCode::Down => editor.selection = (editor.selection + 1) % Action::ALL.len(),
+        VirtualKeyCode::Return => editor.capturing = true,
+        VirtualKeyCode::Delete | VirtualKeyCode::Back if !keymap.unbind(action) => {
+            editor.notice = Some("This action must keep a key; bind another one instead.")
+        }
+        _ => {}
+    }
+    KeybindingsResult::NoResponse
+}
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum NameSaveResult {
+    NoResponse,
+    Save,
+    Cancel,
+}
+
+/// The longest name a save can be given from the prompt.
+const MAX_SAVE_NAME: usize = 32;
+
+/// Asks what to call a game's first save, editing `name` as the player types.
+pub fn name_save(ctx: &mut Rltk, name: &mut String, error: Option<&str>) -> NameSaveResult {
+    ctx.draw_box(
+        15,
+        18,
+        50,
+        6,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+    );
+    ctx.print_color(
+        18,
+        18,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        " Save Game ",
+    );
+    ctx.print_color(
+        18,
+        20,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+        "Name:",
+    );
+    ctx.print_color(
+        24,
+        20,
+        RGB::named(rltk::MAGENTA),
+        RGB::named(rltk::BLACK),
+        format!("{}_", name),
+    );
+    ctx.print_color(
+        18,
+        24,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        " Enter: save  Escape: cancel ",
+    );
+    if let Some(error) = error {
+        print_error(ctx, 26, error);
+    }
+
+    match ctx.key {
+        None => {}
+        Some(VirtualKeyCode::Return) => return NameSaveResult::Save,
+        Some(VirtualKeyCode::Escape) => return NameSaveResult::Cancel,
+        Some(VirtualKeyCode::Back) => {
+            name.pop();
+        }
+        Some(_) if name.len() >= MAX_SAVE_NAME => {}
+        Some(VirtualKeyCode::Space) => name.push(' '),
+        Some(VirtualKeyCode::Minus) => name.push('-'),
+        Some(key) => {
+            if let Some(c) = key_to_char(key) {
+                name.push(c);
+            }
+        }
+    }
+    NameSaveResult::NoResponse
+}
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum SaveSlotMenuResult {
+    NoResponse {
+        selection: usize,
+        confirm_delete: bool,
+    },
+    Load {
+        selection: usize,
+    },
+    Delete {
+        selection: usize,
+    },
+    Cancel,
+}
+
+/// How many save slots the load menu shows at once; longer lists are split into pages.
+const SLOTS_PER_PAGE: usize = 14;
+
+pub fn save_slot_menu(
+    ctx: &mut Rltk,
+    slots: &[SaveSlot],
+    selection: usize,
+    confirm_delete: bool,
+    error: Option<&str>,
+) -> SaveSlotMenuResult {
+    let selection = usize::min(selection, slots.len().saturating_sub(1));
+    let page_count = slots.len().div_ceil(SLOTS_PER_PAGE);
+    let page = selection / SLOTS_PER_PAGE;
+    let first = page * SLOTS_PER_PAGE;
+    let shown = &slots[first..usize::min(first + SLOTS_PER_PAGE, slots.len())];
+
+    ctx.draw_box(
+        5,
+        8,
+        70,
+        4 + shown.len() as i32 * 2,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+    );
+    ctx.print_color(
+        8,
+        8,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        " Load Game ",
+    );
+    if page_count > 1 {
+        ctx.print_color(
+            60,
+            8,
+            RGB::named(rltk::YELLOW),
+            RGB::named(rltk::BLACK),
+            format!(" Page {}/{} ", page + 1, page_count),
+        );
+    }
+
+    for (i, slot) in shown.iter().enumerate() {
+        let y = 10 + i as i32 * 2;
+        let fg = if first + i == selection {
+            RGB::named(rltk::MAGENTA)
+        } else {
+            RGB::named(rltk::WHITE)
+        };
+        ctx.print_color(8, y, fg, RGB::named(rltk::BLACK), &slot.name);
+        match &slot.metadata {
+            Ok(metadata) => {
+                ctx.print_color(
+                    10,
+                    y + 1,
+                    RGB::named(rltk::GREY),
+                    RGB::named(rltk::BLACK),
+                    format!(
+                        "{}, depth {}, turn {}, seed {}",
+                        metadata.character_name, metadata.depth, metadata.turn, metadata.seed
+                    ),
+                );
+                ctx.print_color(
+                    56,
+                    y,
+                    RGB::named(rltk::GREY),
+                    RGB::named(rltk::BLACK),
+                    format_timestamp(metadata.timestamp),
+                );
+            }
+            Err(e) => {
+                // The error can quote the save file, so cut it by characters, not bytes
+                let message: String = format!("Can't be loaded: {}", e).chars().take(64).collect();
+                ctx.print_color(
+                    10,
+                    y + 1,
+                    RGB::named(rltk::RED),
+                    RGB::named(rltk::BLACK),
+                    message,
+                );
+            }
+        }
+    }
+
+    let footer_y = 12 + shown.len() as i32 * 2;
+    if confirm_delete {
+        ctx.print_color(
+            8,
+            footer_y,
+            RGB::named(rltk::YELLOW),
+            RGB::named(rltk::BLACK),
+            format!(" Delete {}? (y/n) ", slots[selection].name),
+        );
+    } else if page_count > 1 {
+        ctx.print_color(
+            8,
+            footer_y,
+            RGB::named(rltk::YELLOW),
+            RGB::named(rltk::BLACK),
+            " Enter: load  D: delete  PgUp/PgDn: page  Escape: back ",
+        );
+    } else {
+        ctx.print_color(
+            8,
+            footer_y,
+            RGB::named(rltk::YELLOW),
+            RGB::named(rltk::BLACK),
+            " Enter: load  D: delete  Escape: back ",
+        );
+    }
+    if let Some(error) = error {
+        print_error(ctx, footer_y + 2, error);
+    }
+
+    let key = match ctx.key {
+        None => {
+            return SaveSlotMenuResult::NoResponse {
+                selection,
+                confirm_delete,
+            }
+        }
+        Some(key) => key,
+    };
+
+    if confirm_delete {
+        if key == VirtualKeyCode::Y {
+            return SaveSlotMenuResult::Delete { selection };
+        }
+        return SaveSlotMenuResult::NoResponse {
+            selection,
+            confirm_delete: false,
+        };
+    }
+
+    let selection = match key {
+        VirtualKeyCode::Escape => return SaveSlotMenuResult::Cancel,
+        VirtualKeyCode::Return => return SaveSlotMenuResult::Load { selection },
+        VirtualKeyCode::D | VirtualKeyCode::Delete => {
+            return SaveSlotMenuResult::NoResponse {
+                selection,
+                confirm_delete: true,
+            }
+        }
+        VirtualKeyCode::Up => (selection + slots.len() - 1) % slots.len(),
+        VirtualKeyCode::Down => (selection + 1) % slots.len(),
+        VirtualKeyCode::PageUp => selection.saturating_sub(SLOTS_PER_PAGE),
+        VirtualKeyCode::PageDown => usize::min(selection + SLOTS_PER_PAGE, slots.len() - 1),
+        _ => selection,
+    };
+    SaveSlotMenuResult::NoResponse {
+        selection,
+        confirm_delete: false,
+    }
+}
+
+/// Prints `message` in red, centred and wrapped to fit the screen, starting at row `y`.
+fn print_error(ctx: &mut Rltk, y: i32, message: &str) {
+    let mut lines: Vec<String> = Vec::new();
+    for word in message.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.len() + 1 + word.len() <= 70 => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    for (i, line) in lines.iter().enumerate() {
+        ctx.print_color_centered(
+            y + i as i32,
+            RGB::named(rltk::RED),
+            RGB::named(rltk::BLACK),
+            line,
+        );
+    }
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time.
+fn format_timestamp(timestamp: u64) -> String {
+    if timestamp == 0 {
+        return "Unknown time".to_string();
+    }
+    // Days to a civil date, after Howard Hinnant's days_from_civil inverse
+    let days = (timestamp / 86400) as i64 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    let seconds = timestamp % 86400;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
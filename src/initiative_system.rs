@@ -0,0 +1,130 @@
+use super::{Energy, GameLog, LogCategory, MyTurn, Position, RunState, SpeedModifier};
+use specs::prelude::*;
+
+/// Energy an entity needs before it may act.
+pub const TURN_ENERGY: i32 = 100;
+/// Energy gained per tick by an unmodified creature; a normal creature acts every ten ticks.
+pub const NORMAL_SPEED: i32 = 10;
+
+pub const MOVE_COST: i32 = 100;
+pub const ATTACK_COST: i32 = 100;
+pub const WAIT_COST: i32 = 100;
+pub const USE_ITEM_COST: i32 = 100;
+pub const PICKUP_COST: i32 = 50;
+pub const DROP_COST: i32 = 50;
+pub const REMOVE_COST: i32 = 50;
+pub const EAT_COST: i32 = 100;
+
+/// Counts the turns the player has been given since the game began.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct GameClock {
+    pub turn: i32,
+}
+
+pub struct InitiativeSystem {}
+
+impl<'a> System<'a> for InitiativeSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, RunState>,
+        WriteExpect<'a, GameLog>,
+        WriteExpect<'a, GameClock>,
+        WriteStorage<'a, Energy>,
+        WriteStorage<'a, MyTurn>,
+        WriteStorage<'a, SpeedModifier>,
+        ReadStorage<'a, Position>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player_entity,
+            runstate,
+            mut gamelog,
+            mut clock,
+            mut energy,
+            mut turns,
+            mut speed_modifiers,
+            positions,
+        ) = data;
+
+        if *runstate != RunState::Ticking || turns.contains(*player_entity) {
+            return;
+        }
+
+        // Whoever acted last pass has already spent their energy. Anything still holding its
+        // turn had nothing to act for it, and waits, so it can't hold up everyone else
+        for (energy, _turn) in (&mut energy, &turns).join() {
+            energy.current -= WAIT_COST;
+        }
+        turns.clear();
+
+        if (&energy, &positions).join().next().is_none() {
+            return;
+        }
+
+        // Tick the clock forward until someone has enough energy to act. Only entities on the
+        // current level, which have a `Position`, take part
+        while !(&energy, &positions)
+            .join()
+            .any(|(e, _)| e.current >= TURN_ENERGY)
+        {
+            for (entity, energy, _) in (&entities, &mut energy, &positions).join() {
+                energy.current += effective_speed(energy.speed, speed_modifiers.get(entity));
+            }
+        }
+
+        let mut expired: Vec<Entity> = Vec::new();
+        for (entity, energy, _) in (&entities, &energy, &positions).join() {
+            if energy.current < TURN_ENERGY {
+                continue;
+            }
+            turns
+                .insert(entity, MyTurn {})
+                .expect("Unable to insert turn marker.");
+            if entity == *player_entity {
+                clock.turn += 1;
+                gamelog.turn = clock.turn;
+            }
+
+            if let Some(modifier) = speed_modifiers.get_mut(entity) {
+                modifier.duration -= 1;
+                if modifier.duration < 1 {
+                    expired.push(entity);
+                }
+            }
+        }
+
+        for entity in expired {
+            speed_modifiers.remove(entity);
+            if entity == *player_entity {
+                gamelog
+                    .entry(LogCategory::System)
+                    .append("You feel your pace return to normal.")
+                    .log();
+            }
+        }
+    }
+}
+
+pub fn effective_speed(speed: i32, modifier: Option<&SpeedModifier>) -> i32 {
+    let speed = match modifier {
+        None => speed,
+        Some(modifier) => speed * modifier.percent / 100,
+    };
+    i32::max(1, speed)
+}
+
+/// Takes the cost of an action out of an entity's energy and ends its turn.
+pub fn spend_energy(
+    energy: &mut WriteStorage<Energy>,
+    turns: &mut WriteStorage<MyTurn>,
+    entity: Entity,
+    cost: i32,
+) {
+    if let Some(energy) = energy.get_mut(entity) {
+        energy.current -= cost;
+    }
+    turns.remove(entity);
+}
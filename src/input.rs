@@ -0,0 +1,39 @@
+use rltk::Rltk;
+use rogue::keymap::{Action, Keymap};
+use rogue::{Command, RunState, State};
+
+pub fn player_input(gs: &mut State, ctx: &mut Rltk, keymap: &Keymap) -> RunState {
+    let action = match ctx.key.and_then(|key| keymap.action(key)) {
+        None => return RunState::AwaitingInput, // Nothing happened
+        Some(action) => action,
+    };
+    let command = match action {
+        Action::MoveLeft => move_by(-1, 0),
+        Action::MoveRight => move_by(1, 0),
+        Action::MoveUp => move_by(0, -1),
+        Action::MoveDown => move_by(0, 1),
+
+        // Diagonals
+        Action::MoveUpRight => move_by(1, -1),
+        Action::MoveUpLeft => move_by(-1, -1),
+        Action::MoveDownRight => move_by(1, 1),
+        Action::MoveDownLeft => move_by(-1, 1),
+
+        // Actions
+        Action::PickUp => Command::PickUp,
+        Action::Inventory => return RunState::ShowInventory,
+        Action::DropItem => return RunState::ShowDropItem,
+        Action::RemoveItem => return RunState::ShowRemoveItem,
+        Action::MessageLog => return RunState::ShowLog,
+        Action::Keybindings => return RunState::ShowKeybindings,
+        Action::SaveAndQuit => return RunState::SaveGame,
+        Action::Descend => Command::Descend,
+        Action::Ascend => Command::Ascend,
+        Action::Wait => Command::Wait,
+    };
+    gs.submit(command)
+}
+
+fn move_by(delta_x: i32, delta_y: i32) -> Command {
+    Command::Move { delta_x, delta_y }
+}
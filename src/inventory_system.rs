@@ -1,9 +1,9 @@
 use specs::prelude::*;
 
 use super::{
-    AreaOfEffect, CombatStats, Confusion, Consumable, Equippable, Equipped, GameLog, InBackpack,
-    InflictsDamage, MagicMapper, Map, Name, ParticleBuilder, Position, ProvidesHealing, RunState,
-    SufferDamage, WantsToDropItem, WantsToPickupItem, WantsToRemoveItem, WantsToUseItem,
+    effects::{EffectQueue, EffectType, Targets},
+    AreaOfEffect, Consumable, Equippable, Equipped, GameLog, InBackpack, LogCategory, Map, Name,
+    Position, Renderable, WantsToDropItem, WantsToPickupItem, WantsToRemoveItem, WantsToUseItem,
 };
 
 pub struct InventorySystem {}
@@ -16,12 +16,20 @@ impl<'a> System<'a> for InventorySystem {
         WriteStorage<'a, WantsToPickupItem>,
         WriteStorage<'a, Position>,
         ReadStorage<'a, Name>,
+        ReadStorage<'a, Renderable>,
         WriteStorage<'a, InBackpack>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (player_entity, mut gamelog, mut wants_pickup, mut positions, names, mut backpack) =
-            data;
+        let (
+            player_entity,
+            mut gamelog,
+            mut wants_pickup,
+            mut positions,
+            names,
+            renderables,
+            mut backpack,
+        ) = data;
 
         for pickup in wants_pickup.join() {
             positions.remove(pickup.item);
@@ -35,10 +43,15 @@ impl<'a> System<'a> for InventorySystem {
                 .expect("Unable to insert into backpack!");
 
             if pickup.collected_by == *player_entity {
-                gamelog.entries.push(format!(
-                    "You pick up the {}.",
-                    names.get(pickup.item).unwrap().name
-                ));
+                gamelog
+                    .entry(LogCategory::Item)
+                    .append("You pick up the ")
+                    .name(
+                        &names.get(pickup.item).unwrap().name,
+                        renderables.get(pickup.item),
+                    )
+                    .append(".")
+                    .log();
             }
         }
 
@@ -49,6 +62,7 @@ impl<'a> System<'a> for InventorySystem {
 pub struct ItemUseSystem {}
 
 impl<'a> System<'a> for ItemUseSystem {
+    #[allow(clippy::type_complexity)]
     type SystemData = (
         ReadExpect<'a, Entity>,
         WriteExpect<'a, GameLog>,
@@ -56,20 +70,13 @@ impl<'a> System<'a> for ItemUseSystem {
         Entities<'a>,
         WriteStorage<'a, WantsToUseItem>,
         ReadStorage<'a, Name>,
-        ReadStorage<'a, InflictsDamage>,
+        ReadStorage<'a, Renderable>,
         ReadStorage<'a, Consumable>,
-        WriteStorage<'a, CombatStats>,
-        ReadStorage<'a, ProvidesHealing>,
-        WriteStorage<'a, SufferDamage>,
         ReadStorage<'a, AreaOfEffect>,
-        WriteStorage<'a, Confusion>,
         WriteStorage<'a, InBackpack>,
         WriteStorage<'a, Equipped>,
         ReadStorage<'a, Equippable>,
-        ReadStorage<'a, MagicMapper>,
-        WriteExpect<'a, RunState>,
-        WriteExpect<'a, ParticleBuilder>,
-        ReadStorage<'a, Position>,
+        WriteExpect<'a, EffectQueue>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -80,73 +87,23 @@ impl<'a> System<'a> for ItemUseSystem {
             entities,
             mut wants_use,
             names,
-            inflicts_damage,
+            renderables,
             consumables,
-            mut combat_stats,
-            healing,
-            mut suffer_damage,
             aoe,
-            mut confused,
             mut backpack,
             mut equipped,
             equippable,
-            magic_mapper,
-            mut runstate,
-            mut particle_builder,
-            positions,
+            mut effects,
         ) = data;
 
         for (entity, useitem) in (&entities, &wants_use).join() {
-            let mut used_item = true;
-            // let consumable = consumables.get(useitem.item);
-
-            // Calculate item targets
-            let mut targets: Vec<Entity> = Vec::new();
-            match useitem.target {
-                None => targets.push(*player_entity),
-                Some(target) => {
-                    let area_effect = aoe.get(useitem.item);
-                    match area_effect {
-                        None => {
-                            // Single target
-                            let idx = map.xy_idx(target.x, target.y);
-                            for mob in map.tile_content[idx].iter() {
-                                targets.push(*mob);
-                            }
-                        }
-                        Some(area_effect) => {
-                            // AoE
-                            let mut affected_tiles =
-                                rltk::field_of_view(target, area_effect.radius, &*map);
-                            affected_tiles.retain(|p| {
-                                p.x > 0 && p.x < map.width - 1 && p.y > 0 && p.y < map.height - 1
-                            });
-                            for tile_idx in affected_tiles.iter() {
-                                let idx = map.xy_idx(tile_idx.x, tile_idx.y);
-                                for mob in map.tile_content[idx].iter() {
-                                    targets.push(*mob);
-                                }
-                                particle_builder.request(
-                                    tile_idx.x,
-                                    tile_idx.y,
-                                    rltk::RGB::named(rltk::ORANGE),
-                                    rltk::RGB::named(rltk::BLACK),
-                                    rltk::to_cp437('░'),
-                                    200.0,
-                                );
-                            }
-                        }
-                    }
-                }
-            }
-
-            // If equippable
+            // If equippable, wield it and replace whatever was in its slot
             let item_equippable = equippable.get(useitem.item);
             match item_equippable {
                 None => {}
                 Some(can_equip) => {
                     let target_slot = can_equip.slot;
-                    let target = targets[0];
+                    let target = entity;
 
                     // Remove any item in targets relevant slot
                     let mut to_unequip: Vec<Entity> = Vec::new();
@@ -158,8 +115,11 @@ impl<'a> System<'a> for ItemUseSystem {
                             to_unequip.push(item_entity);
                             if target == *player_entity {
                                 gamelog
-                                    .entries
-                                    .push(format!("You unequip the {}.", name.name));
+                                    .entry(LogCategory::Item)
+                                    .append("You unequip the ")
+                                    .name(&name.name, renderables.get(item_entity))
+                                    .append(".")
+                                    .log();
                             }
                         }
                     }
@@ -182,147 +142,77 @@ impl<'a> System<'a> for ItemUseSystem {
                         .expect("Unable to wield item.");
                     backpack.remove(useitem.item);
                     if target == *player_entity {
-                        gamelog.entries.push(format!(
-                            "You equip the {}.",
-                            names.get(useitem.item).unwrap().name
-                        ));
+                        gamelog
+                            .entry(LogCategory::Item)
+                            .append("You equip the ")
+                            .name(
+                                &names.get(useitem.item).unwrap().name,
+                                renderables.get(useitem.item),
+                            )
+                            .append(".")
+                            .log();
                     }
+                    continue;
                 }
             }
 
-            // If heals, provide healing
-            let item_heals = healing.get(useitem.item);
-            match item_heals {
-                None => {}
-                Some(healer) => {
-                    for target in targets.iter() {
-                        let stats = combat_stats.get_mut(*target);
-                        if let Some(stats) = stats {
-                            stats.hp = i32::min(stats.max_hp, stats.hp + healer.heal_amount);
-                            if entity == *player_entity {
-                                gamelog.entries.push(format!(
-                                    "You drink the {}, healing {} health.",
-                                    names.get(useitem.item).unwrap().name,
-                                    healer.heal_amount
-                                ));
-                            }
-                        }
-                        used_item = true;
-
-                        let pos = positions.get(*target);
-                        if let Some(pos) = pos {
-                            particle_builder.request(
-                                pos.x,
-                                pos.y,
-                                rltk::RGB::named(rltk::GREEN),
-                                rltk::RGB::named(rltk::BLACK),
-                                rltk::to_cp437('♥'),
-                                200.0,
+            // Calculate item targets
+            let targets = match useitem.target {
+                None => Targets::Single { target: entity },
+                Some(target) => {
+                    let area_effect = aoe.get(useitem.item);
+                    match area_effect {
+                        None => Targets::Tile {
+                            tile_idx: map.xy_idx(target.x, target.y),
+                        },
+                        Some(area_effect) => {
+                            let mut affected_tiles =
+                                rltk::field_of_view(target, area_effect.radius, &*map);
+                            affected_tiles.retain(|p| {
+                                p.x > 0 && p.x < map.width - 1 && p.y > 0 && p.y < map.height - 1
+                            });
+                            let tiles: Vec<usize> = affected_tiles
+                                .iter()
+                                .map(|p| map.xy_idx(p.x, p.y))
+                                .collect();
+                            effects.add(
+                                Some(entity),
+                                EffectType::Particle {
+                                    glyph: rltk::to_cp437('░'),
+                                    fg: rltk::RGB::named(rltk::ORANGE),
+                                    bg: rltk::RGB::named(rltk::BLACK),
+                                    lifetime: 200.0,
+                                },
+                                Targets::Tiles {
+                                    tiles: tiles.clone(),
+                                },
                             );
+                            Targets::Tiles { tiles }
                         }
                     }
                 }
-            }
-
-            // If inflicts damage, apply to target cell
-            let item_damages = inflicts_damage.get(useitem.item);
-            match item_damages {
-                None => {}
-                Some(damage) => {
-                    used_item = false;
-                    for mob in targets.iter() {
-                        SufferDamage::new_damage(&mut suffer_damage, *mob, damage.damage);
-                        if entity == *player_entity {
-                            let mob_name = names.get(*mob).unwrap();
-                            let item_name = names.get(useitem.item).unwrap();
-                            gamelog.entries.push(format!(
-                                "You use {} on {}, inflicting {} damage.",
-                                item_name.name, mob_name.name, damage.damage
-                            ));
-
-                            let pos = positions.get(*mob);
-
-                            if let Some(pos) = pos {
-                                particle_builder.request(
-                                    pos.x,
-                                    pos.y,
-                                    rltk::RGB::named(rltk::GREEN),
-                                    rltk::RGB::named(rltk::BLACK),
-                                    rltk::to_cp437('♥'),
-                                    200.0,
-                                );
-                            }
-                        }
-
-                        used_item = true;
-                    }
-                }
-            }
-
-            // If adds confusion, calculate
-            let mut add_confusion = Vec::new();
-            {
-                let causes_confusion = confused.get(useitem.item);
-                match causes_confusion {
-                    None => {}
-                    Some(confusion) => {
-                        used_item = false;
-                        for mob in targets.iter() {
-                            add_confusion.push((*mob, confusion.duration));
-                            if entity == *player_entity {
-                                let mob_name = names.get(*mob).unwrap();
-                                let item_name = names.get(useitem.item).unwrap();
-                                gamelog.entries.push(format!(
-                                    "You use {} on {}, confusing them.",
-                                    item_name.name, mob_name.name
-                                ));
-                            }
-                            used_item = true;
-
-                            let pos = positions.get(*mob);
-                            if let Some(pos) = pos {
-                                particle_builder.request(
-                                    pos.x,
-                                    pos.y,
-                                    rltk::RGB::named(rltk::GREEN),
-                                    rltk::RGB::named(rltk::BLACK),
-                                    rltk::to_cp437('♥'),
-                                    200.0,
-                                );
-                            }
-                        }
-                    }
-                }
-            }
-            for mob in add_confusion.iter() {
-                confused
-                    .insert(mob.0, Confusion { duration: mob.1 })
-                    .expect("Unable to apply confusion");
-            }
-
-            // If its a magic mapper
-            let is_mapper = magic_mapper.get(useitem.item);
-            match is_mapper {
-                None => {}
-                Some(_) => {
-                    used_item = true;
-                    gamelog
-                        .entries
-                        .push("The map is revealed to you!".to_string());
+            };
 
-                    *runstate = RunState::MagicMapReveal { row: 0 };
-                }
+            if entity == *player_entity {
+                gamelog
+                    .entry(LogCategory::Item)
+                    .append("You use the ")
+                    .name(
+                        &names.get(useitem.item).unwrap().name,
+                        renderables.get(useitem.item),
+                    )
+                    .append(".")
+                    .log();
             }
-
-            // Delete the item if successfully used
-            if used_item {
-                let consumable = consumables.get(useitem.item);
-                match consumable {
-                    None => {}
-                    Some(_) => {
-                        entities.delete(useitem.item).expect("Delete failed.");
-                    }
-                }
+            effects.add(
+                Some(entity),
+                EffectType::ItemUse { item: useitem.item },
+                targets,
+            );
+
+            // Consumables are used up
+            if consumables.get(useitem.item).is_some() {
+                entities.delete(useitem.item).expect("Delete failed.");
             }
         }
         wants_use.clear();
@@ -338,6 +228,7 @@ impl<'a> System<'a> for ItemDropSystem {
         Entities<'a>,
         WriteStorage<'a, WantsToDropItem>,
         ReadStorage<'a, Name>,
+        ReadStorage<'a, Renderable>,
         WriteStorage<'a, Position>,
         WriteStorage<'a, InBackpack>,
     );
@@ -349,6 +240,7 @@ impl<'a> System<'a> for ItemDropSystem {
             entities,
             mut wants_drop,
             names,
+            renderables,
             mut positions,
             mut backpack,
         ) = data;
@@ -372,10 +264,15 @@ impl<'a> System<'a> for ItemDropSystem {
             backpack.remove(to_drop.item);
 
             if entity == *player_entity {
-                gamelog.entries.push(format!(
-                    "You drop the {}.",
-                    names.get(to_drop.item).unwrap().name
-                ));
+                gamelog
+                    .entry(LogCategory::Item)
+                    .append("You drop the ")
+                    .name(
+                        &names.get(to_drop.item).unwrap().name,
+                        renderables.get(to_drop.item),
+                    )
+                    .append(".")
+                    .log();
             }
         }
         wants_drop.clear();
@@ -391,6 +288,7 @@ impl<'a> System<'a> for ItemRemoveSystem {
         WriteStorage<'a, Equipped>,
         WriteStorage<'a, InBackpack>,
         ReadStorage<'a, Name>,
+        ReadStorage<'a, Renderable>,
         ReadExpect<'a, Entity>,
         WriteExpect<'a, GameLog>,
     );
@@ -402,6 +300,7 @@ impl<'a> System<'a> for ItemRemoveSystem {
             mut equipped,
             mut backpack,
             names,
+            renderables,
             player_entity,
             mut gamelog,
         ) = data;
@@ -412,10 +311,15 @@ impl<'a> System<'a> for ItemRemoveSystem {
                 .insert(to_remove.item, InBackpack { owner: entity })
                 .expect("Unable to insert in backpack.");
             if entity == *player_entity {
-                gamelog.entries.push(format!(
-                    "You unequip the {}.",
-                    names.get(to_remove.item).unwrap().name
-                ));
+                gamelog
+                    .entry(LogCategory::Item)
+                    .append("You unequip the ")
+                    .name(
+                        &names.get(to_remove.item).unwrap().name,
+                        renderables.get(to_remove.item),
+                    )
+                    .append(".")
+                    .log();
             }
         }
         wants_remove.clear();
@@ -0,0 +1,322 @@
+use rltk::VirtualKeyCode;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Everything the player can bind a key to.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    MoveUpLeft,
+    MoveUpRight,
+    MoveDownLeft,
+    MoveDownRight,
+    Wait,
+    PickUp,
+    Descend,
+    Ascend,
+    Inventory,
+    DropItem,
+    RemoveItem,
+    MessageLog,
+    Keybindings,
+    SaveAndQuit,
+}
+
+impl Action {
+    pub const ALL: [Action; 18] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveUpLeft,
+        Action::MoveUpRight,
+        Action::MoveDownLeft,
+        Action::MoveDownRight,
+        Action::Wait,
+        Action::PickUp,
+        Action::Descend,
+        Action::Ascend,
+        Action::Inventory,
+        Action::DropItem,
+        Action::RemoveItem,
+        Action::MessageLog,
+        Action::Keybindings,
+        Action::SaveAndQuit,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::MoveUpLeft => "Move up-left",
+            Action::MoveUpRight => "Move up-right",
+            Action::MoveDownLeft => "Move down-left",
+            Action::MoveDownRight => "Move down-right",
+            Action::Wait => "Wait a turn",
+            Action::PickUp => "Pick up",
+            Action::Descend => "Take the stairs down",
+            Action::Ascend => "Take the stairs up",
+            Action::Inventory => "Use an item",
+            Action::DropItem => "Drop an item",
+            Action::RemoveItem => "Remove equipment",
+            Action::MessageLog => "Message history",
+            Action::Keybindings => "Keybindings",
+            Action::SaveAndQuit => "Save and quit",
+        }
+    }
+
+    /// Actions that must always keep a key: without one the rebind screen can't be reopened
+    /// to fix the binding, or the game can't be left.
+    pub fn is_essential(&self) -> bool {
+        matches!(self, Action::Keybindings | Action::SaveAndQuit)
+    }
+
+    fn default_keys(&self) -> Vec<VirtualKeyCode> {
+        use VirtualKeyCode::*;
+        match self {
+            Action::MoveLeft => vec![Left, Numpad4, H],
+            Action::MoveRight => vec![Right, Numpad6, L],
+            Action::MoveUp => vec![Up, Numpad8, K],
+            Action::MoveDown => vec![Down, Numpad2, J],
+            Action::MoveUpLeft => vec![Numpad7, Y],
+            Action::MoveUpRight => vec![Numpad9, U],
+            Action::MoveDownLeft => vec![Numpad1, B],
+            Action::MoveDownRight => vec![Numpad3, N],
+            Action::Wait => vec![Space, Numpad5],
+            Action::PickUp => vec![G],
+            Action::Descend => vec![Period],
+            Action::Ascend => vec![Comma],
+            Action::Inventory => vec![I],
+            Action::DropItem => vec![D],
+            Action::RemoveItem => vec![R],
+            Action::MessageLog => vec![M],
+            Action::Keybindings => vec![F1],
+            Action::SaveAndQuit => vec![Escape],
+        }
+    }
+}
+
+/// Maps keys to actions. Loaded from a JSON file of action names to key names; any action
+/// the file leaves out keeps its default keys.
+pub struct Keymap {
+    bindings: BTreeMap<Action, Vec<VirtualKeyCode>>,
+    lookup: HashMap<VirtualKeyCode, Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut keymap = Keymap {
+            bindings: BTreeMap::new(),
+            lookup: HashMap::new(),
+        };
+        for action in Action::ALL.iter() {
+            for key in action.default_keys() {
+                keymap.bind(*action, key);
+            }
+        }
+        keymap
+    }
+}
+
+impl Keymap {
+    /// Loads the keymap at `path`, falling back to the defaults if it is missing or unreadable.
+    pub fn load(path: &str) -> Keymap {
+        let mut keymap = Keymap::default();
+        if !Path::new(path).exists() {
+            return keymap;
+        }
+
+        let file: BTreeMap<Action, Vec<String>> = match fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| serde_json::from_str(&data).map_err(|e| e.to_string()))
+        {
+            Ok(file) => file,
+            Err(e) => {
+                println!(
+                    "WARNING: Unable to read {}, using default keys: {}",
+                    path, e
+                );
+                return keymap;
+            }
+        };
+
+        for (action, names) in file {
+            keymap.clear(action);
+            for name in names {
+                match key_from_name(&name) {
+                    Some(key) => keymap.bind(action, key),
+                    None => println!("WARNING: Unknown key {} bound to {:?}", name, action),
+                }
+            }
+        }
+
+        for action in Action::ALL.iter().filter(|a| a.is_essential()) {
+            if keymap.keys(*action).is_empty() {
+                println!(
+                    "WARNING: No key bound to {:?} in {}, using its default keys",
+                    action, path
+                );
+                for key in action.default_keys() {
+                    keymap.bind(*action, key);
+                }
+            }
+        }
+        keymap
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let file: BTreeMap<Action, Vec<&str>> = self
+            .bindings
+            .iter()
+            .map(|(action, keys)| (*action, keys.iter().filter_map(|k| key_name(*k)).collect()))
+            .collect();
+        let data = serde_json::to_string_pretty(&file)?;
+        fs::write(path, data)
+    }
+
+    pub fn action(&self, key: VirtualKeyCode) -> Option<Action> {
+        self.lookup.get(&key).copied()
+    }
+
+    pub fn keys(&self, action: Action) -> &[VirtualKeyCode] {
+        self.bindings
+            .get(&action)
+            .map_or(&[], |keys| keys.as_slice())
+    }
+
+    /// Binds `key` to `action` like `bind`, unless that would take the last key away from an
+    /// essential action. Returns whether the key was bound.
+    pub fn rebind(&mut self, action: Action, key: VirtualKeyCode) -> bool {
+        if let Some(previous) = self.action(key) {
+            if previous != action && previous.is_essential() && self.keys(previous).len() == 1 {
+                return false;
+            }
+        }
+        self.bind(action, key);
+        true
+    }
+
+    /// Clears `action` like `clear`, unless it is essential. Returns whether it was cleared.
+    pub fn unbind(&mut self, action: Action) -> bool {
+        if action.is_essential() {
+            return false;
+        }
+        self.clear(action);
+        true
+    }
+
+    /// Binds `key` to `action`, taking it away from whatever it was bound to before.
+    fn bind(&mut self, action: Action, key: VirtualKeyCode) {
+        if let Some(previous) = self.lookup.insert(key, action) {
+            if let Some(keys) = self.bindings.get_mut(&previous) {
+                keys.retain(|k| *k != key);
+            }
+        }
+        self.bindings.entry(action).or_default().push(key);
+    }
+
+    fn clear(&mut self, action: Action) {
+        for key in self.bindings.remove(&action).unwrap_or_default() {
+            self.lookup.remove(&key);
+        }
+    }
+}
+
+/// Keys that may appear in the keymap file, by the name used there.
+const KEY_NAMES: [(&str, VirtualKeyCode); 73] = [
+    ("A", VirtualKeyCode::A),
+    ("B", VirtualKeyCode::B),
+    ("C", VirtualKeyCode::C),
+    ("D", VirtualKeyCode::D),
+    ("E", VirtualKeyCode::E),
+    ("F", VirtualKeyCode::F),
+    ("G", VirtualKeyCode::G),
+    ("H", VirtualKeyCode::H),
+    ("I", VirtualKeyCode::I),
+    ("J", VirtualKeyCode::J),
+    ("K", VirtualKeyCode::K),
+    ("L", VirtualKeyCode::L),
+    ("M", VirtualKeyCode::M),
+    ("N", VirtualKeyCode::N),
+    ("O", VirtualKeyCode::O),
+    ("P", VirtualKeyCode::P),
+    ("Q", VirtualKeyCode::Q),
+    ("R", VirtualKeyCode::R),
+    ("S", VirtualKeyCode::S),
+    ("T", VirtualKeyCode::T),
+    ("U", VirtualKeyCode::U),
+    ("V", VirtualKeyCode::V),
+    ("W", VirtualKeyCode::W),
+    ("X", VirtualKeyCode::X),
+    ("Y", VirtualKeyCode::Y),
+    ("Z", VirtualKeyCode::Z),
+    ("Key0", VirtualKeyCode::Key0),
+    ("Key1", VirtualKeyCode::Key1),
+    ("Key2", VirtualKeyCode::Key2),
+    ("Key3", VirtualKeyCode::Key3),
+    ("Key4", VirtualKeyCode::Key4),
+    ("Key5", VirtualKeyCode::Key5),
+    ("Key6", VirtualKeyCode::Key6),
+    ("Key7", VirtualKeyCode::Key7),
+    ("Key8", VirtualKeyCode::Key8),
+    ("Key9", VirtualKeyCode::Key9),
+    ("Numpad0", VirtualKeyCode::Numpad0),
+    ("Numpad1", VirtualKeyCode::Numpad1),
+    ("Numpad2", VirtualKeyCode::Numpad2),
+    ("Numpad3", VirtualKeyCode::Numpad3),
+    ("Numpad4", VirtualKeyCode::Numpad4),
+    ("Numpad5", VirtualKeyCode::Numpad5),
+    ("Numpad6", VirtualKeyCode::Numpad6),
+    ("Numpad7", VirtualKeyCode::Numpad7),
+    ("Numpad8", VirtualKeyCode::Numpad8),
+    ("Numpad9", VirtualKeyCode::Numpad9),
+    ("Left", VirtualKeyCode::Left),
+    ("Right", VirtualKeyCode::Right),
+    ("Up", VirtualKeyCode::Up),
+    ("Down", VirtualKeyCode::Down),
+    ("Home", VirtualKeyCode::Home),
+    ("End", VirtualKeyCode::End),
+    ("PageUp", VirtualKeyCode::PageUp),
+    ("PageDown", VirtualKeyCode::PageDown),
+    ("Insert", VirtualKeyCode::Insert),
+    ("Delete", VirtualKeyCode::Delete),
+    ("Space", VirtualKeyCode::Space),
+    ("Return", VirtualKeyCode::Return),
+    ("Tab", VirtualKeyCode::Tab),
+    ("Escape", VirtualKeyCode::Escape),
+    ("Period", VirtualKeyCode::Period),
+    ("Comma", VirtualKeyCode::Comma),
+    ("Slash", VirtualKeyCode::Slash),
+    ("Semicolon", VirtualKeyCode::Semicolon),
+    ("Apostrophe", VirtualKeyCode::Apostrophe),
+    ("Minus", VirtualKeyCode::Minus),
+    ("Equals", VirtualKeyCode::Equals),
+    ("F1", VirtualKeyCode::F1),
+    ("F2", VirtualKeyCode::F2),
+    ("F3", VirtualKeyCode::F3),
+    ("F4", VirtualKeyCode::F4),
+    ("F5", VirtualKeyCode::F5),
+    ("F6", VirtualKeyCode::F6),
+];
+
+pub fn key_name(key: VirtualKeyCode) -> Option<&'static str> {
+    KEY_NAMES
+        .iter()
+        .find(|(_, k)| *k == key)
+        .map(|(name, _)| *name)
+}
+
+fn key_from_name(name: &str) -> Option<VirtualKeyCode> {
+    KEY_NAMES
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, key)| *key)
+}
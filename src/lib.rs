@@ -0,0 +1,439 @@
+use rltk::Point;
+use rltk::RandomNumberGenerator as RNG;
+use specs::prelude::*;
+use specs::saveload::SimpleMarkerAllocator;
+mod components;
+pub use components::*;
+mod map;
+pub use map::*;
+mod player;
+pub use player::*;
+mod rect;
+pub use rect::Rect;
+mod gamelog;
+pub use gamelog::{GameLog, LogCategory, LogEntry};
+mod spawner;
+pub use spawner::*;
+mod random_table;
+pub use random_table::RandomTable;
+pub mod saveload_system;
+pub use saveload_system::save_game;
+pub mod map_builders;
+pub mod morgue;
+pub mod raws;
+pub mod replay;
+use replay::ReplayRecorder;
+pub mod rex_assets;
+
+// Systems
+pub mod ai;
+mod damage_system;
+pub mod initiative_system;
+pub use initiative_system::GameClock;
+mod inventory_system;
+mod map_indexing_system;
+mod melee_combat_system;
+mod visibility_system;
+pub use inventory_system::{InventorySystem, ItemDropSystem, ItemRemoveSystem, ItemUseSystem};
+mod trigger_system;
+pub use trigger_system::TriggerSystem;
+pub mod particle_system;
+pub use particle_system::{cull_dead_particles, delete_particles, ParticleBuilder};
+pub mod dispatcher;
+pub mod effects;
+use dispatcher::Systems;
+pub mod settings;
+pub use settings::Settings;
+pub mod keymap;
+
+#[macro_use]
+extern crate lazy_static;
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum MainMenuSelection {
+    NewGame,
+    LoadGame,
+    Quit,
+}
+
+#[derive(PartialEq, Copy, Clone)]
+pub enum RunState {
+    AwaitingInput,
+    PreRun,
+    PlayerTurn,
+    Ticking,
+    ShowInventory,
+    ShowDropItem,
+    ShowRemoveItem,
+    ShowTargeting {
+        range: i32,
+        item: Entity,
+    },
+    MainMenu {
+        menu_selection: MainMenuSelection,
+    },
+    SaveGame,
+    /// Asking what to call the game's first save.
+    NameSave,
+    NextLevel,
+    PreviousLevel,
+    GameOver,
+    MagicMapReveal {
+        row: i32,
+    },
+    SaveSlots {
+        selection: usize,
+        confirm_delete: bool,
+    },
+    MapGeneration,
+    ShowLog,
+    ShowKeybindings,
+    RecoverCheckpoint,
+}
+
+/// The seed the current game was started with. Together with the player's inputs it
+/// fully determines the maps, spawns and combat rolls of a run.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct GameSeed {
+    pub seed: u64,
+}
+
+/// Picks a fresh seed for a new game.
+pub fn random_seed() -> u64 {
+    RNG::new().next_u64()
+}
+
+/// The headless game: the ECS world plus everything needed to advance turns.
+/// Frontends read and render `ecs`, feed player commands in through `submit`,
+/// and hand every other run state to `advance`.
+pub struct State {
+    pub ecs: World,
+    pub mapgen_history: Vec<Map>,
+    pub recorder: Option<ReplayRecorder>,
+    pub systems: Systems,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State::new()
+    }
+}
+
+impl State {
+    /// Sets up a fresh world with a player standing on a newly generated first level.
+    pub fn new() -> State {
+        State::with_seed_option(None)
+    }
+
+    /// Like `State::new`, but every random roll in the game is derived from `seed`.
+    pub fn with_seed(seed: u64) -> State {
+        State::with_seed_option(Some(seed))
+    }
+
+    /// A game with the default settings, which only use the raws built into the game.
+    fn with_seed_option(seed: Option<u64>) -> State {
+        State::with_settings(Settings {
+            seed,
+            ..Settings::default()
+        })
+        .unwrap_or_else(|report| panic!("The built-in raws are invalid: {}", report))
+    }
+
+    /// Sets up a fresh world as `settings` describe, seeded from `settings.seed` if it has one.
+    /// Fails, without touching the loaded raws, if the raws the settings name are invalid.
+    pub fn with_settings(settings: Settings) -> Result<State, raws::RawsError> {
+        raws::load_raws(&raws::RawSources::from_settings(&settings))?;
+
+        let seed = settings.seed.unwrap_or_else(random_seed);
+        let start_depth = settings.start_depth;
+        let mut ecs = World::new();
+        components::register(&mut ecs);
+        ecs.insert(SimpleMarkerAllocator::<SerializeMe>::new());
+        let systems = dispatcher::game_systems().build(&mut ecs);
+
+        let mut gs = State {
+            ecs,
+            mapgen_history: Vec::new(),
+            recorder: None,
+            systems,
+        };
+
+        gs.ecs.insert(Map::new(1, 64, 64));
+        gs.ecs.insert(DungeonMaster::default());
+        gs.ecs.insert(Point::new(0, 0));
+        gs.ecs.insert(RNG::seeded(seed));
+        gs.ecs.insert(GameSeed { seed });
+        gs.ecs.insert(settings);
+        gs.ecs.insert(saveload_system::ActiveSlot::default());
+        let player_entity = spawner::player(&mut gs.ecs, 0, 0);
+        gs.ecs.insert(player_entity);
+        gs.ecs.insert(RunState::MapGeneration);
+        gs.ecs.insert(GameClock { turn: 0 });
+        let mut gamelog = GameLog::new();
+        gamelog
+            .entry(LogCategory::System)
+            .append("Welcome to ")
+            .colored(rltk::RGB::named(rltk::CYAN), "Rogue")
+            .append(".")
+            .log();
+        gs.ecs.insert(gamelog);
+        gs.ecs.insert(rex_assets::RexAssets::new());
+        gs.ecs.insert(particle_system::ParticleBuilder::new());
+        gs.ecs.insert(effects::EffectQueue::new());
+        gs.generate_world_map(start_depth);
+
+        Ok(gs)
+    }
+
+    pub fn run_systems(&mut self) {
+        self.systems.run(&mut self.ecs);
+    }
+
+    pub fn generate_world_map(&mut self, new_depth: i32) {
+        self.mapgen_history.clear();
+        let settings = self.ecs.fetch::<Settings>();
+        let mut rng = self.ecs.write_resource::<RNG>();
+        let mut builder = map_builders::level_builder(
+            new_depth,
+            &mut rng,
+            settings.map_width,
+            settings.map_height,
+        );
+        builder.build_data.record_history = settings.show_mapgen_visualizer;
+        std::mem::drop(settings);
+        builder.build_map(&mut rng);
+        std::mem::drop(rng);
+        self.mapgen_history = builder.build_data.history.clone();
+        let player_start;
+        {
+            let mut worldmap_resource = self.ecs.write_resource::<Map>();
+            *worldmap_resource = builder.build_data.map.clone();
+            player_start = *builder.build_data.starting_position.as_mut().unwrap();
+        }
+
+        builder.spawn_entities(&mut self.ecs);
+        self.move_player_to(player_start.x, player_start.y);
+    }
+
+    /// Puts the player at (`player_x`, `player_y`) on the current map.
+    fn move_player_to(&mut self, player_x: i32, player_y: i32) {
+        let mut player_position = self.ecs.write_resource::<Point>();
+        *player_position = Point::new(player_x, player_y);
+        let mut position_components = self.ecs.write_storage::<Position>();
+        let player_entity = self.ecs.fetch::<Entity>();
+        let player_pos_comp = position_components.get_mut(*player_entity);
+        if let Some(player_pos_comp) = player_pos_comp {
+            player_pos_comp.x = player_x;
+            player_pos_comp.y = player_y;
+        }
+
+        let mut viewshed_components = self.ecs.write_storage::<Viewshed>();
+        let vs = viewshed_components.get_mut(*player_entity);
+        if let Some(vs) = vs {
+            vs.dirty = true;
+        }
+    }
+
+    /// Leaves the current level by its stairs, `offset` levels down (or up, if negative).
+    /// The level is stored as it is left; a level visited before is restored from the
+    /// `DungeonMaster`, and a new one is generated.
+    pub fn goto_level(&mut self, offset: i32) {
+        particle_system::delete_particles(&mut self.ecs);
+        freeze_level_entities(&mut self.ecs);
+
+        let current_depth = self.ecs.fetch::<Map>().depth;
+        let new_depth = current_depth + offset;
+        let stored_map = self.ecs.fetch::<DungeonMaster>().get_map(new_depth);
+
+        if let Some(map) = stored_map {
+            self.mapgen_history.clear();
+            let (player_x, player_y) = arrival_point(&map, offset > 0);
+            *self.ecs.write_resource::<Map>() = map;
+            thaw_level_entities(&mut self.ecs, new_depth);
+            self.move_player_to(player_x, player_y);
+
+            let message = if offset > 0 {
+                "You descend the stairs, and take a moment to rest."
+            } else {
+                "You climb the stairs, and take a moment to rest."
+            };
+            self.ecs
+                .fetch_mut::<GameLog>()
+                .entry(LogCategory::System)
+                .append(message)
+                .log();
+            self.rest_player();
+            return;
+        }
+
+        self.generate_world_map(new_depth);
+        if self.ecs.fetch::<DungeonMaster>().has_level(new_depth - 1) {
+            // Give the way back up a tile of its own where the player arrives
+            let player_pos = *self.ecs.fetch::<Point>();
+            let mut map = self.ecs.write_resource::<Map>();
+            let idx = map.xy_idx(player_pos.x, player_pos.y);
+            if map.tiles[idx] != TileType::DownStairs {
+                map.tiles[idx] = TileType::UpStairs;
+            }
+        }
+
+        self.ecs
+            .fetch_mut::<GameLog>()
+            .entry(LogCategory::System)
+            .append("You descend to the next level.")
+            .log();
+    }
+
+    /// Restores the player to full health, on their way back to a level they know.
+    fn rest_player(&mut self) {
+        let player_entity = self.ecs.fetch::<Entity>();
+        let mut player_health_store = self.ecs.write_storage::<CombatStats>();
+        let player_health = player_health_store.get_mut(*player_entity);
+        if let Some(player_health) = player_health {
+            player_health.hp = i32::max(player_health.hp, player_health.max_hp);
+        }
+    }
+
+    /// Restarts the random number generator for a new game started from `seed`.
+    pub fn reseed(&mut self, seed: u64) {
+        *self.ecs.write_resource::<RNG>() = RNG::seeded(seed);
+        *self.ecs.write_resource::<GameSeed>() = GameSeed { seed };
+    }
+
+    pub fn game_over_cleanup(&mut self) {
+        let mut to_delete = Vec::new();
+        for e in self.ecs.entities().join() {
+            to_delete.push(e);
+        }
+        for del in to_delete.iter() {
+            self.ecs
+                .delete_entity(*del)
+                .expect("Game over cleanup delete failed.");
+        }
+
+        {
+            let player_entity = spawner::player(&mut self.ecs, 0, 0);
+            let mut player_entity_writer = self.ecs.write_resource::<Entity>();
+            *player_entity_writer = player_entity;
+        }
+
+        *self.ecs.write_resource::<GameClock>() = GameClock { turn: 0 };
+        *self.ecs.write_resource::<DungeonMaster>() = DungeonMaster::default();
+        self.ecs.write_resource::<GameLog>().turn = 0;
+        *self.ecs.write_resource::<saveload_system::ActiveSlot>() =
+            saveload_system::ActiveSlot::default();
+        let (seed, start_depth) = {
+            let settings = self.ecs.fetch::<Settings>();
+            (
+                settings.seed.unwrap_or_else(random_seed),
+                settings.start_depth,
+            )
+        };
+        self.reseed(seed);
+        self.generate_world_map(start_depth);
+    }
+
+    pub fn runstate(&self) -> RunState {
+        *self.ecs.fetch::<RunState>()
+    }
+
+    /// Stores the run state for the next tick and clears out anything that died this one.
+    /// Death of the player overrides `newrunstate` with `RunState::GameOver`.
+    pub fn set_runstate(&mut self, newrunstate: RunState) {
+        {
+            let mut runwriter = self.ecs.write_resource::<RunState>();
+            *runwriter = newrunstate;
+        }
+        damage_system::delete_the_dead(&mut self.ecs);
+        if self.runstate() == RunState::GameOver {
+            self.finish_recording();
+        }
+    }
+
+    /// Performs a player command and returns the run state it leads to.
+    pub fn submit(&mut self, command: Command) -> RunState {
+        self.record(command);
+        perform_command(&mut self.ecs, command)
+    }
+
+    /// Advances one of the non-interactive run states and returns the next one.
+    /// States that wait on the player (input, menus, targeting, game over) are returned unchanged.
+    pub fn advance(&mut self, runstate: RunState) -> RunState {
+        match runstate {
+            RunState::MapGeneration => RunState::PreRun,
+            RunState::PreRun => {
+                self.run_systems();
+                RunState::Ticking
+            }
+            RunState::PlayerTurn => {
+                particle_system::delete_particles(&mut self.ecs);
+                self.run_systems();
+                match *self.ecs.fetch::<RunState>() {
+                    RunState::MagicMapReveal { .. } => RunState::MagicMapReveal { row: 0 },
+                    _ => RunState::Ticking,
+                }
+            }
+            RunState::Ticking => {
+                // Let everyone faster than the player act until the player's turn comes around
+                loop {
+                    self.run_systems();
+                    damage_system::delete_the_dead(&mut self.ecs);
+                    if *self.ecs.fetch::<RunState>() == RunState::GameOver {
+                        return RunState::GameOver;
+                    }
+                    if self.is_player_turn() {
+                        return RunState::AwaitingInput;
+                    }
+                }
+            }
+            RunState::NextLevel => {
+                self.goto_level(1);
+                RunState::MapGeneration
+            }
+            RunState::PreviousLevel => {
+                self.goto_level(-1);
+                RunState::MapGeneration
+            }
+            RunState::MagicMapReveal { row } => {
+                let mut map = self.ecs.fetch_mut::<Map>();
+                for x in 0..map.width {
+                    let idx = map.xy_idx(x, row);
+                    map.revealed_tiles[idx] = true;
+                }
+                if row == map.height - 1 {
+                    RunState::Ticking
+                } else {
+                    RunState::MagicMapReveal { row: row + 1 }
+                }
+            }
+            _ => runstate,
+        }
+    }
+
+    /// True once the player has built up enough energy to act. A player without energy
+    /// never has to wait.
+    pub fn is_player_turn(&self) -> bool {
+        let player_entity = *self.ecs.fetch::<Entity>();
+        self.ecs.read_storage::<MyTurn>().contains(player_entity)
+            || !self.ecs.read_storage::<Energy>().contains(player_entity)
+    }
+
+    /// Advances the game until it is waiting on the player again.
+    pub fn run_until_input(&mut self) -> RunState {
+        loop {
+            let runstate = self.runstate();
+            let newrunstate = self.advance(runstate);
+            if newrunstate == runstate {
+                return runstate;
+            }
+            self.set_runstate(newrunstate);
+        }
+    }
+
+    /// Submits a command and plays out the resulting turn.
+    pub fn play(&mut self, command: Command) -> RunState {
+        let newrunstate = self.submit(command);
+        self.set_runstate(newrunstate);
+        self.run_until_input()
+    }
+}
@@ -1,329 +1,398 @@
-use rltk::RandomNumberGenerator as RNG;
-use rltk::{GameState, Point, Rltk};
-use saveload_system::delete_save;
+use rltk::{GameState, Rltk, VirtualKeyCode, RGB};
+use rogue::keymap::Keymap;
+use rogue::replay::Replay;
+use rogue::saveload_system::{self, delete_save, ActiveSlot, SaveSlot};
+use rogue::settings::{self, Settings};
+use rogue::*;
 use specs::prelude::*;
-use specs::saveload::SimpleMarkerAllocator;
-mod components;
-pub use components::*;
-mod map;
-pub use map::*;
-mod player;
-use player::*;
-mod rect;
-pub use rect::Rect;
+mod camera;
+use camera::{render_camera, render_debug_map};
 mod gui;
-pub use gui::*;
-mod gamelog;
-pub use gamelog::GameLog;
-mod spawner;
-pub use spawner::*;
-mod random_table;
-pub use random_table::RandomTable;
-mod saveload_system;
-pub use saveload_system::save_game;
-pub mod camera;
-pub mod map_builders;
-pub mod rex_assets;
-pub use camera::{get_screen_bounds, render_camera, render_debug_map};
-pub mod raws;
-
-// Systems
-mod visibility_system;
-use visibility_system::VisibilitySystem;
-mod monster_ai_system;
-use monster_ai_system::MonsterAI;
-mod map_indexing_system;
-use map_indexing_system::MapIndexingSystem;
-mod melee_combat_system;
-use melee_combat_system::MeleeCombatSystem;
-mod damage_system;
-use damage_system::DamageSystem;
-mod inventory_system;
-pub use inventory_system::{InventorySystem, ItemDropSystem, ItemRemoveSystem, ItemUseSystem};
-mod trigger_system;
-pub use trigger_system::TriggerSystem;
-mod particle_system;
-pub use particle_system::{cull_dead_particles, ParticleBuilder};
-
-#[macro_use]
-extern crate lazy_static;
-
-const SHOW_MAPGEN_VISUALIZER: bool = true;
-
-#[derive(PartialEq, Copy, Clone)]
-pub enum RunState {
-    AwaitingInput,
-    PreRun,
-    PlayerTurn,
-    MonsterTurn,
-    ShowInventory,
-    ShowDropItem,
-    ShowRemoveItem,
-    ShowTargeting { range: i32, item: Entity },
-    MainMenu { menu_selection: MainMenuSelection },
-    SaveGame,
-    NextLevel,
-    GameOver,
-    MagicMapReveal { row: i32 },
-    MapGeneration,
+use gui::*;
+mod input;
+use input::player_input;
+
+const REPLAY_PATH: &str = "./replay.jsonl";
+const KEYBINDINGS_PATH: &str = "./keybindings.json";
+const SETTINGS_PATH: &str = "./settings.json";
+
+/// Feeds a recorded game back through the frontend, one command every `delay_ms`.
+struct Playback {
+    replay: Replay,
+    next: usize,
+    delay_ms: f32,
+    timer: f32,
 }
 
-pub struct State {
-    pub ecs: World,
+struct Frontend {
+    gs: State,
     mapgen_next_state: Option<RunState>,
-    mapgen_history: Vec<Map>,
     mapgen_index: usize,
     mapgen_timer: f32,
+    playback: Option<Playback>,
+    log_viewer: LogViewer,
+    keymap: Keymap,
+    keybinding_editor: KeybindingEditor,
+    save_slots: Vec<SaveSlot>,
+    /// The name being typed for the game's first save.
+    save_name: String,
+    /// Why the last save, load or delete failed, shown on the menu until the next choice.
+    menu_error: Option<String>,
+    /// The checkpoint offered for recovery after an abnormal exit.
+    checkpoint: Option<SaveSlot>,
+    last_autosave_turn: i32,
+    /// Where the last death's morgue file was written, shown on the game over screen.
+    morgue_path: Option<String>,
+    /// Reloads the raws when they change on disk, in development mode.
+    raws_watcher: Option<raws::RawsWatcher>,
 }
 
-impl State {
-    fn run_systems(&mut self) {
-        let mut vis = VisibilitySystem {};
-        vis.run_now(&self.ecs);
-        let mut mob = MonsterAI {};
-        mob.run_now(&self.ecs);
-        let mut trigger_system = TriggerSystem {};
-        trigger_system.run_now(&self.ecs);
-        let mut mapindex = MapIndexingSystem {};
-        mapindex.run_now(&self.ecs);
-        let mut melee = MeleeCombatSystem {};
-        melee.run_now(&self.ecs);
-        let mut damage = DamageSystem {};
-        damage.run_now(&self.ecs);
-        let mut inventory = InventorySystem {};
-        inventory.run_now(&self.ecs);
-        let mut item = ItemUseSystem {};
-        item.run_now(&self.ecs);
-        let mut drop_items = ItemDropSystem {};
-        drop_items.run_now(&self.ecs);
-        let mut remove_items = ItemRemoveSystem {};
-        remove_items.run_now(&self.ecs);
-        let mut particles = particle_system::ParticleSpawnSystem {};
-        particles.run_now(&self.ecs);
-
-        self.ecs.maintain();
-    }
-
-    fn generate_world_map(&mut self, new_depth: i32) {
+impl Frontend {
+    fn reset_mapgen_visualizer(&mut self) {
         self.mapgen_index = 0;
         self.mapgen_timer = 0.0;
-        self.mapgen_history.clear();
-        let mut rng = self.ecs.write_resource::<RNG>();
-        let mut builder = map_builders::level_builder(new_depth, &mut rng, 80, 50);
-        builder.build_map(&mut rng);
-        std::mem::drop(rng);
-        self.mapgen_history = builder.build_data.history.clone();
-        let player_start;
-        {
-            let mut worldmap_resource = self.ecs.write_resource::<Map>();
-            *worldmap_resource = builder.build_data.map.clone();
-            player_start = builder
-                .build_data
-                .starting_position
-                .as_mut()
-                .unwrap()
-                .clone();
-        }
+    }
 
-        builder.spawn_entities(&mut self.ecs);
-        let (player_x, player_y) = (player_start.x, player_start.y);
-        let mut player_position = self.ecs.write_resource::<Point>();
-        *player_position = Point::new(player_x, player_y);
-        let mut position_components = self.ecs.write_storage::<Position>();
-        let player_entity = self.ecs.fetch::<Entity>();
-        let player_pos_comp = position_components.get_mut(*player_entity);
-        if let Some(player_pos_comp) = player_pos_comp {
-            player_pos_comp.x = player_x;
-            player_pos_comp.y = player_y;
+    /// Records the new game to the replay file. The game can be played unrecorded, so a
+    /// failure only earns a warning in the log.
+    fn start_recording(&mut self) {
+        if let Err(e) = self.gs.start_recording(REPLAY_PATH) {
+            self.gs
+                .ecs
+                .fetch_mut::<GameLog>()
+                .entry(LogCategory::System)
+                .colored(
+                    RGB::named(rltk::RED),
+                    format!("Unable to record a replay: {}", e),
+                )
+                .log();
         }
+    }
 
-        let mut viewshed_components = self.ecs.write_storage::<Viewshed>();
-        let vs = viewshed_components.get_mut(*player_entity);
-        if let Some(vs) = vs {
-            vs.dirty = true;
+    /// Checkpoints the game without leaving it. Replays are never saved, and a failed
+    /// autosave is logged rather than interrupting play.
+    fn autosave(&mut self) {
+        if self.playback.is_some() {
+            return;
+        }
+        self.last_autosave_turn = self.gs.ecs.fetch::<GameClock>().turn;
+        if let Err(e) = saveload_system::autosave(&mut self.gs.ecs) {
+            self.gs
+                .ecs
+                .fetch_mut::<GameLog>()
+                .entry(LogCategory::System)
+                .colored(RGB::named(rltk::RED), format!("Autosave failed: {}", e))
+                .log();
         }
     }
 
-    fn entities_to_remove_on_level_change(&mut self) -> Vec<Entity> {
-        let entities = self.ecs.entities();
-        let player = self.ecs.read_storage::<Player>();
-        let backpack = self.ecs.read_storage::<InBackpack>();
-        let player_entity = self.ecs.fetch::<Entity>();
-        let equipped = self.ecs.read_storage::<Equipped>();
-
-        let mut to_delete: Vec<Entity> = Vec::new();
-        for entity in entities.join() {
-            let mut should_delete = true;
-
-            let p = player.get(entity);
-            if let Some(_p) = p {
-                should_delete = false;
+    /// Picks up edits to the raws in development mode, saying in the log whether the reload
+    /// worked or what is wrong with the new raws.
+    fn reload_changed_raws(&mut self, frame_time_ms: f32) {
+        let watcher = match &mut self.raws_watcher {
+            Some(watcher) => watcher,
+            None => return,
+        };
+        let result = match watcher.poll(frame_time_ms) {
+            Some(result) => result,
+            None => return,
+        };
+        let mut gamelog = self.gs.ecs.fetch_mut::<GameLog>();
+        match result {
+            Ok(()) => {
+                let counts: Vec<String> = raws::RAWS
+                    .lock()
+                    .unwrap()
+                    .source_counts()
+                    .iter()
+                    .map(|(source, count)| format!("{} from {}", count, source))
+                    .collect();
+                gamelog
+                    .entry(LogCategory::System)
+                    .colored(
+                        RGB::named(rltk::CYAN),
+                        format!("Reloaded the raws: {}.", counts.join(", ")),
+                    )
+                    .log();
             }
-            let bp = backpack.get(entity);
-            if let Some(bp) = bp {
-                if bp.owner == *player_entity {
-                    should_delete = false;
+            Err(report) => {
+                gamelog
+                    .entry(LogCategory::System)
+                    .colored(
+                        RGB::named(rltk::RED),
+                        "Unable to reload the raws, keeping the old ones:",
+                    )
+                    .log();
+                for line in report.to_string().lines() {
+                    gamelog
+                        .entry(LogCategory::System)
+                        .colored(RGB::named(rltk::RED), line)
+                        .log();
                 }
             }
-            let eq = equipped.get(entity);
-            if let Some(eq) = eq {
-                if eq.owner == *player_entity {
-                    should_delete = false;
-                }
+        }
+    }
+
+    /// Writes the dead character's morgue file. A finished game can't be loaded again, from
+    /// its slot or from a checkpoint.
+    fn end_finished_game(&mut self) {
+        self.morgue_path = None;
+        if self.playback.is_none() {
+            match morgue::write_morgue(&self.gs.ecs) {
+                Ok(path) => self.morgue_path = Some(path.display().to_string()),
+                Err(e) => println!("WARNING: Unable to write the morgue file: {}", e),
             }
+        }
 
-            if should_delete {
-                to_delete.push(entity);
+        let slot = self.gs.ecs.fetch::<ActiveSlot>().name.clone();
+        if let Some(slot) = slot {
+            if let Err(e) = delete_save(&self.gs.ecs, &slot) {
+                println!("WARNING: Unable to delete the finished game's save: {}", e);
             }
         }
+        saveload_system::end_session(&self.gs.ecs);
+    }
 
-        to_delete
+    fn autosave_due(&self) -> bool {
+        let interval = self.gs.ecs.fetch::<Settings>().autosave_interval;
+        let turn = self.gs.ecs.fetch::<GameClock>().turn;
+        interval > 0 && turn - self.last_autosave_turn >= interval
     }
 
-    fn goto_next_level(&mut self) {
-        let to_delete = self.entities_to_remove_on_level_change();
-        for target in to_delete {
-            self.ecs
-                .delete_entity(target)
-                .expect("Unable to delete entity.");
-        }
+    /// Picks up a loaded game where it was saved.
+    fn resume_loaded_game(&mut self) -> RunState {
+        self.gs.stop_recording();
+        self.last_autosave_turn = self.gs.ecs.fetch::<GameClock>().turn;
+        RunState::AwaitingInput
+    }
 
-        let current_depth;
-        {
-            let worldmap_resource = self.ecs.write_resource::<Map>();
-            current_depth = worldmap_resource.depth;
+    fn recover_checkpoint_selection(&mut self, result: RecoverCheckpointResult) -> RunState {
+        match (result, &self.checkpoint) {
+            (RecoverCheckpointResult::NoResponse, _) => return RunState::RecoverCheckpoint,
+            (RecoverCheckpointResult::Recover, Some(_)) => {}
+            _ => {
+                // The game's own slot, if it has one, still holds its last manual save
+                saveload_system::end_session(&self.gs.ecs);
+                self.checkpoint = None;
+                return RunState::MapGeneration;
+            }
         }
+        self.checkpoint = None;
+        match saveload_system::recover_session(&mut self.gs.ecs) {
+            Ok(()) => self.resume_loaded_game(),
+            Err(e) => {
+                self.menu_error = Some(format!("Unable to recover the checkpoint: {}", e));
+                saveload_system::end_session(&self.gs.ecs);
+                self.gs.game_over_cleanup();
+                self.start_recording();
+                RunState::MainMenu {
+                    menu_selection: MainMenuSelection::NewGame,
+                }
+            }
+        }
+    }
 
-        self.generate_world_map(current_depth + 1);
-
-        let player_entity = self.ecs.fetch::<Entity>();
-
-        let mut gamelog = self.ecs.fetch_mut::<GameLog>();
-        gamelog
-            .entries
-            .push("You descend to the next level, and take a moment to rest.".to_string());
-        let mut player_health_store = self.ecs.write_storage::<CombatStats>();
-        let player_health = player_health_store.get_mut(*player_entity);
-        if let Some(player_health) = player_health {
-            player_health.hp = i32::max(player_health.hp, player_health.max_hp);
+    fn save_slot_selection(&mut self, result: SaveSlotMenuResult) -> RunState {
+        if !matches!(result, SaveSlotMenuResult::NoResponse { .. }) {
+            self.menu_error = None;
+        }
+        match result {
+            SaveSlotMenuResult::NoResponse {
+                selection,
+                confirm_delete,
+            } => RunState::SaveSlots {
+                selection,
+                confirm_delete,
+            },
+            SaveSlotMenuResult::Cancel => RunState::MainMenu {
+                menu_selection: MainMenuSelection::LoadGame,
+            },
+            SaveSlotMenuResult::Delete { selection } => {
+                if let Err(e) = delete_save(&self.gs.ecs, &self.save_slots[selection].name) {
+                    self.menu_error = Some(format!("Unable to delete the save: {}", e));
+                }
+                self.save_slots = saveload_system::list_slots(&self.gs.ecs);
+                RunState::SaveSlots {
+                    selection: usize::min(selection, self.save_slots.len().saturating_sub(1)),
+                    confirm_delete: false,
+                }
+            }
+            SaveSlotMenuResult::Load { selection } => {
+                let slot = &self.save_slots[selection];
+                if slot.metadata.is_err() {
+                    return RunState::SaveSlots {
+                        selection,
+                        confirm_delete: false,
+                    };
+                }
+                match saveload_system::load_game(&mut self.gs.ecs, &slot.name) {
+                    Ok(()) => self.resume_loaded_game(),
+                    Err(e) => {
+                        self.menu_error = Some(format!("Unable to load the saved game: {}", e));
+                        // The world may be half-loaded, so start over from a fresh one
+                        self.gs.game_over_cleanup();
+                        self.start_recording();
+                        self.save_slots = saveload_system::list_slots(&self.gs.ecs);
+                        RunState::SaveSlots {
+                            selection,
+                            confirm_delete: false,
+                        }
+                    }
+                }
+            }
         }
     }
 
-    fn game_over_cleanup(&mut self) {
-        let mut to_delete = Vec::new();
-        for e in self.ecs.entities().join() {
-            to_delete.push(e);
+    fn playback_input(&mut self, ctx: &mut Rltk) -> RunState {
+        let playback = self.playback.as_mut().unwrap();
+        match ctx.key {
+            Some(VirtualKeyCode::Equals) | Some(VirtualKeyCode::NumpadAdd) => {
+                playback.delay_ms = f32::max(playback.delay_ms / 2.0, 10.0)
+            }
+            Some(VirtualKeyCode::Minus) | Some(VirtualKeyCode::NumpadSubtract) => {
+                playback.delay_ms = f32::min(playback.delay_ms * 2.0, 5000.0)
+            }
+            _ => {}
         }
-        for del in to_delete.iter() {
-            self.ecs
-                .delete_entity(*del)
-                .expect("Game over cleanup delete failed.");
+
+        playback.timer += ctx.frame_time_ms;
+        if playback.timer < playback.delay_ms {
+            return RunState::AwaitingInput;
         }
+        playback.timer = 0.0;
 
-        {
-            let player_entity = spawner::player(&mut self.ecs, 0, 0);
-            let mut player_entity_writer = self.ecs.write_resource::<Entity>();
-            *player_entity_writer = player_entity;
+        if let Some(recorded) = playback.replay.commands.get(playback.next) {
+            playback.next += 1;
+            let command = recorded.resolve(&self.gs.ecs);
+            return self.gs.submit(command);
         }
 
-        self.generate_world_map(1);
+        let matches = playback
+            .replay
+            .verify(&replay::ReplayDigest::of(&self.gs.ecs));
+        let message = if matches {
+            "Replay finished; the final state matches the recording."
+        } else {
+            "Replay finished; the final state does NOT match the recording!"
+        };
+        self.gs
+            .ecs
+            .fetch_mut::<GameLog>()
+            .entry(LogCategory::System)
+            .append(message)
+            .log();
+        self.playback = None;
+        RunState::AwaitingInput
     }
 }
 
-impl GameState for State {
+impl GameState for Frontend {
     fn tick(&mut self, ctx: &mut Rltk) {
-        let mut newrunstate;
-        {
-            let runstate = self.ecs.fetch::<RunState>();
-            newrunstate = *runstate;
-        }
+        let runstate = self.gs.runstate();
+        let mut newrunstate = runstate;
 
         ctx.cls();
-        particle_system::cull_dead_particles(&mut self.ecs, ctx);
+        cull_dead_particles(&mut self.gs.ecs, ctx.frame_time_ms);
+        self.reload_changed_raws(ctx.frame_time_ms);
 
         // Render loop if in game
         match newrunstate {
-            RunState::MainMenu { .. } => {}
-            RunState::GameOver { .. } => {}
+            RunState::MainMenu { .. }
+            | RunState::SaveSlots { .. }
+            | RunState::RecoverCheckpoint => {}
+            RunState::GameOver => {}
             _ => {
-                render_camera(&self.ecs, ctx);
-                draw_ui(&self.ecs, ctx);
+                render_camera(&self.gs.ecs, ctx);
+                draw_ui(&self.gs.ecs, ctx);
             }
         }
 
         match newrunstate {
             RunState::MapGeneration => {
-                if !SHOW_MAPGEN_VISUALIZER {
+                // Levels restored from the dungeon master have no generation to show
+                if !self.gs.ecs.fetch::<Settings>().show_mapgen_visualizer
+                    || self.gs.mapgen_history.is_empty()
+                {
                     newrunstate = self.mapgen_next_state.unwrap();
                 }
                 ctx.cls();
-                if self.mapgen_index < self.mapgen_history.len() {
-                    render_debug_map(&self.mapgen_history[self.mapgen_index], ctx);
+                let history = &self.gs.mapgen_history;
+                if self.mapgen_index < history.len() {
+                    render_debug_map(&history[self.mapgen_index], ctx);
                 }
 
-                let frametime: f32 = 5000.0 / (self.mapgen_history.len() as f32);
+                let frametime: f32 = 5000.0 / (history.len() as f32);
                 self.mapgen_timer += ctx.frame_time_ms;
                 if self.mapgen_timer > frametime {
                     self.mapgen_timer = 0.0;
                     self.mapgen_index += 1;
-                    if self.mapgen_index >= self.mapgen_history.len() {
+                    if self.mapgen_index >= history.len() {
                         newrunstate = self.mapgen_next_state.unwrap();
                     }
                 }
             }
 
             RunState::MainMenu { .. } => {
-                let result = main_menu(self, ctx);
+                let result = main_menu(&mut self.gs, ctx, self.menu_error.as_deref());
                 match result {
                     MainMenuResult::NoSeleciton { selected } => {
                         newrunstate = RunState::MainMenu {
                             menu_selection: selected,
                         }
                     }
-                    MainMenuResult::Selected { selected } => match selected {
-                        MainMenuSelection::NewGame => newrunstate = RunState::MapGeneration,
-                        MainMenuSelection::LoadGame => {
-                            saveload_system::load_game(&mut self.ecs);
-                            newrunstate = RunState::AwaitingInput;
-                            delete_save();
-                        }
-                        MainMenuSelection::Quit => {
-                            std::process::exit(0);
+                    MainMenuResult::Selected { selected } => {
+                        self.menu_error = None;
+                        match selected {
+                            MainMenuSelection::NewGame => newrunstate = RunState::MapGeneration,
+                            MainMenuSelection::LoadGame => {
+                                self.save_slots = saveload_system::list_slots(&self.gs.ecs);
+                                newrunstate = RunState::SaveSlots {
+                                    selection: 0,
+                                    confirm_delete: false,
+                                };
+                            }
+                            MainMenuSelection::Quit => {
+                                saveload_system::end_session(&self.gs.ecs);
+                                std::process::exit(0);
+                            }
                         }
-                    },
+                    }
                 }
             }
-            RunState::PreRun => {
-                self.run_systems();
-                self.ecs.maintain();
-                newrunstate = RunState::AwaitingInput;
+            RunState::SaveSlots {
+                selection,
+                confirm_delete,
+            } => {
+                if self.save_slots.is_empty() {
+                    newrunstate = RunState::MainMenu {
+                        menu_selection: MainMenuSelection::NewGame,
+                    };
+                } else {
+                    let result = save_slot_menu(
+                        ctx,
+                        &self.save_slots,
+                        selection,
+                        confirm_delete,
+                        self.menu_error.as_deref(),
+                    );
+                    newrunstate = self.save_slot_selection(result);
+                }
             }
             RunState::AwaitingInput => {
-                newrunstate = player_input(self, ctx);
-            }
-            RunState::PlayerTurn => {
-                self.run_systems();
-                self.ecs.maintain();
-                match *self.ecs.fetch::<RunState>() {
-                    RunState::MagicMapReveal { .. } => {
-                        newrunstate = RunState::MagicMapReveal { row: 0 }
-                    }
-                    _ => newrunstate = RunState::MonsterTurn,
+                if self.playback.is_some() {
+                    newrunstate = self.playback_input(ctx);
+                } else {
+                    newrunstate = player_input(&mut self.gs, ctx, &self.keymap);
                 }
             }
-            RunState::MonsterTurn => {
-                self.run_systems();
-                self.ecs.maintain();
-                newrunstate = RunState::AwaitingInput;
-            }
             RunState::ShowInventory => {
-                let result = show_inventory(self, ctx);
+                let result = show_inventory(&mut self.gs, ctx);
                 match result.0 {
                     ItemMenuResult::Cancel => newrunstate = RunState::AwaitingInput,
                     ItemMenuResult::NoResponse => {}
                     ItemMenuResult::Selected => {
                         let item_entity = result.1.unwrap();
-                        let is_ranged = self.ecs.read_storage::<Ranged>();
+                        let is_ranged = self.gs.ecs.read_storage::<Ranged>();
                         let is_item_ranged = is_ranged.get(item_entity);
                         if let Some(is_item_ranged) = is_item_ranged {
                             newrunstate = RunState::ShowTargeting {
@@ -331,99 +400,138 @@ impl GameState for State {
                                 item: item_entity,
                             };
                         } else {
-                            let mut intent = self.ecs.write_storage::<WantsToUseItem>();
-                            intent
-                                .insert(
-                                    *self.ecs.fetch::<Entity>(),
-                                    WantsToUseItem {
-                                        item: item_entity,
-                                        target: None,
-                                    },
-                                )
-                                .expect("Unable to insert intent to use item.");
-                            newrunstate = RunState::PlayerTurn;
+                            std::mem::drop(is_ranged);
+                            newrunstate = self.gs.submit(Command::UseItem {
+                                item: item_entity,
+                                target: None,
+                            });
                         }
                     }
                 }
             }
             RunState::ShowDropItem => {
-                let result = drop_item_menu(self, ctx);
+                let result = drop_item_menu(&mut self.gs, ctx);
                 match result.0 {
                     ItemMenuResult::Cancel => newrunstate = RunState::AwaitingInput,
                     ItemMenuResult::NoResponse => {}
                     ItemMenuResult::Selected => {
                         let item_entity = result.1.unwrap();
-                        let mut intent = self.ecs.write_storage::<WantsToDropItem>();
-                        intent
-                            .insert(
-                                *self.ecs.fetch::<Entity>(),
-                                WantsToDropItem { item: item_entity },
-                            )
-                            .expect("Unable to drop item");
-                        newrunstate = RunState::PlayerTurn;
+                        newrunstate = self.gs.submit(Command::DropItem { item: item_entity });
                     }
                 }
             }
 
             RunState::ShowRemoveItem => {
-                let result = remove_item_menu(self, ctx);
+                let result = remove_item_menu(&mut self.gs, ctx);
                 match result.0 {
                     ItemMenuResult::Cancel => newrunstate = RunState::AwaitingInput,
                     ItemMenuResult::NoResponse => {}
                     ItemMenuResult::Selected => {
                         let item_entity = result.1.unwrap();
-                        let mut intent = self.ecs.write_storage::<WantsToRemoveItem>();
-                        intent
-                            .insert(
-                                *self.ecs.fetch::<Entity>(),
-                                WantsToRemoveItem { item: item_entity },
-                            )
-                            .expect("Unable to insert intent to remove item.");
-                        newrunstate = RunState::PlayerTurn;
+                        newrunstate = self.gs.submit(Command::RemoveItem { item: item_entity });
                     }
                 }
             }
 
             RunState::ShowTargeting { range, item } => {
-                let result = ranged_target(self, ctx, range);
+                let result = ranged_target(&mut self.gs, ctx, range);
                 match result.0 {
                     ItemMenuResult::Cancel => newrunstate = RunState::AwaitingInput,
                     ItemMenuResult::NoResponse => {}
                     ItemMenuResult::Selected => {
-                        let mut intent = self.ecs.write_storage::<WantsToUseItem>();
-                        intent
-                            .insert(
-                                *self.ecs.fetch::<Entity>(),
-                                WantsToUseItem {
-                                    item,
-                                    target: result.1,
-                                },
-                            )
-                            .expect("Unable to insert target intent.");
-                        newrunstate = RunState::PlayerTurn;
+                        newrunstate = self.gs.submit(Command::UseItem {
+                            item,
+                            target: result.1,
+                        });
                     }
                 }
             }
 
-            RunState::SaveGame => {
-                save_game(&mut self.ecs);
+            RunState::ShowLog => {
+                let result = show_log(&self.gs.ecs, ctx, &mut self.log_viewer);
+                if result == LogViewerResult::Close {
+                    self.log_viewer = LogViewer::default();
+                    newrunstate = RunState::AwaitingInput;
+                }
+            }
 
-                newrunstate = RunState::MainMenu {
-                    menu_selection: MainMenuSelection::LoadGame,
-                };
+            RunState::ShowKeybindings => {
+                let result = show_keybindings(ctx, &mut self.keymap, &mut self.keybinding_editor);
+                if result == KeybindingsResult::Close {
+                    if let Err(e) = self.keymap.save(KEYBINDINGS_PATH) {
+                        self.gs
+                            .ecs
+                            .fetch_mut::<GameLog>()
+                            .entry(LogCategory::System)
+                            .colored(
+                                RGB::named(rltk::RED),
+                                format!("Unable to save the keybindings: {}", e),
+                            )
+                            .log();
+                    }
+                    self.keybinding_editor = KeybindingEditor::default();
+                    newrunstate = RunState::AwaitingInput;
+                }
             }
 
-            RunState::NextLevel => {
-                self.goto_next_level();
-                newrunstate = RunState::MapGeneration;
+            // A game saved for the first time is given a name of the player's choosing
+            RunState::SaveGame if self.gs.ecs.fetch::<ActiveSlot>().name.is_none() => {
+                self.save_name = saveload_system::unused_slot_name(&self.gs.ecs);
+                self.menu_error = None;
+                newrunstate = RunState::NameSave;
             }
 
+            RunState::NameSave => {
+                let result = name_save(ctx, &mut self.save_name, self.menu_error.as_deref());
+                match result {
+                    NameSaveResult::NoResponse => {}
+                    NameSaveResult::Cancel => {
+                        self.menu_error = None;
+                        newrunstate = RunState::AwaitingInput;
+                    }
+                    NameSaveResult::Save => {
+                        match saveload_system::name_slot(&mut self.gs.ecs, &self.save_name) {
+                            Ok(()) => {
+                                self.menu_error = None;
+                                newrunstate = RunState::SaveGame;
+                            }
+                            Err(e) => self.menu_error = Some(e.to_string()),
+                        }
+                    }
+                }
+            }
+
+            RunState::SaveGame => match save_game(&mut self.gs.ecs) {
+                Ok(()) => {
+                    saveload_system::end_session(&self.gs.ecs);
+                    self.gs.finish_recording();
+                    newrunstate = RunState::MainMenu {
+                        menu_selection: MainMenuSelection::LoadGame,
+                    };
+                }
+                Err(e) => {
+                    // Keep playing rather than lose the game; the slot still holds the last
+                    // good save
+                    self.gs
+                        .ecs
+                        .fetch_mut::<GameLog>()
+                        .entry(LogCategory::System)
+                        .colored(RGB::named(rltk::RED), format!("Unable to save: {}", e))
+                        .log();
+                    newrunstate = RunState::AwaitingInput;
+                }
+            },
+
             RunState::GameOver => {
-                let result = game_over(ctx);
+                let result = game_over(ctx, self.morgue_path.as_deref());
                 match result {
                     GameOverResult::NoSeleciton => {}
                     GameOverResult::QuitToMenu => {
-                        self.game_over_cleanup();
+                        self.gs.game_over_cleanup();
+                        self.start_recording();
+                        self.last_autosave_turn = 0;
+                        self.playback = None;
+                        self.reset_mapgen_visualizer();
                         newrunstate = RunState::MainMenu {
                             menu_selection: MainMenuSelection::NewGame,
                         };
@@ -431,61 +539,163 @@ impl GameState for State {
                 }
             }
 
-            RunState::MagicMapReveal { row } => {
-                let mut map = self.ecs.fetch_mut::<Map>();
-                for x in 0..map.width {
-                    let idx = map.xy_idx(x as i32, row);
-                    map.revealed_tiles[idx] = true;
-                }
-                if row == map.height - 1 {
-                    newrunstate = RunState::MonsterTurn;
-                } else {
-                    newrunstate = RunState::MagicMapReveal { row: row + 1 };
-                }
+            RunState::NextLevel | RunState::PreviousLevel => {
+                newrunstate = self.gs.advance(newrunstate);
+                self.reset_mapgen_visualizer();
+                self.autosave();
             }
+
+            RunState::RecoverCheckpoint => {
+                let result = match &self.checkpoint {
+                    Some(checkpoint) => recover_checkpoint(ctx, checkpoint),
+                    None => RecoverCheckpointResult::Discard,
+                };
+                newrunstate = self.recover_checkpoint_selection(result);
+            }
+
+            _ => {
+                newrunstate = self.gs.advance(newrunstate);
+            }
+        }
+
+        if newrunstate == RunState::AwaitingInput && self.autosave_due() {
+            self.autosave();
         }
 
-        {
-            let mut runwriter = self.ecs.write_resource::<RunState>();
-            *runwriter = newrunstate;
+        self.gs.set_runstate(newrunstate);
+        if self.gs.runstate() == RunState::GameOver && runstate != RunState::GameOver {
+            self.end_finished_game();
         }
-        damage_system::delete_the_dead(&mut self.ecs);
     }
 }
 
 fn main() -> rltk::BError {
     use rltk::RltkBuilder;
+
+    let args: Vec<String> = std::env::args().collect();
+    let arg_value = |flag: &str| match settings::flag_value(&args, flag) {
+        Ok(value) => value.map(|v| v.to_string()),
+        Err(e) => {
+            println!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    let replay = arg_value("--replay").map(|path| match Replay::load(&path) {
+        Ok(replay) => replay,
+        Err(e) => {
+            println!("Unable to load the replay {}: {}", path, e);
+            std::process::exit(1);
+        }
+    });
+    let replay_delay_ms = match settings::number_flag(&args, "--replay-delay") {
+        Ok(delay) => delay.unwrap_or(250.0),
+        Err(e) => {
+            println!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    let settings = match Settings::from_args(&args, SETTINGS_PATH) {
+        Ok(settings) => settings,
+        Err(e) => {
+            println!("{}", e);
+            std::process::exit(2);
+        }
+    };
+
+    if let Some(replay) = &replay {
+        if args.iter().any(|a| a == "--verify") {
+            let digest = match replay.run() {
+                Ok(digest) => digest,
+                Err(report) => {
+                    println!("{}", report);
+                    std::process::exit(1);
+                }
+            };
+            if replay.verify(&digest) {
+                println!("Replay verified: {:?}", digest);
+                std::process::exit(0);
+            }
+            println!(
+                "Replay mismatch: expected {:?}, got {:?}",
+                replay.expected, digest
+            );
+            std::process::exit(1);
+        }
+    }
+
+    // A replay plays out with the settings it was recorded with
+    let game_settings = match &replay {
+        Some(replay) => Settings {
+            show_mapgen_visualizer: settings.show_mapgen_visualizer,
+            ..replay.start_settings()
+        },
+        None => settings.clone(),
+    };
+    let raw_sources = raws::RawSources::from_settings(&game_settings);
+    let gs = match State::with_settings(game_settings) {
+        Ok(gs) => gs,
+        Err(report) => {
+            println!("{}", report);
+            std::process::exit(1);
+        }
+    };
+
     let mut context = RltkBuilder::simple80x50()
         .with_title("Rogue")
-        .with_dimensions(160, 100)
+        .with_dimensions(settings.window_width, settings.window_height)
         .build()?;
-    context.with_post_scanlines(true);
+    context.with_post_scanlines(settings.scanlines);
 
-    let mut gs = State {
-        ecs: World::new(),
+    let mut frontend = Frontend {
+        gs,
         mapgen_next_state: Some(RunState::PreRun),
         mapgen_index: 0,
-        mapgen_history: Vec::new(),
         mapgen_timer: 0.0,
+        playback: None,
+        log_viewer: LogViewer::default(),
+        keymap: Keymap::load(KEYBINDINGS_PATH),
+        keybinding_editor: KeybindingEditor::default(),
+        save_slots: Vec::new(),
+        save_name: String::new(),
+        menu_error: None,
+        checkpoint: None,
+        last_autosave_turn: 0,
+        morgue_path: None,
+        raws_watcher: raw_sources
+            .base_dir
+            .as_ref()
+            .map(|_| raws::RawsWatcher::new(&raw_sources)),
     };
 
-    components::register(&mut gs.ecs);
-    gs.ecs.insert(SimpleMarkerAllocator::<SerializeMe>::new());
-
-    raws::load_raws();
-
-    gs.ecs.insert(Map::new(1, 64, 64));
-    gs.ecs.insert(Point::new(0, 0));
-    gs.ecs.insert(RNG::new());
-    let player_entity = spawner::player(&mut gs.ecs, 0, 0);
-    gs.ecs.insert(player_entity);
-    gs.ecs.insert(RunState::MapGeneration);
-    gs.ecs.insert(gamelog::GameLog {
-        entries: vec!["Welcome to Rogue.".to_string()],
-    });
-    gs.ecs.insert(rex_assets::RexAssets::new());
-    gs.ecs.insert(particle_system::ParticleBuilder::new());
-    gs.generate_world_map(1);
+    match replay {
+        None => {
+            let legacy_save = std::path::Path::new(saveload_system::LEGACY_SAVE_PATH);
+            match saveload_system::import_legacy_save(&frontend.gs.ecs, legacy_save) {
+                Ok(Some(slot)) => {
+                    println!("Moved {} into the slot {}", legacy_save.display(), slot)
+                }
+                Ok(None) => {}
+                Err(e) => println!(
+                    "WARNING: Unable to move {} into the save directory: {}",
+                    legacy_save.display(),
+                    e
+                ),
+            }
+            frontend.start_recording();
+            frontend.checkpoint = saveload_system::interrupted_session(&frontend.gs.ecs);
+            if frontend.checkpoint.is_some() {
+                frontend.gs.set_runstate(RunState::RecoverCheckpoint);
+            }
+        }
+        Some(replay) => {
+            frontend.playback = Some(Playback {
+                replay,
+                next: 0,
+                delay_ms: replay_delay_ms,
+                timer: 0.0,
+            });
+        }
+    }
 
-    rltk::main_loop(context, gs)
+    rltk::main_loop(context, frontend)
 }
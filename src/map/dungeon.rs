@@ -0,0 +1,101 @@
+use super::super::{OtherLevelPosition, Position};
+use super::{Map, TileType};
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use std::collections::BTreeMap;
+
+/// Every level the player has left, kept so it can be restored exactly as it was: tiles,
+/// revealed areas and all. The entities on those levels stay in the world with an
+/// `OtherLevelPosition` in place of their `Position`.
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub struct DungeonMaster {
+    levels: BTreeMap<i32, Map>,
+}
+
+impl DungeonMaster {
+    pub fn store_map(&mut self, map: &Map) {
+        self.levels.insert(map.depth, map.clone());
+    }
+
+    /// The stored level at `depth`, ready to become the current map.
+    pub fn get_map(&self, depth: i32) -> Option<Map> {
+        self.levels.get(&depth).map(|map| {
+            let mut map = map.clone();
+            map.tile_content = vec![Vec::new(); (map.width * map.height) as usize];
+            map
+        })
+    }
+
+    pub fn has_level(&self, depth: i32) -> bool {
+        self.levels.contains_key(&depth)
+    }
+}
+
+/// Stores the current level and takes everything on it, apart from the player, out of the
+/// world's `Position`s, so no system touches it until the player comes back.
+pub fn freeze_level_entities(ecs: &mut World) {
+    let map = ecs.fetch::<Map>();
+    ecs.write_resource::<DungeonMaster>().store_map(&map);
+
+    let entities = ecs.entities();
+    let player_entity = *ecs.fetch::<Entity>();
+    let mut positions = ecs.write_storage::<Position>();
+    let mut other_level_positions = ecs.write_storage::<OtherLevelPosition>();
+
+    let mut frozen: Vec<Entity> = Vec::new();
+    for (entity, pos) in (&entities, &positions).join() {
+        if entity != player_entity {
+            other_level_positions
+                .insert(
+                    entity,
+                    OtherLevelPosition {
+                        x: pos.x,
+                        y: pos.y,
+                        depth: map.depth,
+                    },
+                )
+                .expect("Unable to insert other level position");
+            frozen.push(entity);
+        }
+    }
+    for entity in frozen {
+        positions.remove(entity);
+    }
+}
+
+/// Puts everything stored on the level at `depth` back where it was left.
+pub fn thaw_level_entities(ecs: &mut World, depth: i32) {
+    let entities = ecs.entities();
+    let mut positions = ecs.write_storage::<Position>();
+    let mut other_level_positions = ecs.write_storage::<OtherLevelPosition>();
+
+    let mut thawed: Vec<Entity> = Vec::new();
+    for (entity, pos) in (&entities, &other_level_positions).join() {
+        if pos.depth == depth {
+            positions
+                .insert(entity, Position { x: pos.x, y: pos.y })
+                .expect("Unable to insert position");
+            thawed.push(entity);
+        }
+    }
+    for entity in thawed {
+        other_level_positions.remove(entity);
+    }
+}
+
+/// Where the player arrives on a stored level: on the up-stairs when coming down, and on the
+/// down-stairs when coming up. Falls back to the first open tile if the stairs are missing.
+pub fn arrival_point(map: &Map, descending: bool) -> (i32, i32) {
+    let stairs = if descending {
+        TileType::UpStairs
+    } else {
+        TileType::DownStairs
+    };
+    let idx = map
+        .tiles
+        .iter()
+        .position(|tile| *tile == stairs)
+        .or_else(|| map.tiles.iter().position(|tile| tile.is_walkable()))
+        .unwrap_or(0);
+    map.idx_xy(idx)
+}
@@ -1,9 +1,11 @@
 use rltk::{Algorithm2D, BaseMap, Point};
 use serde::{Deserialize, Serialize};
 use specs::prelude::*;
-use std::collections::HashSet;
+use std::collections::BTreeSet;
 mod tiletype;
 pub use tiletype::TileType;
+mod dungeon;
+pub use dungeon::*;
 
 #[derive(Default, Serialize, Deserialize, Clone)]
 pub struct Map {
@@ -14,7 +16,7 @@ pub struct Map {
     pub visible_tiles: Vec<bool>,
     pub blocked: Vec<bool>,
     pub depth: i32,
-    pub view_blocked: HashSet<usize>,
+    pub view_blocked: BTreeSet<usize>,
 
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
@@ -32,7 +34,7 @@ impl Map {
             visible_tiles: vec![false; map_tile_count],
             blocked: vec![false; map_tile_count],
             depth: new_depth,
-            view_blocked: HashSet::new(),
+            view_blocked: BTreeSet::new(),
             tile_content: vec![Vec::new(); map_tile_count],
         }
     }
@@ -79,7 +81,7 @@ impl BaseMap for Map {
         let x = idx as i32 % self.width;
         let y = idx as i32 / self.width;
         let w = self.width as usize;
-        let tt = self.tiles[idx as usize];
+        let tt = self.tiles[idx];
 
         // Cardinal directions
         if self.is_exit_valid(x - 1, y) {
@@ -5,6 +5,7 @@ pub enum TileType {
     Wall,
     Floor,
     DownStairs,
+    UpStairs,
     Road,
     Gravel,
     Grass,
@@ -16,24 +17,22 @@ pub enum TileType {
 
 impl TileType {
     pub fn is_walkable(&self) -> bool {
-        match self {
+        matches!(
+            self,
             TileType::Floor
-            | TileType::DownStairs
-            | TileType::Road
-            | TileType::Grass
-            | TileType::ShallowWater
-            | TileType::WoodFloor
-            | TileType::Bridge
-            | TileType::Gravel => true,
-            _ => false,
-        }
+                | TileType::DownStairs
+                | TileType::UpStairs
+                | TileType::Road
+                | TileType::Grass
+                | TileType::ShallowWater
+                | TileType::WoodFloor
+                | TileType::Bridge
+                | TileType::Gravel
+        )
     }
 
     pub fn is_opaque(&self) -> bool {
-        match self {
-            TileType::Wall => true,
-            _ => false,
-        }
+        matches!(self, TileType::Wall)
     }
 
     pub fn move_cost(&self) -> f32 {
@@ -33,20 +33,17 @@ impl AreaStartingPosition {
     }
 
     fn build(&mut self, _rng: &mut Rng, build_data: &mut BuilderMap) {
-        let seed_x;
-        let seed_y;
+        let seed_x = match self.x {
+            XStart::Left => 1,
+            XStart::Center => build_data.map.width / 2,
+            XStart::Right => build_data.map.width - 2,
+        };
 
-        match self.x {
-            XStart::Left => seed_x = 1,
-            XStart::Center => seed_x = build_data.map.width / 2,
-            XStart::Right => seed_x = build_data.map.width - 2,
-        }
-
-        match self.y {
-            YStart::Top => seed_y = 1,
-            YStart::Center => seed_y = build_data.map.height / 2,
-            YStart::Bottom => seed_y = build_data.map.height - 2,
-        }
+        let seed_y = match self.y {
+            YStart::Top => 1,
+            YStart::Center => build_data.map.height / 2,
+            YStart::Bottom => build_data.map.height - 2,
+        };
 
         let mut available_floors: Vec<(usize, f32)> = Vec::new();
         for (idx, tiletype) in build_data.map.tiles.iter().enumerate() {
@@ -16,7 +16,7 @@ impl DistantExit {
     }
 
     fn build(&mut self, _rng: &mut Rng, build_data: &mut BuilderMap) {
-        let starting_pos = build_data.starting_position.as_ref().unwrap().clone();
+        let starting_pos = *build_data.starting_position.as_ref().unwrap();
         let start_idx = build_data.map.xy_idx(starting_pos.x, starting_pos.y);
         build_data.map.populate_blocked();
         let map_starts: Vec<usize> = vec![start_idx];
@@ -31,7 +31,7 @@ impl DistantExit {
         for (i, tile) in build_data.map.tiles.iter_mut().enumerate() {
             if *tile == TileType::Floor {
                 let distance_to_start = dijkstra_map.map[i];
-                if distance_to_start != std::f32::MAX {
+                if distance_to_start != f32::MAX {
                     // If it is further away than our current exit candidate, move the exit
                     if distance_to_start > exit_tile.1 {
                         exit_tile.0 = i;
@@ -20,10 +20,8 @@ impl DoorBuilder {
         if let Some(halls_original) = &build_data.corridors {
             let halls = halls_original.clone();
             for hall in halls.iter() {
-                if hall.len() > 2 {
-                    if self.door_possible(build_data, hall[0]) {
-                        build_data.spawn_list.push((hall[0], "Door".to_string()));
-                    }
+                if hall.len() > 2 && self.door_possible(build_data, hall[0]) {
+                    build_data.spawn_list.push((hall[0], "Door".to_string()));
                 }
             }
         } else {
@@ -25,7 +25,7 @@ impl EdgeWallBuilder {
         }
 
         for y in 0..height {
-            build_data.map.tiles[(width * y)] = TileType::Wall;
+            build_data.map.tiles[width * y] = TileType::Wall;
             build_data.map.tiles[width * (y + 1) - 1] = TileType::Wall;
         }
 
@@ -83,7 +83,7 @@ struct Grid<'a> {
 }
 
 impl<'a> Grid<'a> {
-    fn new(width: i32, height: i32, rng: &mut Rng) -> Grid {
+    fn new(width: i32, height: i32, rng: &mut Rng) -> Grid<'_> {
         let mut grid = Grid {
             width,
             height,
@@ -1,6 +1,6 @@
 use super::{
     spawner::{spawn_entity, spawn_region},
-    Map, Position, Rect, TileType, SHOW_MAPGEN_VISUALIZER,
+    Map, Position, Rect, TileType,
 };
 use rltk::RandomNumberGenerator as Rng;
 mod simple_map;
@@ -101,13 +101,15 @@ pub struct BuilderMap {
     pub rooms: Option<Vec<Rect>>,
     pub corridors: Option<Vec<Vec<usize>>>,
     pub history: Vec<Map>,
+    /// Whether `take_snapshot` records anything, for the mapgen visualizer.
+    pub record_history: bool,
     pub width: i32,
     pub height: i32,
 }
 
 impl BuilderMap {
     fn take_snapshot(&mut self) {
-        if SHOW_MAPGEN_VISUALIZER {
+        if self.record_history {
             let mut snapshot = self.map.clone();
             for v in snapshot.revealed_tiles.iter_mut() {
                 *v = true;
@@ -135,6 +137,7 @@ impl BuilderChain {
                 rooms: None,
                 corridors: None,
                 history: Vec::new(),
+                record_history: false,
                 width,
                 height,
             },
@@ -166,7 +169,7 @@ impl BuilderChain {
     }
 
     pub fn spawn_entities(&mut self, ecs: &mut World) {
-        if self.build_data.spawn_list.len() == 0 {
+        if self.build_data.spawn_list.is_empty() {
             panic!("Cannot spawn without spawn list.");
         }
 
@@ -225,21 +228,19 @@ pub fn random_builder(new_depth: i32, rng: &mut Rng, width: i32, height: i32) ->
 
 #[allow(dead_code)]
 fn random_start_position(rng: &mut Rng) -> (XStart, YStart) {
-    let x;
     let xroll = rng.roll_dice(1, 3);
-    match xroll {
-        1 => x = XStart::Left,
-        2 => x = XStart::Center,
-        _ => x = XStart::Right,
-    }
+    let x = match xroll {
+        1 => XStart::Left,
+        2 => XStart::Center,
+        _ => XStart::Right,
+    };
 
-    let y;
     let yroll = rng.roll_dice(1, 3);
-    match yroll {
-        1 => y = YStart::Bottom,
-        2 => y = YStart::Center,
-        _ => y = YStart::Top,
-    }
+    let y = match yroll {
+        1 => YStart::Bottom,
+        2 => YStart::Center,
+        _ => YStart::Top,
+    };
 
     (x, y)
 }
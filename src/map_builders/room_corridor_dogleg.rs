@@ -28,7 +28,7 @@ impl DoglegCorridors {
         for (i, room) in rooms.iter().enumerate() {
             if i > 0 {
                 let (new_x, new_y) = room.center();
-                let (prev_x, prev_y) = rooms[i as usize - 1].center();
+                let (prev_x, prev_y) = rooms[i - 1].center();
                 if rng.range(0, 2) == 1 {
                     let mut c1 =
                         apply_horizontal_tunnel(&mut build_data.map, prev_x, new_x, prev_y);
@@ -19,7 +19,7 @@ impl CorridorSpawner {
         if let Some(corridors) = &build_data.corridors {
             for c in corridors.iter() {
                 let depth = build_data.map.depth;
-                spawn_region(&build_data.map, rng, &c, depth, &mut build_data.spawn_list);
+                spawn_region(&build_data.map, rng, c, depth, &mut build_data.spawn_list);
             }
         } else {
             panic!("Corridor spawning requires corridors.");
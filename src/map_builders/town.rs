@@ -1,10 +1,9 @@
-use super::{
-    random_start_position, AreaStartingPosition, BuilderChain, BuilderMap, DistantExit,
-    InitialMapBuilder, TileType,
-};
+use super::{BuilderChain, BuilderMap, InitialMapBuilder, Position, TileType};
 use rltk::RandomNumberGenerator as Rng;
 use std::collections::HashSet;
 
+const TOWN_WALL_X: i32 = 30;
+
 pub struct TownBuilder {}
 
 impl InitialMapBuilder for TownBuilder {
@@ -31,11 +30,18 @@ impl TownBuilder {
         let exit_idx = build_data.map.xy_idx(build_data.width - 5, wall_gap_y);
         build_data.map.tiles[exit_idx] = TileType::DownStairs;
 
+        // Start on the road, just inside the western gate
+        build_data.starting_position = Some(Position {
+            x: TOWN_WALL_X + 2,
+            y: wall_gap_y,
+        });
+
         let mut building_size: Vec<(usize, i32)> = Vec::new();
         for (i, building) in buildings.iter().enumerate() {
             building_size.push((i, building.2 * building.3));
         }
-        building_size.sort_by(|a, b| b.1.cmp(&a.1));
+        building_size.sort_by_key(|a| std::cmp::Reverse(a.1));
+        self.populate(rng, build_data, &buildings, &building_size, wall_gap_y);
 
         for t in build_data.map.visible_tiles.iter_mut() {
             *t = true;
@@ -81,12 +87,11 @@ impl TownBuilder {
     }
 
     fn town_walls(&mut self, rng: &mut Rng, build_data: &mut BuilderMap) -> (HashSet<usize>, i32) {
-        const X_OFFSET: i32 = 30;
         let mut available_building_tiles: HashSet<usize> = HashSet::new();
         let wall_gap_y = rng.roll_dice(1, build_data.height - 9) + 5;
         for y in 1..build_data.height - 2 {
             if !(y > wall_gap_y - 4 && y < wall_gap_y + 4) {
-                let idx = build_data.map.xy_idx(X_OFFSET, y);
+                let idx = build_data.map.xy_idx(TOWN_WALL_X, y);
                 build_data.map.tiles[idx] = TileType::Wall;
                 build_data.map.tiles[idx - 1] = TileType::Floor;
                 let idx_right = build_data.map.xy_idx(build_data.width - 2, y);
@@ -99,7 +104,7 @@ impl TownBuilder {
                     }
                 }
             } else {
-                for x in X_OFFSET..build_data.width {
+                for x in TOWN_WALL_X..build_data.width {
                     let road_idx = build_data.map.xy_idx(x, y);
                     build_data.map.tiles[road_idx] = TileType::Road;
                 }
@@ -108,7 +113,7 @@ impl TownBuilder {
 
         build_data.take_snapshot();
 
-        for x in X_OFFSET..build_data.width - 1 {
+        for x in TOWN_WALL_X..build_data.width - 1 {
             let idx_top = build_data.map.xy_idx(x, 1);
             build_data.map.tiles[idx_top] = TileType::Wall;
             let idx_bot = build_data.map.xy_idx(x, build_data.height - 2);
@@ -156,8 +161,8 @@ impl TownBuilder {
                         build_data.map.tiles[idx] = TileType::WoodFloor;
                         available_building_tiles.remove(&idx);
                         available_building_tiles.remove(&(&idx + 1));
-                        available_building_tiles.remove(&(&idx + build_data.width as usize));
-                        available_building_tiles.remove(&(&idx - build_data.width as usize));
+                        available_building_tiles.remove(&(idx + build_data.width as usize));
+                        available_building_tiles.remove(&(idx - build_data.width as usize));
                     }
                 }
                 build_data.take_snapshot();
@@ -200,7 +205,7 @@ impl TownBuilder {
         &mut self,
         rng: &mut Rng,
         build_data: &mut BuilderMap,
-        buildings: &mut Vec<(i32, i32, i32, i32)>,
+        buildings: &mut [(i32, i32, i32, i32)],
         wall_gap_y: i32,
     ) -> Vec<usize> {
         let mut doors = Vec::new();
@@ -235,8 +240,8 @@ impl TownBuilder {
         for door_idx in doors.iter() {
             let mut nearest_roads: Vec<(usize, f32)> = Vec::new();
             let door_pt = rltk::Point::new(
-                *door_idx as i32 % build_data.map.width as i32,
-                *door_idx as i32 / build_data.map.width as i32,
+                *door_idx as i32 % build_data.map.width,
+                *door_idx as i32 / build_data.map.width,
             );
             for r in roads.iter() {
                 nearest_roads.push((
@@ -253,10 +258,10 @@ impl TownBuilder {
             nearest_roads.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
 
             let destination = nearest_roads[0].0;
-            let path = rltk::a_star_search(*door_idx, destination, &mut build_data.map);
+            let path = rltk::a_star_search(*door_idx, destination, &build_data.map);
             if path.success {
                 for step in path.steps.iter() {
-                    let idx = *step as usize;
+                    let idx = *step;
                     build_data.map.tiles[idx] = TileType::Road;
                     roads.push(idx);
                 }
@@ -264,9 +269,45 @@ impl TownBuilder {
             build_data.take_snapshot();
         }
     }
+
+    /// Puts the shopkeeper in the largest building, a townsperson in each of the others and
+    /// a guard either side of the western gate.
+    fn populate(
+        &mut self,
+        rng: &mut Rng,
+        build_data: &mut BuilderMap,
+        buildings: &[(i32, i32, i32, i32)],
+        building_size: &[(usize, i32)],
+        wall_gap_y: i32,
+    ) {
+        for (n, (i, _size)) in building_size.iter().enumerate() {
+            let (bx, by, bw, bh) = buildings[*i];
+            let mut floor: Vec<usize> = Vec::new();
+            for y in by + 1..by + bh - 1 {
+                for x in bx + 1..bx + bw - 1 {
+                    let idx = build_data.map.xy_idx(x, y);
+                    if build_data.map.tiles[idx] == TileType::WoodFloor {
+                        floor.push(idx);
+                    }
+                }
+            }
+            if floor.is_empty() {
+                continue;
+            }
+            let idx = floor[(rng.roll_dice(1, floor.len() as i32) - 1) as usize];
+            let name = if n == 0 { "Shopkeeper" } else { "Townsperson" };
+            build_data.spawn_list.push((idx, name.to_string()));
+        }
+
+        for y in [wall_gap_y - 2, wall_gap_y + 2].iter() {
+            let idx = build_data.map.xy_idx(TOWN_WALL_X + 1, *y);
+            build_data.spawn_list.push((idx, "Town Guard".to_string()));
+        }
+        build_data.take_snapshot();
+    }
 }
 
-pub fn town_builder(new_depth: i32, rng: &mut Rng, width: i32, height: i32) -> BuilderChain {
+pub fn town_builder(new_depth: i32, _rng: &mut Rng, width: i32, height: i32) -> BuilderChain {
     let mut chain = BuilderChain::new(new_depth, width, height);
     chain.start_with(TownBuilder::new());
     chain.with(super::VoronoiSpawning::new());
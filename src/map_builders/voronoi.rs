@@ -69,21 +69,16 @@ impl VoronoiCellBuilder {
             let y = i as i32 / build_data.map.width;
 
             for (seed, pos) in voronoi_seeds.iter().enumerate() {
-                let distance;
-                match self.distance_algorithm {
-                    DistanceAlgorithm::Pythagoras => {
-                        distance = rltk::DistanceAlg::PythagorasSquared
-                            .distance2d(rltk::Point::new(x, y), pos.1);
-                    }
+                let distance = match self.distance_algorithm {
+                    DistanceAlgorithm::Pythagoras => rltk::DistanceAlg::PythagorasSquared
+                        .distance2d(rltk::Point::new(x, y), pos.1),
                     DistanceAlgorithm::Manhattan => {
-                        distance =
-                            rltk::DistanceAlg::Manhattan.distance2d(rltk::Point::new(x, y), pos.1);
+                        rltk::DistanceAlg::Manhattan.distance2d(rltk::Point::new(x, y), pos.1)
                     }
                     DistanceAlgorithm::Chebyshev => {
-                        distance =
-                            rltk::DistanceAlg::Chebyshev.distance2d(rltk::Point::new(x, y), pos.1);
+                        rltk::DistanceAlg::Chebyshev.distance2d(rltk::Point::new(x, y), pos.1)
                     }
-                }
+                };
                 voronoi_distance[seed] = (seed, distance);
             }
 
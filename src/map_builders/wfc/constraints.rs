@@ -64,8 +64,9 @@ pub fn build_patterns(
 
     if dedupe {
         println!("Pre de-duplication, there are {} patterns.", patterns.len());
-        let set: HashSet<Vec<TileType>> = patterns.drain(..).collect();
-        patterns.extend(set.into_iter());
+        // Keep the first occurrence of each pattern so the order stays stable between runs
+        let mut seen: HashSet<Vec<TileType>> = HashSet::new();
+        patterns.retain(|pattern| seen.insert(pattern.clone()));
         println!("There are {} patterns.", patterns.len());
     }
 
@@ -49,7 +49,7 @@ impl WFCBuilder {
         chunk_size: i32,
         build_data: &mut BuilderMap,
     ) {
-        build_data.map = Map::new(0, build_data.width, build_data.height);
+        build_data.map = Map::new(build_data.map.depth, build_data.width, build_data.height);
         let mut counter = 0;
         let mut x = 1;
         let mut y = 1;
@@ -65,7 +65,8 @@ impl WFCBuilder {
                 if y + chunk_size > build_data.map.height {
                     // Move to the next page
                     build_data.take_snapshot();
-                    build_data.map = Map::new(0, build_data.width, build_data.height);
+                    build_data.map =
+                        Map::new(build_data.map.depth, build_data.width, build_data.height);
 
                     x = 1;
                     y = 1;
@@ -1,8 +1,9 @@
 use specs::prelude::*;
 
 use super::{
-    particle_system::ParticleBuilder, CombatStats, DefenseBonus, Equipped, GameLog,
-    MeleePowerBonus, Name, Position, SufferDamage, WantsToMelee,
+    effects::{EffectQueue, EffectType, Targets},
+    CombatStats, DefenseBonus, Equipped, GameLog, LogCategory, MeleePowerBonus, Name, Renderable,
+    WantsToMelee,
 };
 
 pub struct MeleeCombatSystem {}
@@ -14,12 +15,11 @@ impl<'a> System<'a> for MeleeCombatSystem {
         WriteStorage<'a, WantsToMelee>,
         ReadStorage<'a, Name>,
         ReadStorage<'a, CombatStats>,
-        WriteStorage<'a, SufferDamage>,
         ReadStorage<'a, Equipped>,
-        WriteExpect<'a, ParticleBuilder>,
+        WriteExpect<'a, EffectQueue>,
         ReadStorage<'a, DefenseBonus>,
         ReadStorage<'a, MeleePowerBonus>,
-        ReadStorage<'a, Position>,
+        ReadStorage<'a, Renderable>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -29,12 +29,11 @@ impl<'a> System<'a> for MeleeCombatSystem {
             mut wants_melee,
             names,
             combat_stats,
-            mut inflict_damage,
             equipped,
-            mut particle_builder,
+            mut effects,
             defense_bonus,
             power_bonus,
-            positions,
+            renderables,
         ) = data;
 
         for (entity, wants_melee, name, stats) in
@@ -63,34 +62,26 @@ impl<'a> System<'a> for MeleeCombatSystem {
                         }
                     }
 
-                    let pos = positions.get(wants_melee.target);
-                    if let Some(pos) = pos {
-                        particle_builder.request(
-                            pos.x,
-                            pos.y,
-                            rltk::RGB::named(rltk::ORANGE),
-                            rltk::RGB::named(rltk::BLACK),
-                            rltk::to_cp437('‼'),
-                            200.0,
-                        );
-                    }
-
                     let damage = i32::max(
                         0,
                         (stats.power + offensive_bonus) - (target_stats.defense + defensive_bonus),
                     );
 
                     if damage == 0 {
-                        log.entries.push(format!(
-                            "{} is unable to hurt {}",
-                            &name.name, &target_name.name
-                        ));
+                        log.entry(LogCategory::Combat)
+                            .name(&name.name, renderables.get(entity))
+                            .append(" is unable to hurt ")
+                            .name(&target_name.name, renderables.get(wants_melee.target))
+                            .append(".")
+                            .log();
                     } else {
-                        log.entries.push(format!(
-                            "{} hits {}, for {} hp.",
-                            &name.name, &target_name.name, damage
-                        ));
-                        SufferDamage::new_damage(&mut inflict_damage, wants_melee.target, damage);
+                        effects.add(
+                            Some(entity),
+                            EffectType::Damage { amount: damage },
+                            Targets::Single {
+                                target: wants_melee.target,
+                            },
+                        );
                     }
                 }
             }
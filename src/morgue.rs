@@ -0,0 +1,220 @@
+use super::{
+    saveload_system::GAME_VERSION, CombatStats, DefenseBonus, EquipmentSlot, Equipped, GameClock,
+    GameLog, GameSeed, Hidden, InBackpack, LastDamage, Map, MeleePowerBonus, Name, Position,
+    Renderable, Settings, TileType,
+};
+use specs::prelude::*;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How many of the final log entries go into a morgue file.
+pub const LOG_LINES: usize = 30;
+
+/// Writes a morgue file describing the player's death into the morgue directory, returning
+/// where it went. The file is named for the character, the time, the seed and the turn, so
+/// deaths in the same second don't overwrite each other.
+pub fn write_morgue(ecs: &World) -> io::Result<PathBuf> {
+    let dir = PathBuf::from(&ecs.fetch::<Settings>().morgue_dir);
+    fs::create_dir_all(&dir)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    let name: String = player_name(ecs)
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    let path = dir.join(format!(
+        "{}-{}-seed{}-turn{}.txt",
+        name,
+        timestamp,
+        ecs.fetch::<GameSeed>().seed,
+        ecs.fetch::<GameClock>().turn
+    ));
+    fs::write(&path, morgue_text(ecs))?;
+    Ok(path)
+}
+
+fn player_name(ecs: &World) -> String {
+    ecs.read_storage::<Name>()
+        .get(*ecs.fetch::<Entity>())
+        .map_or("Unknown".to_string(), |name| name.name.clone())
+}
+
+/// The character dump for the player as the world stands: how they died, their stats and
+/// belongings, the end of the message log and the level they died on.
+pub fn morgue_text(ecs: &World) -> String {
+    let player_entity = *ecs.fetch::<Entity>();
+    let map = ecs.fetch::<Map>();
+    let name = player_name(ecs);
+    let mut out = String::new();
+
+    out.push_str(&format!("Rogue {} character dump\n\n", GAME_VERSION));
+    out.push_str(&format!(
+        "{} died on depth {} after {} turns.\n",
+        name,
+        map.depth,
+        ecs.fetch::<GameClock>().turn
+    ));
+    let cause = match ecs.read_storage::<LastDamage>().get(player_entity) {
+        Some(LastDamage {
+            source: Some(source),
+            amount,
+        }) if *source == name => format!("Killed by their own hand, for {} hp.", amount),
+        Some(LastDamage {
+            source: Some(source),
+            amount,
+        }) => format!("Killed by {}, who dealt the final {} hp.", source, amount),
+        Some(LastDamage {
+            source: None,
+            amount,
+        }) => format!("Killed by {} hp of damage from an unknown source.", amount),
+        None => "Died of unknown causes.".to_string(),
+    };
+    out.push_str(&cause);
+    out.push('\n');
+    out.push_str(&format!("Seed: {}\n", ecs.fetch::<GameSeed>().seed));
+
+    out.push_str("\nStats\n");
+    if let Some(stats) = ecs.read_storage::<CombatStats>().get(player_entity) {
+        out.push_str(&format!(
+            "  HP {}/{}  Power {}  Defense {}\n",
+            stats.hp, stats.max_hp, stats.power, stats.defense
+        ));
+    }
+
+    let names = ecs.read_storage::<Name>();
+    let power_bonus = ecs.read_storage::<MeleePowerBonus>();
+    let defense_bonus = ecs.read_storage::<DefenseBonus>();
+    out.push_str("\nEquipment\n");
+    let mut equipped_any = false;
+    for (equipped, item_name, item) in
+        (&ecs.read_storage::<Equipped>(), &names, &ecs.entities()).join()
+    {
+        if equipped.owner != player_entity {
+            continue;
+        }
+        equipped_any = true;
+        let slot = match equipped.slot {
+            EquipmentSlot::Melee => "Melee",
+            EquipmentSlot::Shield => "Shield",
+        };
+        let mut bonuses = Vec::new();
+        if let Some(bonus) = power_bonus.get(item) {
+            bonuses.push(format!("{:+} power", bonus.power));
+        }
+        if let Some(bonus) = defense_bonus.get(item) {
+            bonuses.push(format!("{:+} defense", bonus.defense));
+        }
+        if bonuses.is_empty() {
+            out.push_str(&format!("  {}: {}\n", slot, item_name.name));
+        } else {
+            out.push_str(&format!(
+                "  {}: {} ({})\n",
+                slot,
+                item_name.name,
+                bonuses.join(", ")
+            ));
+        }
+    }
+    if !equipped_any {
+        out.push_str("  Nothing\n");
+    }
+
+    out.push_str("\nInventory\n");
+    let mut carried: Vec<&str> = (&ecs.read_storage::<InBackpack>(), &names)
+        .join()
+        .filter(|(pack, _)| pack.owner == player_entity)
+        .map(|(_, item_name)| item_name.name.as_str())
+        .collect();
+    carried.sort_unstable();
+    if carried.is_empty() {
+        out.push_str("  Nothing\n");
+    }
+    for item_name in carried {
+        out.push_str(&format!("  {}\n", item_name));
+    }
+
+    out.push_str(&format!("\nLast {} messages\n", LOG_LINES));
+    let log = ecs.fetch::<GameLog>();
+    let start = log.entries.len().saturating_sub(LOG_LINES);
+    for entry in &log.entries[start..] {
+        out.push_str(&format!("  [{:>5}] {}\n", entry.turn, entry.text()));
+    }
+
+    out.push_str(&format!("\nFinal level (depth {})\n", map.depth));
+    out.push_str(&render_level(ecs, &map));
+    out
+}
+
+fn tile_char(tile: TileType) -> char {
+    match tile {
+        TileType::Wall => '#',
+        TileType::Floor => '.',
+        TileType::DownStairs => '>',
+        TileType::UpStairs => '<',
+        TileType::Road => '=',
+        TileType::Gravel => ';',
+        TileType::Grass => '"',
+        TileType::ShallowWater => '~',
+        TileType::DeepWater => '~',
+        TileType::WoodFloor => '_',
+        TileType::Bridge => ':',
+    }
+}
+
+/// The revealed part of the level in plain ASCII, cropped to what the player has seen, with
+/// whatever was in sight when they died drawn over it.
+fn render_level(ecs: &World, map: &Map) -> String {
+    let revealed: Vec<(i32, i32)> = (0..map.tiles.len())
+        .filter(|idx| map.revealed_tiles[*idx])
+        .map(|idx| map.idx_xy(idx))
+        .collect();
+    if revealed.is_empty() {
+        return "  Nothing was explored.\n".to_string();
+    }
+    let min_x = revealed.iter().map(|(x, _)| *x).min().unwrap();
+    let max_x = revealed.iter().map(|(x, _)| *x).max().unwrap();
+    let min_y = revealed.iter().map(|(_, y)| *y).min().unwrap();
+    let max_y = revealed.iter().map(|(_, y)| *y).max().unwrap();
+
+    let mut glyphs: Vec<char> = map
+        .tiles
+        .iter()
+        .enumerate()
+        .map(|(idx, tile)| {
+            if map.revealed_tiles[idx] {
+                tile_char(*tile)
+            } else {
+                ' '
+            }
+        })
+        .collect();
+
+    // Draw lower render orders last so the player and monsters end up on top of items
+    let positions = ecs.read_storage::<Position>();
+    let renderables = ecs.read_storage::<Renderable>();
+    let hidden = ecs.read_storage::<Hidden>();
+    let mut drawn: Vec<(&Position, &Renderable)> = (&positions, &renderables, !&hidden)
+        .join()
+        .map(|(pos, render, _)| (pos, render))
+        .collect();
+    drawn.sort_by_key(|(_, render)| std::cmp::Reverse(render.render_order));
+    for (pos, render) in drawn {
+        let idx = map.xy_idx(pos.x, pos.y);
+        if idx < glyphs.len() && map.visible_tiles[idx] {
+            let glyph = rltk::to_char(render.glyph as u8);
+            glyphs[idx] = if glyph.is_ascii_graphic() { glyph } else { '*' };
+        }
+    }
+
+    let mut out = String::new();
+    for y in min_y..=max_y {
+        let row: String = (min_x..=max_x).map(|x| glyphs[map.xy_idx(x, y)]).collect();
+        out.push_str("  ");
+        out.push_str(row.trim_end());
+        out.push('\n');
+    }
+    out
+}
@@ -1,23 +1,42 @@
-use super::{ParticleLifetime, Position, Renderable, Rltk};
+use super::{ParticleLifetime, Position, Renderable};
 use rltk::RGB;
 use specs::prelude::*;
 
-pub fn cull_dead_particles(ecs: &mut World, ctx: &Rltk) {
+/// Ages particles by the frame time and hides the ones that have expired.
+/// Expired particles are only deleted by `delete_particles`, at a fixed point in the turn,
+/// so that frame timing never changes which entities exist during a turn.
+pub fn cull_dead_particles(ecs: &mut World, frame_time_ms: f32) {
     let mut dead_particles: Vec<Entity> = Vec::new();
     {
         let mut particles = ecs.write_storage::<ParticleLifetime>();
         let entities = ecs.entities();
-        for (entity, mut particle) in (&entities, &mut particles).join() {
-            particle.lifetime_ms -= ctx.frame_time_ms;
+        for (entity, particle) in (&entities, &mut particles).join() {
+            particle.lifetime_ms -= frame_time_ms;
             if particle.lifetime_ms < 0.0 {
                 dead_particles.push(entity);
             }
         }
     }
 
+    let mut renderables = ecs.write_storage::<Renderable>();
     for dead in dead_particles.iter() {
-        ecs.delete_entity(*dead)
-            .expect("Unable to delete dead particle.");
+        renderables.remove(*dead);
+    }
+}
+
+pub fn delete_particles(ecs: &mut World) {
+    let mut particles_to_delete: Vec<Entity> = Vec::new();
+    {
+        let particles = ecs.read_storage::<ParticleLifetime>();
+        let entities = ecs.entities();
+        for (entity, _particle) in (&entities, &particles).join() {
+            particles_to_delete.push(entity);
+        }
+    }
+
+    for particle in particles_to_delete.iter() {
+        ecs.delete_entity(*particle)
+            .expect("Unable to delete particle.");
     }
 }
 
@@ -34,6 +53,12 @@ pub struct ParticleBuilder {
     requests: Vec<ParticleRequest>,
 }
 
+impl Default for ParticleBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ParticleBuilder {
     pub fn new() -> ParticleBuilder {
         ParticleBuilder {
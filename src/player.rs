@@ -1,14 +1,21 @@
 use crate::TileType;
-use rltk::{Point, Rltk, VirtualKeyCode};
+use rltk::Point;
 use specs::prelude::*;
 use std::cmp::{max, min};
 
 use super::{
-    BlocksTile, BlocksVisibility, CombatStats, Door, EntityMoved, GameLog, Item, Map, Monster,
-    Player, Position, Renderable, RunState, State, Viewshed, WantsToMelee, WantsToPickupItem,
+    initiative_system::{
+        spend_energy, ATTACK_COST, DROP_COST, MOVE_COST, PICKUP_COST, REMOVE_COST, USE_ITEM_COST,
+        WAIT_COST,
+    },
+    BlocksTile, BlocksVisibility, BystanderAi, CombatStats, Door, Energy, EntityMoved, GameLog,
+    Item, LogCategory, Map, Monster, MyTurn, Player, Position, Renderable, RunState, VendorAi,
+    Viewshed, WantsToDropItem, WantsToMelee, WantsToPickupItem, WantsToRemoveItem, WantsToUseItem,
 };
 
-pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
+/// Moves the player, or attacks whatever stands in the way. Bystanders swap places with the
+/// player instead, and vendors are left alone. Returns the energy the action cost.
+pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) -> i32 {
     let mut positions = ecs.write_storage::<Position>();
     let players = ecs.read_storage::<Player>();
     let mut viewsheds = ecs.write_storage::<Viewshed>();
@@ -21,7 +28,10 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
     let mut blocks_visibility = ecs.write_storage::<BlocksVisibility>();
     let mut blocks_movement = ecs.write_storage::<BlocksTile>();
     let mut renderables = ecs.write_storage::<Renderable>();
+    let bystanders = ecs.read_storage::<BystanderAi>();
+    let vendors = ecs.read_storage::<VendorAi>();
 
+    let mut swapped: Option<(Entity, Position)> = None;
     for (entity, _player, pos, viewshed) in
         (&entities, &players, &mut positions, &mut viewsheds).join()
     {
@@ -30,11 +40,19 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
             || pos.y + delta_y < 1
             || pos.y + delta_y > map.height - 1
         {
-            return;
+            return MOVE_COST;
         }
         let destination_idx = map.xy_idx(pos.x + delta_x, pos.y + delta_y);
 
         for potential_target in map.tile_content[destination_idx].iter() {
+            if bystanders.contains(*potential_target) {
+                swapped = Some((*potential_target, *pos));
+                continue;
+            }
+            if vendors.contains(*potential_target) {
+                continue;
+            }
+
             let target = combat_stats.get(*potential_target);
             if let Some(_target) = target {
                 wants_to_melee
@@ -45,7 +63,7 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
                         },
                     )
                     .expect("Add target failed");
-                return;
+                return ATTACK_COST;
             }
 
             let door = doors.get_mut(*potential_target);
@@ -59,7 +77,7 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
             }
         }
 
-        if !map.blocked[destination_idx] {
+        if !map.blocked[destination_idx] || swapped.is_some() {
             pos.x = min(map.width - 1, max(0, pos.x + delta_x));
             pos.y = min(map.height - 1, max(0, pos.y + delta_y));
 
@@ -73,55 +91,89 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
                 .expect("Unable to insert marker");
         }
     }
-}
-
-pub fn player_input(gs: &mut State, ctx: &mut Rltk) -> RunState {
-    // Player movement
-    match ctx.key {
-        None => return RunState::AwaitingInput, // Nothing happened
-        Some(key) => match key {
-            VirtualKeyCode::Left | VirtualKeyCode::Numpad4 | VirtualKeyCode::H => {
-                try_move_player(-1, 0, &mut gs.ecs)
-            }
 
-            VirtualKeyCode::Right | VirtualKeyCode::Numpad6 | VirtualKeyCode::L => {
-                try_move_player(1, 0, &mut gs.ecs)
-            }
+    if let Some((bystander, player_pos)) = swapped {
+        if let Some(pos) = positions.get_mut(bystander) {
+            *pos = player_pos;
+        }
+        if let Some(viewshed) = viewsheds.get_mut(bystander) {
+            viewshed.dirty = true;
+        }
+        entity_moved
+            .insert(bystander, EntityMoved {})
+            .expect("Unable to insert marker");
+    }
+    MOVE_COST
+}
 
-            VirtualKeyCode::Up | VirtualKeyCode::Numpad8 | VirtualKeyCode::K => {
-                try_move_player(0, -1, &mut gs.ecs)
-            }
+/// A single player action, independent of how the frontend collected it.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Command {
+    Move { delta_x: i32, delta_y: i32 },
+    Wait,
+    PickUp,
+    UseItem { item: Entity, target: Option<Point> },
+    DropItem { item: Entity },
+    RemoveItem { item: Entity },
+    Descend,
+    Ascend,
+}
 
-            VirtualKeyCode::Down | VirtualKeyCode::Numpad2 | VirtualKeyCode::J => {
-                try_move_player(0, 1, &mut gs.ecs)
+pub fn perform_command(ecs: &mut World, command: Command) -> RunState {
+    let player_entity = *ecs.fetch::<Entity>();
+    let mut newrunstate = RunState::PlayerTurn;
+    let cost = match command {
+        Command::Move { delta_x, delta_y } => try_move_player(delta_x, delta_y, ecs),
+        Command::Wait => {
+            skip_turn(ecs);
+            WAIT_COST
+        }
+        Command::PickUp => {
+            get_item(ecs);
+            PICKUP_COST
+        }
+        Command::UseItem { item, target } => {
+            let mut intent = ecs.write_storage::<WantsToUseItem>();
+            intent
+                .insert(player_entity, WantsToUseItem { item, target })
+                .expect("Unable to insert intent to use item.");
+            USE_ITEM_COST
+        }
+        Command::DropItem { item } => {
+            let mut intent = ecs.write_storage::<WantsToDropItem>();
+            intent
+                .insert(player_entity, WantsToDropItem { item })
+                .expect("Unable to drop item");
+            DROP_COST
+        }
+        Command::RemoveItem { item } => {
+            let mut intent = ecs.write_storage::<WantsToRemoveItem>();
+            intent
+                .insert(player_entity, WantsToRemoveItem { item })
+                .expect("Unable to insert intent to remove item.");
+            REMOVE_COST
+        }
+        Command::Descend => {
+            if try_next_level(ecs) {
+                newrunstate = RunState::NextLevel;
             }
-
-            // Diagonals
-            VirtualKeyCode::Numpad9 | VirtualKeyCode::U => try_move_player(1, -1, &mut gs.ecs),
-
-            VirtualKeyCode::Numpad7 | VirtualKeyCode::Y => try_move_player(-1, -1, &mut gs.ecs),
-
-            VirtualKeyCode::Numpad3 | VirtualKeyCode::N => try_move_player(1, 1, &mut gs.ecs),
-
-            VirtualKeyCode::Numpad1 | VirtualKeyCode::B => try_move_player(-1, 1, &mut gs.ecs),
-
-            // Actions
-            VirtualKeyCode::G => get_item(&mut gs.ecs),
-            VirtualKeyCode::I => return RunState::ShowInventory,
-            VirtualKeyCode::D => return RunState::ShowDropItem,
-            VirtualKeyCode::R => return RunState::ShowRemoveItem,
-            VirtualKeyCode::Escape => return RunState::SaveGame,
-            VirtualKeyCode::Period => {
-                if try_next_level(&mut gs.ecs) {
-                    return RunState::NextLevel;
-                }
+            MOVE_COST
+        }
+        Command::Ascend => {
+            if try_previous_level(ecs) {
+                newrunstate = RunState::PreviousLevel;
             }
-            VirtualKeyCode::Space | VirtualKeyCode::Numpad5 => return skip_turn(&mut gs.ecs),
+            MOVE_COST
+        }
+    };
 
-            _ => return RunState::AwaitingInput,
-        },
-    }
-    RunState::PlayerTurn
+    spend_energy(
+        &mut ecs.write_storage::<Energy>(),
+        &mut ecs.write_storage::<MyTurn>(),
+        player_entity,
+        cost,
+    );
+    newrunstate
 }
 
 fn get_item(ecs: &mut World) {
@@ -140,7 +192,10 @@ fn get_item(ecs: &mut World) {
     }
 
     match target_item {
-        None => gamelog.entries.push("Nothing here to pick up.".to_string()),
+        None => gamelog
+            .entry(LogCategory::Item)
+            .append("Nothing here to pick up.")
+            .log(),
         Some(item) => {
             let mut pickup = ecs.write_storage::<WantsToPickupItem>();
             pickup
@@ -165,13 +220,30 @@ pub fn try_next_level(ecs: &mut World) -> bool {
     } else {
         let mut gamelog = ecs.fetch_mut::<GameLog>();
         gamelog
-            .entries
-            .push("There is no way down from here.".to_string());
+            .entry(LogCategory::System)
+            .append("There is no way down from here.")
+            .log();
         false
     }
 }
 
-fn skip_turn(ecs: &mut World) -> RunState {
+pub fn try_previous_level(ecs: &mut World) -> bool {
+    let player_pos = ecs.fetch::<Point>();
+    let map = ecs.fetch::<Map>();
+    let player_idx = map.xy_idx(player_pos.x, player_pos.y);
+    if map.tiles[player_idx] == TileType::UpStairs {
+        true
+    } else {
+        let mut gamelog = ecs.fetch_mut::<GameLog>();
+        gamelog
+            .entry(LogCategory::System)
+            .append("There is no way up from here.")
+            .log();
+        false
+    }
+}
+
+fn skip_turn(ecs: &mut World) {
     let player_entity = ecs.fetch::<Entity>();
     let viewshed_component = ecs.read_storage::<Viewshed>();
     let monsters = ecs.read_storage::<Monster>();
@@ -183,7 +255,7 @@ fn skip_turn(ecs: &mut World) -> RunState {
         let idx = worldmap_resource.xy_idx(tile.x, tile.y);
         for entity_id in worldmap_resource.tile_content[idx].iter() {
             let mob = monsters.get(*entity_id);
-            if let Some(_) = mob {
+            if mob.is_some() {
                 can_heal = false;
             }
         }
@@ -194,6 +266,4 @@ fn skip_turn(ecs: &mut World) -> RunState {
         let player_hp = health_components.get_mut(*player_entity).unwrap();
         player_hp.hp = i32::min(player_hp.hp + 1, player_hp.max_hp);
     }
-
-    RunState::PlayerTurn
 }
@@ -0,0 +1,19 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize, Debug)]
+pub struct FactionInfo {
+    pub name: String,
+    #[serde(skip)]
+    pub source: String,
+    /// How members treat each other faction, with "Default" covering any not listed.
+    pub responses: HashMap<String, Reaction>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Copy, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum Reaction {
+    Attack,
+    Ignore,
+    Flee,
+}
@@ -0,0 +1,153 @@
+use super::loader::{Template, TemplateKind, Templates};
+use super::{Item, Mob, Prop, RawProblem, Raws};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// Builds the raws from the merged templates, filling in what each inherits through
+/// `"extends"`. Templates marked `"abstract": true` only exist to be extended, so they are
+/// left out, as is anything whose inheritance can't be worked out.
+pub fn resolve(templates: Templates, problems: &mut Vec<RawProblem>) -> Raws {
+    let mut raws = Raws::default();
+    for (source, mut item) in resolve_section::<Item>(&templates, TemplateKind::Item, problems) {
+        item.source = source;
+        raws.items.push(item);
+    }
+    for (source, mut mob) in resolve_section::<Mob>(&templates, TemplateKind::Mob, problems) {
+        mob.source = source;
+        raws.mobs.push(mob);
+    }
+    for (source, mut prop) in resolve_section::<Prop>(&templates, TemplateKind::Prop, problems) {
+        prop.source = source;
+        raws.props.push(prop);
+    }
+
+    let abstract_names: Vec<String> = [TemplateKind::Item, TemplateKind::Mob, TemplateKind::Prop]
+        .iter()
+        .flat_map(|kind| templates.section(*kind))
+        .filter(|template| is_abstract(&template.value))
+        .map(|template| template.name.clone())
+        .collect();
+    for spawn in templates.spawn_table {
+        if abstract_names.contains(&spawn.name) {
+            problems.push(RawProblem {
+                source: spawn.source.clone(),
+                entity: format!("Spawn table entry \"{}\"", spawn.name),
+                field: "name".to_string(),
+                message: "is an abstract template, which can't be spawned".to_string(),
+            });
+            continue;
+        }
+        raws.spawn_table.push(spawn);
+    }
+    raws.loot_tables = templates.loot_tables;
+    raws.factions = templates.factions;
+    raws
+}
+
+fn is_abstract(value: &Value) -> bool {
+    value
+        .get("abstract")
+        .and_then(|flag| flag.as_bool())
+        .unwrap_or(false)
+}
+
+/// The concrete templates of one kind, with their sources, as `T`.
+fn resolve_section<T: DeserializeOwned>(
+    templates: &Templates,
+    kind: TemplateKind,
+    problems: &mut Vec<RawProblem>,
+) -> Vec<(String, T)> {
+    let section = templates.section(kind);
+    let mut resolved = Vec::new();
+    for template in section.iter() {
+        let entity = format!("{} \"{}\"", kind.label(), template.name);
+        let value = match inherited_value(section, kind, template) {
+            Ok(value) => value,
+            Err(message) => {
+                problems.push(RawProblem {
+                    source: template.source.clone(),
+                    entity,
+                    field: "extends".to_string(),
+                    message,
+                });
+                continue;
+            }
+        };
+        if is_abstract(&template.value) {
+            continue;
+        }
+        match serde_json::from_value::<T>(value) {
+            Ok(entry) => resolved.push((template.source.clone(), entry)),
+            Err(e) => problems.push(RawProblem {
+                source: template.source.clone(),
+                entity,
+                field: "definition".to_string(),
+                message: e.to_string(),
+            }),
+        }
+    }
+    resolved
+}
+
+/// The template with everything it inherits filled in. Each field comes from the nearest
+/// template up the `extends` chain that sets it; objects, like `stats` or an effects list,
+/// are merged field by field rather than replaced whole.
+fn inherited_value(
+    section: &[Template],
+    kind: TemplateKind,
+    template: &Template,
+) -> Result<Value, String> {
+    let mut chain: Vec<&Template> = vec![template];
+    let mut current = template;
+    while let Some(parent) = current.value.get("extends") {
+        let parent_name = parent
+            .as_str()
+            .ok_or_else(|| format!("{} should be the name of a template", parent))?;
+        if chain.iter().any(|ancestor| ancestor.name == parent_name) {
+            let mut names: Vec<&str> = chain.iter().map(|t| t.name.as_str()).collect();
+            names.push(parent_name);
+            return Err(format!("inheritance cycle: {}", names.join(" -> ")));
+        }
+        current = section
+            .iter()
+            .find(|t| t.name == parent_name)
+            .ok_or_else(|| {
+                format!(
+                    "there is no {} called \"{}\" to extend",
+                    kind.label().to_lowercase(),
+                    parent_name
+                )
+            })?;
+        chain.push(current);
+    }
+
+    let mut value = Value::Object(Map::new());
+    for ancestor in chain.iter().rev() {
+        overlay(&mut value, &ancestor.value);
+    }
+    // Being a template isn't inherited: whatever extends one is concrete unless it says so
+    if let Value::Object(fields) = &mut value {
+        fields.remove("extends");
+        fields.remove("abstract");
+    }
+    Ok(value)
+}
+
+/// Writes the fields of `top` over `base`, merging objects present in both.
+fn overlay(base: &mut Value, top: &Value) {
+    match (base, top) {
+        (Value::Object(base_fields), Value::Object(top_fields)) => {
+            for (key, top_value) in top_fields {
+                match base_fields.get_mut(key) {
+                    Some(base_value) if base_value.is_object() && top_value.is_object() => {
+                        overlay(base_value, top_value)
+                    }
+                    _ => {
+                        base_fields.insert(key.clone(), top_value.clone());
+                    }
+                }
+            }
+        }
+        (base, top) => *base = top.clone(),
+    }
+}
@@ -0,0 +1,260 @@
+use super::{FactionInfo, LootTable, RawProblem, RawsError, SpawnTableEntry};
+use crate::settings::Settings;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where the raws come from: the game's own set, then each mod directory in turn. Every
+/// `.json` file in a directory is read, in file name order.
+#[derive(Clone, Default, Debug)]
+pub struct RawSources {
+    /// The game's raws on disk, in development mode; otherwise the copy built into the game.
+    pub base_dir: Option<PathBuf>,
+    pub mod_dirs: Vec<PathBuf>,
+}
+
+impl RawSources {
+    pub fn from_settings(settings: &Settings) -> RawSources {
+        RawSources {
+            base_dir: settings.raws_dir.as_ref().map(PathBuf::from),
+            mod_dirs: settings.mod_dirs.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    /// The directories read from disk.
+    pub fn dirs(&self) -> impl Iterator<Item = &Path> {
+        self.base_dir
+            .iter()
+            .chain(self.mod_dirs.iter())
+            .map(|dir| dir.as_path())
+    }
+}
+
+/// One raws file as written. Every section is optional. `delete` names entries from earlier
+/// files to take out before this file's own are merged in; it covers every kind of entry,
+/// loot tables and factions included. Items, mobs and props stay as JSON until every file is
+/// merged, since they may extend templates from other files.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct RawFile {
+    pub items: Vec<Value>,
+    pub mobs: Vec<Value>,
+    pub props: Vec<Value>,
+    pub spawn_table: Vec<SpawnTableEntry>,
+    pub loot_tables: Vec<LootTable>,
+    pub factions: Vec<FactionInfo>,
+    pub delete: Vec<String>,
+}
+
+/// An item, mob or prop definition as written, before inheritance is resolved.
+pub struct Template {
+    pub name: String,
+    pub source: String,
+    pub value: Value,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub enum TemplateKind {
+    Item,
+    Mob,
+    Prop,
+}
+
+impl TemplateKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            TemplateKind::Item => "Item",
+            TemplateKind::Mob => "Mob",
+            TemplateKind::Prop => "Prop",
+        }
+    }
+}
+
+/// Every raws file merged together, with items, mobs and props still as written.
+#[derive(Default)]
+pub struct Templates {
+    pub items: Vec<Template>,
+    pub mobs: Vec<Template>,
+    pub props: Vec<Template>,
+    pub spawn_table: Vec<SpawnTableEntry>,
+    pub loot_tables: Vec<LootTable>,
+    pub factions: Vec<FactionInfo>,
+}
+
+/// The `.json` files in `dir` as `(path, contents)`, in the order they are merged.
+pub fn read_raw_dir(dir: &Path) -> Result<Vec<(String, String)>, RawsError> {
+    let entries = fs::read_dir(dir).map_err(|e| RawsError::Io(dir.display().to_string(), e))?;
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    let mut files = Vec::new();
+    for path in paths {
+        let source = path.display().to_string();
+        let raw_string = fs::read_to_string(&path).map_err(|e| RawsError::Io(source.clone(), e))?;
+        files.push((source, raw_string));
+    }
+    Ok(files)
+}
+
+pub fn parse_raw_file(raw_string: &str, source: &str) -> Result<RawFile, RawsError> {
+    serde_json::from_str(raw_string).map_err(|e| RawsError::Parse(source.to_string(), e))
+}
+
+impl Templates {
+    pub fn section(&self, kind: TemplateKind) -> &Vec<Template> {
+        match kind {
+            TemplateKind::Item => &self.items,
+            TemplateKind::Mob => &self.mobs,
+            TemplateKind::Prop => &self.props,
+        }
+    }
+
+    fn section_mut(&mut self, kind: TemplateKind) -> &mut Vec<Template> {
+        match kind {
+            TemplateKind::Item => &mut self.items,
+            TemplateKind::Mob => &mut self.mobs,
+            TemplateKind::Prop => &mut self.props,
+        }
+    }
+
+    /// Merges a file over what has been loaded so far. An entry replaces an earlier one with
+    /// the same name where it stood, so overriding doesn't reorder the spawn table.
+    pub fn merge(&mut self, file: RawFile, source: &str, problems: &mut Vec<RawProblem>) {
+        for name in file.delete.iter() {
+            let removed = self.remove_entity(name)
+                | remove_named(&mut self.spawn_table, name, |e| &e.name)
+                | remove_named(&mut self.loot_tables, name, |t| &t.name)
+                | remove_named(&mut self.factions, name, |f| &f.name);
+            if !removed {
+                problems.push(RawProblem {
+                    source: source.to_string(),
+                    entity: format!("\"{}\"", name),
+                    field: "delete".to_string(),
+                    message: "there is nothing by that name to delete".to_string(),
+                });
+            }
+        }
+
+        let mut defined: Vec<String> = Vec::new();
+        let sections = [
+            (TemplateKind::Item, file.items),
+            (TemplateKind::Mob, file.mobs),
+            (TemplateKind::Prop, file.props),
+        ];
+        for (kind, entries) in sections {
+            for value in entries {
+                let name = match value.get("name").and_then(|name| name.as_str()) {
+                    Some(name) => name.to_string(),
+                    None => {
+                        problems.push(RawProblem {
+                            source: source.to_string(),
+                            entity: format!("An unnamed {}", kind.label().to_lowercase()),
+                            field: "name".to_string(),
+                            message: "every entry needs a name".to_string(),
+                        });
+                        continue;
+                    }
+                };
+                if defined.contains(&name) {
+                    problems.push(RawProblem {
+                        source: source.to_string(),
+                        entity: format!("{} \"{}\"", kind.label(), name),
+                        field: "name".to_string(),
+                        message: "is defined more than once in this file".to_string(),
+                    });
+                }
+                defined.push(name.clone());
+
+                if !self.section(kind).iter().any(|t| t.name == name) {
+                    self.remove_entity(&name);
+                }
+                let template = Template {
+                    name,
+                    source: source.to_string(),
+                    value,
+                };
+                replace_named(self.section_mut(kind), template, |t| &t.name);
+            }
+        }
+
+        merge_named(
+            &mut self.spawn_table,
+            file.spawn_table,
+            "Spawn table entry",
+            source,
+            problems,
+            |e| &e.name,
+            |e, source| e.source = source.to_string(),
+        );
+        merge_named(
+            &mut self.loot_tables,
+            file.loot_tables,
+            "Loot table",
+            source,
+            problems,
+            |t| &t.name,
+            |t, source| t.source = source.to_string(),
+        );
+        merge_named(
+            &mut self.factions,
+            file.factions,
+            "Faction",
+            source,
+            problems,
+            |f| &f.name,
+            |f, source| f.source = source.to_string(),
+        );
+    }
+
+    /// Takes the item, mob or prop called `name` out, returning whether there was one.
+    fn remove_entity(&mut self, name: &str) -> bool {
+        remove_named(&mut self.items, name, |t| &t.name)
+            | remove_named(&mut self.mobs, name, |t| &t.name)
+            | remove_named(&mut self.props, name, |t| &t.name)
+    }
+}
+
+/// Merges entries that aren't templates into `list` by name, noting where each came from.
+fn merge_named<T>(
+    list: &mut Vec<T>,
+    entries: Vec<T>,
+    kind: &str,
+    source: &str,
+    problems: &mut Vec<RawProblem>,
+    name: impl Fn(&T) -> &String,
+    set_source: impl Fn(&mut T, &str),
+) {
+    let mut seen: Vec<String> = Vec::new();
+    for mut entry in entries {
+        if seen.contains(name(&entry)) {
+            problems.push(RawProblem {
+                source: source.to_string(),
+                entity: format!("{} \"{}\"", kind, name(&entry)),
+                field: "name".to_string(),
+                message: "is defined more than once in this file".to_string(),
+            });
+        }
+        seen.push(name(&entry).clone());
+        set_source(&mut entry, source);
+        replace_named(list, entry, &name);
+    }
+}
+
+/// Puts `entry` in place of the one with the same name, or on the end if there isn't one.
+fn replace_named<T>(list: &mut Vec<T>, entry: T, name: impl Fn(&T) -> &String) {
+    match list.iter().position(|e| name(e) == name(&entry)) {
+        Some(idx) => list[idx] = entry,
+        None => list.push(entry),
+    }
+}
+
+fn remove_named<T>(list: &mut Vec<T>, target: &str, name: impl Fn(&T) -> &String) -> bool {
+    let before = list.len();
+    list.retain(|e| name(e) != target);
+    list.len() != before
+}
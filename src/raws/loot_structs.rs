@@ -0,0 +1,19 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct LootTable {
+    pub name: String,
+    #[serde(skip)]
+    pub source: String,
+    pub drops: Vec<LootDrop>,
+}
+
+/// One possible drop. The name "None" stands for dropping nothing, as in a `RandomTable`.
+#[derive(Deserialize, Debug)]
+pub struct LootDrop {
+    pub name: String,
+    pub weight: i32,
+    pub min_depth: Option<i32>,
+    pub max_depth: Option<i32>,
+    pub add_map_depth_to_weight: Option<bool>,
+}
@@ -4,10 +4,17 @@ use serde::Deserialize;
 #[derive(Deserialize, Debug)]
 pub struct Mob {
     pub name: String,
+    #[serde(skip)]
+    pub source: String,
     pub renderable: Option<Renderable>,
     pub blocks_tile: bool,
     pub stats: MobStats,
     pub vision_range: i32,
+    pub speed: Option<i32>,
+    pub loot_table: Option<String>,
+    pub faction: Option<String>,
+    /// How the mob behaves; mobs without one chase and melee.
+    pub ai: Option<MobAi>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -17,3 +24,23 @@ pub struct MobStats {
     pub power: i32,
     pub defense: i32,
 }
+
+/// Written as an object naming the behaviour in `type`, with any settings it takes alongside,
+/// like `{ "type": "ranged", "range": 6, "damage": 2, "min_distance": 3 }`.
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MobAi {
+    Melee,
+    Ranged {
+        range: i32,
+        damage: i32,
+        min_distance: i32,
+    },
+    Cowardly {
+        flee_below: i32,
+    },
+    Guard,
+    Bystander,
+    Vendor,
+    CarrionEater,
+}
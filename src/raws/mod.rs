@@ -1,4 +1,3 @@
-use serde::Deserialize;
 use std::sync::Mutex;
 mod rawmaster;
 pub use rawmaster::*;
@@ -10,6 +9,19 @@ mod prop_structs;
 use prop_structs::*;
 mod spawner_structs;
 use spawner_structs::*;
+mod loot_structs;
+use loot_structs::*;
+mod faction_structs;
+pub use faction_structs::Reaction;
+use faction_structs::*;
+mod inheritance;
+mod loader;
+pub use loader::RawSources;
+use loader::*;
+mod validation;
+pub use validation::{RawProblem, RawsError};
+mod watcher;
+pub use watcher::RawsWatcher;
 
 lazy_static! {
     pub static ref RAWS: Mutex<RawMaster> = Mutex::new(RawMaster::empty());
@@ -17,25 +29,59 @@ lazy_static! {
 
 rltk::embedded_resource!(RAW_FILE, "../../raws/spawns.json");
 
-#[derive(Deserialize, Debug)]
+/// Everything the raws define, merged from all the files they came from.
+#[derive(Default, Debug)]
 pub struct Raws {
     pub items: Vec<Item>,
     pub mobs: Vec<Mob>,
     pub props: Vec<Prop>,
     pub spawn_table: Vec<SpawnTableEntry>,
+    pub loot_tables: Vec<LootTable>,
+    pub factions: Vec<FactionInfo>,
 }
 
-pub fn load_raws() {
+/// Loads the raws into `RAWS` from `sources`, or says everything that is wrong with them.
+/// Nothing is loaded unless they are entirely valid.
+pub fn load_raws(sources: &RawSources) -> Result<(), RawsError> {
+    let mut files = Vec::new();
+    match &sources.base_dir {
+        Some(dir) => files.extend(read_raw_dir(dir)?),
+        None => files.push(("raws/spawns.json".to_string(), embedded_raws())),
+    }
+    for dir in sources.mod_dirs.iter() {
+        files.extend(read_raw_dir(dir)?);
+    }
+
+    let raws = merge_raws(&files)?;
+    RAWS.lock().unwrap().load(raws);
+    Ok(())
+}
+
+fn embedded_raws() -> String {
     rltk::link_resource!(RAW_FILE, "../../raws/spawns.json");
 
     let raw_data = rltk::embedding::EMBED
         .lock()
         .get_resource("../../raws/spawns.json".to_string())
         .unwrap();
-    let raw_string =
-        std::str::from_utf8(&raw_data).expect("Unable to convert RAW to a valid UTF-8 string.");
-
-    let decoder: Raws = serde_json::from_str(&raw_string).expect("Unable to parse JSON.");
+    std::str::from_utf8(raw_data)
+        .expect("Unable to convert RAW to a valid UTF-8 string.")
+        .to_string()
+}
 
-    RAWS.lock().unwrap().load(decoder);
+/// Parses raws files, given as `(source, JSON)` in the order they apply, merges each over the
+/// ones before it, resolves inheritance between templates and validates the result.
+pub fn merge_raws(files: &[(String, String)]) -> Result<Raws, RawsError> {
+    let mut templates = Templates::default();
+    let mut problems = Vec::new();
+    for (source, raw_string) in files {
+        let file = parse_raw_file(raw_string, source)?;
+        templates.merge(file, source, &mut problems);
+    }
+    let raws = inheritance::resolve(templates, &mut problems);
+    problems.extend(validation::validate(&raws));
+    if !problems.is_empty() {
+        return Err(RawsError::Invalid(problems));
+    }
+    Ok(raws)
 }
@@ -5,6 +5,8 @@ use std::collections::HashMap;
 #[derive(Deserialize, Debug)]
 pub struct Prop {
     pub name: String,
+    #[serde(skip)]
+    pub source: String,
     pub renderable: Option<Renderable>,
     pub blocks_tile: Option<bool>,
     pub blocks_visibility: Option<bool>,
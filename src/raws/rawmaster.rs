@@ -1,7 +1,11 @@
-use super::{super::RandomTable, Raws};
+use super::{super::RandomTable, MobAi, Raws, Reaction};
 use crate::components::*;
+use crate::initiative_system::NORMAL_SPEED;
 use specs::prelude::*;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+
+/// The faction the player belongs to, which the raws can name in their reactions.
+pub const PLAYER_FACTION: &str = "Player";
 
 pub enum SpawnType {
     AtPosition { x: i32, y: i32 },
@@ -12,57 +16,79 @@ pub struct RawMaster {
     item_index: HashMap<String, usize>,
     mob_index: HashMap<String, usize>,
     prop_index: HashMap<String, usize>,
+    loot_index: HashMap<String, usize>,
+    faction_index: HashMap<String, usize>,
 }
 
 impl RawMaster {
     pub fn empty() -> RawMaster {
         RawMaster {
-            raws: Raws {
-                items: Vec::new(),
-                mobs: Vec::new(),
-                props: Vec::new(),
-                spawn_table: Vec::new(),
-            },
+            raws: Raws::default(),
             item_index: HashMap::new(),
             mob_index: HashMap::new(),
             prop_index: HashMap::new(),
+            loot_index: HashMap::new(),
+            faction_index: HashMap::new(),
         }
     }
 
+    /// Replaces the templates with `raws` and rebuilds the indexes over them. Names are
+    /// unique across items, mobs and props once the raws files are merged.
     pub fn load(&mut self, raws: Raws) {
         self.raws = raws;
         self.item_index = HashMap::new();
-        let mut used_names: HashSet<String> = HashSet::new();
+        self.mob_index = HashMap::new();
+        self.prop_index = HashMap::new();
+        self.loot_index = HashMap::new();
+        self.faction_index = HashMap::new();
 
         for (i, item) in self.raws.items.iter().enumerate() {
-            if used_names.contains(&item.name) {
-                println!("WARNING - duplicate item name in raws: {}.", item.name);
-            }
             self.item_index.insert(item.name.clone(), i);
-            used_names.insert(item.name.clone());
         }
         for (i, mob) in self.raws.mobs.iter().enumerate() {
-            if used_names.contains(&mob.name) {
-                println!("WARNING - duplicate mob name in raws: {}.", mob.name);
-            }
             self.mob_index.insert(mob.name.clone(), i);
-            used_names.insert(mob.name.clone());
         }
         for (i, prop) in self.raws.props.iter().enumerate() {
-            if used_names.contains(&prop.name) {
-                println!("WARNING - duplicate prop name in raws: {}.", prop.name);
-            }
             self.prop_index.insert(prop.name.clone(), i);
-            used_names.insert(prop.name.clone());
         }
-        for spawn in self.raws.spawn_table.iter() {
-            if !used_names.contains(&spawn.name) {
-                println!(
-                    "WARNING - spawn table lists unspecified entity: {}",
-                    spawn.name
-                );
+        for (i, table) in self.raws.loot_tables.iter().enumerate() {
+            self.loot_index.insert(table.name.clone(), i);
+        }
+        for (i, faction) in self.raws.factions.iter().enumerate() {
+            self.faction_index.insert(faction.name.clone(), i);
+        }
+    }
+
+    /// The raws file the item, mob or prop called `name` was defined in.
+    pub fn source(&self, name: &str) -> Option<&str> {
+        if let Some(i) = self.item_index.get(name) {
+            return Some(&self.raws.items[*i].source);
+        }
+        if let Some(i) = self.mob_index.get(name) {
+            return Some(&self.raws.mobs[*i].source);
+        }
+        self.prop_index
+            .get(name)
+            .map(|i| self.raws.props[*i].source.as_str())
+    }
+
+    /// How many of the items, mobs and props each raws file defines.
+    pub fn source_counts(&self) -> Vec<(&str, usize)> {
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        let sources = self
+            .raws
+            .items
+            .iter()
+            .map(|item| &item.source)
+            .chain(self.raws.mobs.iter().map(|mob| &mob.source))
+            .chain(self.raws.props.iter().map(|prop| &prop.source));
+        for source in sources {
+            match counts.iter_mut().find(|(s, _)| *s == source) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((source, 1)),
             }
         }
+        counts
     }
 }
 
@@ -118,6 +144,18 @@ pub fn spawn_named_item(
                         });
                     }
                     "magic_mapping" => eb = eb.with(MagicMapper {}),
+                    "haste" => {
+                        eb = eb.with(SpeedModifier {
+                            percent: 200,
+                            duration: effect.1.parse::<i32>().unwrap(),
+                        });
+                    }
+                    "slow" => {
+                        eb = eb.with(SpeedModifier {
+                            percent: 50,
+                            duration: effect.1.parse::<i32>().unwrap(),
+                        });
+                    }
 
                     _ => {
                         println!(
@@ -173,7 +211,30 @@ pub fn spawn_named_mob(
             name: mob_template.name.clone(),
         });
 
-        eb = eb.with(Monster {});
+        // Only mobs out to get the player count as monsters, so townsfolk don't stop them resting
+        let hostile = mob_template.faction.as_ref().map_or(true, |faction| {
+            faction_reaction(raws, faction, PLAYER_FACTION) == Reaction::Attack
+        });
+        if hostile {
+            eb = eb.with(Monster {});
+        }
+        eb = match mob_template.ai.unwrap_or(MobAi::Melee) {
+            MobAi::Melee => eb.with(MeleeAi {}),
+            MobAi::Ranged {
+                range,
+                damage,
+                min_distance,
+            } => eb.with(RangedAi {
+                range,
+                damage,
+                min_distance,
+            }),
+            MobAi::Cowardly { flee_below } => eb.with(CowardlyAi { flee_below }),
+            MobAi::Guard => eb.with(GuardAi {}),
+            MobAi::Bystander => eb.with(BystanderAi {}),
+            MobAi::Vendor => eb.with(VendorAi {}),
+            MobAi::CarrionEater => eb.with(CarrionEaterAi {}),
+        };
         if mob_template.blocks_tile {
             eb = eb.with(BlocksTile {});
         }
@@ -188,6 +249,20 @@ pub fn spawn_named_mob(
             range: mob_template.vision_range,
             dirty: true,
         });
+        eb = eb.with(Energy {
+            current: 0,
+            speed: mob_template.speed.unwrap_or(NORMAL_SPEED),
+        });
+        if let Some(faction) = &mob_template.faction {
+            eb = eb.with(Faction {
+                name: faction.clone(),
+            });
+        }
+        if let Some(loot_table) = &mob_template.loot_table {
+            eb = eb.with(LootTable {
+                table: loot_table.clone(),
+            });
+        }
 
         return Some(eb.build());
     }
@@ -320,3 +395,46 @@ pub fn get_spawn_table_for_depth(raws: &RawMaster, depth: i32) -> RandomTable {
 
     rt
 }
+
+/// Rolls the loot table called `table` for something that died on `depth`, returning the
+/// item it drops, if any. Deeper levels open up drops with a `min_depth` and favour those
+/// that add the depth to their weight.
+pub fn get_item_drop(
+    raws: &RawMaster,
+    rng: &mut rltk::RandomNumberGenerator,
+    table: &str,
+    depth: i32,
+) -> Option<String> {
+    let loot_table = &raws.raws.loot_tables[*raws.loot_index.get(table)?];
+
+    let mut rt = RandomTable::new();
+    for drop in loot_table.drops.iter() {
+        if depth < drop.min_depth.unwrap_or(depth) || depth > drop.max_depth.unwrap_or(depth) {
+            continue;
+        }
+        let mut weight = drop.weight;
+        if drop.add_map_depth_to_weight.unwrap_or(false) {
+            weight += depth;
+        }
+        rt = rt.add(drop.name.clone(), weight);
+    }
+
+    let result = rt.roll(rng);
+    if result == "None" {
+        return None;
+    }
+    Some(result)
+}
+
+/// How members of the faction `mine` react to members of `theirs`. Factions that don't say
+/// otherwise ignore everyone.
+pub fn faction_reaction(raws: &RawMaster, mine: &str, theirs: &str) -> Reaction {
+    raws.faction_index
+        .get(mine)
+        .and_then(|i| {
+            let responses = &raws.raws.factions[*i].responses;
+            responses.get(theirs).or_else(|| responses.get("Default"))
+        })
+        .copied()
+        .unwrap_or(Reaction::Ignore)
+}
@@ -3,6 +3,8 @@ use serde::Deserialize;
 #[derive(Deserialize, Debug)]
 pub struct SpawnTableEntry {
     pub name: String,
+    #[serde(skip)]
+    pub source: String,
     pub weight: i32,
     pub min_depth: i32,
     pub max_depth: i32,
@@ -0,0 +1,304 @@
+use super::{MobAi, Raws, Renderable};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io;
+
+/// Consumable effects the spawner knows how to build, and whether each needs a numeric value.
+const CONSUMABLE_EFFECTS: &[(&str, bool)] = &[
+    ("provides_healing", true),
+    ("ranged", true),
+    ("damage", true),
+    ("area_of_effect", true),
+    ("confusion", true),
+    ("magic_mapping", false),
+    ("haste", true),
+    ("slow", true),
+];
+
+/// Entry trigger effects the spawner knows how to build, and whether each needs a numeric value.
+const ENTRY_TRIGGER_EFFECTS: &[(&str, bool)] = &[("damage", true), ("single_activation", false)];
+
+/// One thing wrong with the raws: which file, which entity, which field of it, and what is
+/// wrong.
+#[derive(Debug)]
+pub struct RawProblem {
+    pub source: String,
+    pub entity: String,
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for RawProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} ({}): {}",
+            self.source, self.entity, self.field, self.message
+        )
+    }
+}
+
+/// Why the raws couldn't be loaded. Printed as is when the game refuses to start.
+#[derive(Debug)]
+pub enum RawsError {
+    /// A raws file on disk couldn't be read.
+    Io(String, io::Error),
+    /// A raws file isn't valid JSON, or doesn't have the shape of the raws.
+    Parse(String, serde_json::Error),
+    /// The file parsed, but some entities can't be spawned as written.
+    Invalid(Vec<RawProblem>),
+}
+
+impl fmt::Display for RawsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RawsError::Io(path, e) => write!(f, "Unable to read {}: {}", path, e),
+            RawsError::Parse(path, e) => write!(f, "Unable to parse {}: {}", path, e),
+            RawsError::Invalid(problems) => {
+                write!(f, "The raws have {} problem(s):", problems.len())?;
+                for problem in problems {
+                    write!(f, "\n  {}", problem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for RawsError {}
+
+/// Checks everything the spawner would otherwise trip over mid-game, collecting every problem
+/// rather than stopping at the first.
+pub fn validate(raws: &Raws) -> Vec<RawProblem> {
+    let mut problems = Vec::new();
+    let mut report = |source: &str, entity: &str, field: String, message: String| {
+        problems.push(RawProblem {
+            source: source.to_string(),
+            entity: entity.to_string(),
+            field,
+            message,
+        })
+    };
+
+    for item in raws.items.iter() {
+        let entity = format!("Item \"{}\"", item.name);
+        if let Some(renderable) = &item.renderable {
+            check_renderable(&item.source, &entity, renderable, &mut report);
+        }
+        if let Some(consumable) = &item.consumable {
+            check_effects(
+                &item.source,
+                &entity,
+                "consumable.effects",
+                &consumable.effects,
+                CONSUMABLE_EFFECTS,
+                &mut report,
+            );
+        }
+    }
+
+    for mob in raws.mobs.iter() {
+        let entity = format!("Mob \"{}\"", mob.name);
+        if let Some(renderable) = &mob.renderable {
+            check_renderable(&mob.source, &entity, renderable, &mut report);
+        }
+        if let Some(faction) = &mob.faction {
+            if !raws.factions.iter().any(|f| f.name == *faction) {
+                report(
+                    &mob.source,
+                    &entity,
+                    "faction".to_string(),
+                    format!("there is no faction called \"{}\"", faction),
+                );
+            }
+        }
+        if let Some(loot_table) = &mob.loot_table {
+            if !raws
+                .loot_tables
+                .iter()
+                .any(|table| table.name == *loot_table)
+            {
+                report(
+                    &mob.source,
+                    &entity,
+                    "loot_table".to_string(),
+                    format!("there is no loot table called \"{}\"", loot_table),
+                );
+            }
+        }
+        match mob.ai {
+            Some(MobAi::Ranged {
+                range,
+                damage,
+                min_distance,
+            }) => {
+                if range < 1 {
+                    report(
+                        &mob.source,
+                        &entity,
+                        "ai.range".to_string(),
+                        format!("{} should be at least 1", range),
+                    );
+                }
+                if damage < 1 {
+                    report(
+                        &mob.source,
+                        &entity,
+                        "ai.damage".to_string(),
+                        format!("{} should be at least 1", damage),
+                    );
+                }
+                if min_distance < 0 || min_distance > range {
+                    report(
+                        &mob.source,
+                        &entity,
+                        "ai.min_distance".to_string(),
+                        format!(
+                            "{} should be between 0 and the range, {}",
+                            min_distance, range
+                        ),
+                    );
+                }
+            }
+            Some(MobAi::Cowardly { flee_below }) if !(1..=100).contains(&flee_below) => report(
+                &mob.source,
+                &entity,
+                "ai.flee_below".to_string(),
+                format!("{} should be a percentage from 1 to 100", flee_below),
+            ),
+            _ => {}
+        }
+    }
+
+    for prop in raws.props.iter() {
+        let entity = format!("Prop \"{}\"", prop.name);
+        if let Some(renderable) = &prop.renderable {
+            check_renderable(&prop.source, &entity, renderable, &mut report);
+        }
+        if let Some(entry_trigger) = &prop.entry_trigger {
+            check_effects(
+                &prop.source,
+                &entity,
+                "entry_trigger.effects",
+                &entry_trigger.effects,
+                ENTRY_TRIGGER_EFFECTS,
+                &mut report,
+            );
+        }
+    }
+
+    let names: HashSet<&str> = raws
+        .items
+        .iter()
+        .map(|item| item.name.as_str())
+        .chain(raws.mobs.iter().map(|mob| mob.name.as_str()))
+        .chain(raws.props.iter().map(|prop| prop.name.as_str()))
+        .collect();
+    for spawn in raws.spawn_table.iter() {
+        if !names.contains(spawn.name.as_str()) {
+            report(
+                &spawn.source,
+                &format!("Spawn table entry \"{}\"", spawn.name),
+                "name".to_string(),
+                "there is no item, mob or prop by that name".to_string(),
+            );
+        }
+    }
+
+    for table in raws.loot_tables.iter() {
+        for drop in table.drops.iter() {
+            if drop.name != "None" && !raws.items.iter().any(|item| item.name == drop.name) {
+                report(
+                    &table.source,
+                    &format!("Loot table \"{}\"", table.name),
+                    format!("drops.{}", drop.name),
+                    "there is no item by that name".to_string(),
+                );
+            }
+        }
+    }
+
+    for faction in raws.factions.iter() {
+        let mut others: Vec<&String> = faction.responses.keys().collect();
+        others.sort();
+        for other in others {
+            if other != "Default" && !raws.factions.iter().any(|f| f.name == *other) {
+                report(
+                    &faction.source,
+                    &format!("Faction \"{}\"", faction.name),
+                    format!("responses.{}", other),
+                    "there is no faction by that name".to_string(),
+                );
+            }
+        }
+    }
+
+    problems
+}
+
+fn check_renderable(
+    source: &str,
+    entity: &str,
+    renderable: &Renderable,
+    report: &mut impl FnMut(&str, &str, String, String),
+) {
+    let mut glyph = renderable.glyph.chars();
+    match (glyph.next(), glyph.next()) {
+        (Some(c), None) => {
+            if c != ' ' && rltk::to_cp437(c) == 0 {
+                report(
+                    source,
+                    entity,
+                    "renderable.glyph".to_string(),
+                    format!("'{}' is not a CP437 character", c),
+                );
+            }
+        }
+        _ => report(
+            source,
+            entity,
+            "renderable.glyph".to_string(),
+            format!("\"{}\" should be exactly one character", renderable.glyph),
+        ),
+    }
+    for (field, colour) in [("fg", &renderable.fg), ("bg", &renderable.bg)].iter() {
+        if rltk::RGB::from_hex(colour).is_err() {
+            report(
+                source,
+                entity,
+                format!("renderable.{}", field),
+                format!("\"{}\" is not a hex colour like \"#FF00FF\"", colour),
+            );
+        }
+    }
+}
+
+fn check_effects(
+    source: &str,
+    entity: &str,
+    field: &str,
+    effects: &HashMap<String, String>,
+    known: &[(&str, bool)],
+    report: &mut impl FnMut(&str, &str, String, String),
+) {
+    // Sorted so the report comes out the same way every time
+    let mut effects: Vec<(&String, &String)> = effects.iter().collect();
+    effects.sort();
+    for (key, value) in effects {
+        match known.iter().find(|(name, _)| name == key) {
+            None => report(
+                source,
+                entity,
+                format!("{}.{}", field, key),
+                "unknown effect".to_string(),
+            ),
+            Some((_, true)) if value.parse::<i32>().is_err() => report(
+                source,
+                entity,
+                format!("{}.{}", field, key),
+                format!("\"{}\" is not a whole number", value),
+            ),
+            _ => {}
+        }
+    }
+}
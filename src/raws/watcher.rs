@@ -0,0 +1,65 @@
+use super::{load_raws, RawSources, RawsError};
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// How often the raws directories are checked for changes.
+const POLL_INTERVAL_MS: f32 = 1000.0;
+
+/// Keeps an eye on the raws directories in development mode, reloading `RAWS` whenever a
+/// file in them changes. Entities spawned after a reload use the new templates; ones already
+/// in the world keep what they were built with.
+pub struct RawsWatcher {
+    sources: RawSources,
+    last_modified: Option<SystemTime>,
+    timer: f32,
+}
+
+impl RawsWatcher {
+    /// Watches the directories in `sources`, taking their current contents as already loaded.
+    pub fn new(sources: &RawSources) -> RawsWatcher {
+        RawsWatcher {
+            sources: sources.clone(),
+            last_modified: sources_last_modified(sources),
+            timer: 0.0,
+        }
+    }
+
+    /// Called every frame with the frame time. Once a second, reloads the raws if anything
+    /// in the directories has changed since last time and returns how that went.
+    pub fn poll(&mut self, frame_time_ms: f32) -> Option<Result<(), RawsError>> {
+        self.timer += frame_time_ms;
+        if self.timer < POLL_INTERVAL_MS {
+            return None;
+        }
+        self.timer = 0.0;
+
+        let modified = sources_last_modified(&self.sources);
+        if modified == self.last_modified {
+            return None;
+        }
+        // Remembered even if the reload fails, so a broken file is reported once rather than
+        // every second until it is fixed
+        self.last_modified = modified;
+        Some(load_raws(&self.sources))
+    }
+}
+
+fn sources_last_modified(sources: &RawSources) -> Option<SystemTime> {
+    sources.dirs().filter_map(last_modified).max()
+}
+
+/// The newest modification time of anything in `dir` or below it.
+fn last_modified(dir: &Path) -> Option<SystemTime> {
+    let mut newest = fs::metadata(dir).and_then(|meta| meta.modified()).ok();
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let modified = if path.is_dir() {
+            last_modified(&path)
+        } else {
+            entry.metadata().and_then(|meta| meta.modified()).ok()
+        };
+        newest = newest.max(modified);
+    }
+    newest
+}
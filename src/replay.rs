@@ -0,0 +1,314 @@
+use super::{
+    raws::RawsError, CombatStats, Command, GameLog, GameSeed, LogCategory, Map, Position, RunState,
+    Settings, State,
+};
+use rltk::Point;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+/// A `Command` as it is written to a replay file. Items are stored by entity id; since a
+/// seeded game allocates entities identically, the id resolves to the same item on replay.
+#[derive(Serialize, Deserialize, PartialEq, Copy, Clone, Debug)]
+pub enum RecordedCommand {
+    Move { delta_x: i32, delta_y: i32 },
+    Wait,
+    PickUp,
+    UseItem { item: u32, target: Option<Point> },
+    DropItem { item: u32 },
+    RemoveItem { item: u32 },
+    Descend,
+    Ascend,
+}
+
+impl RecordedCommand {
+    pub fn record(command: Command) -> RecordedCommand {
+        match command {
+            Command::Move { delta_x, delta_y } => RecordedCommand::Move { delta_x, delta_y },
+            Command::Wait => RecordedCommand::Wait,
+            Command::PickUp => RecordedCommand::PickUp,
+            Command::UseItem { item, target } => RecordedCommand::UseItem {
+                item: item.id(),
+                target,
+            },
+            Command::DropItem { item } => RecordedCommand::DropItem { item: item.id() },
+            Command::RemoveItem { item } => RecordedCommand::RemoveItem { item: item.id() },
+            Command::Descend => RecordedCommand::Descend,
+            Command::Ascend => RecordedCommand::Ascend,
+        }
+    }
+
+    pub fn resolve(&self, ecs: &World) -> Command {
+        let entities = ecs.entities();
+        match *self {
+            RecordedCommand::Move { delta_x, delta_y } => Command::Move { delta_x, delta_y },
+            RecordedCommand::Wait => Command::Wait,
+            RecordedCommand::PickUp => Command::PickUp,
+            RecordedCommand::UseItem { item, target } => Command::UseItem {
+                item: entities.entity(item),
+                target,
+            },
+            RecordedCommand::DropItem { item } => Command::DropItem {
+                item: entities.entity(item),
+            },
+            RecordedCommand::RemoveItem { item } => Command::RemoveItem {
+                item: entities.entity(item),
+            },
+            RecordedCommand::Descend => Command::Descend,
+            RecordedCommand::Ascend => Command::Ascend,
+        }
+    }
+}
+
+/// A fingerprint of the game state, used to check that a replay ended where the recording did.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Copy, Clone, Debug)]
+pub struct ReplayDigest {
+    pub depth: i32,
+    pub player_x: i32,
+    pub player_y: i32,
+    pub player_hp: i32,
+    pub entity_count: usize,
+    pub map_hash: u64,
+}
+
+impl ReplayDigest {
+    pub fn of(ecs: &World) -> ReplayDigest {
+        let map = ecs.fetch::<Map>();
+        let player_entity = ecs.fetch::<Entity>();
+        let positions = ecs.read_storage::<Position>();
+        let combat_stats = ecs.read_storage::<CombatStats>();
+
+        let (player_x, player_y) = positions
+            .get(*player_entity)
+            .map_or((0, 0), |pos| (pos.x, pos.y));
+        let player_hp = combat_stats.get(*player_entity).map_or(0, |stats| stats.hp);
+
+        // FNV-1a, so the hash is stable between builds
+        let mut map_hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for (tile, revealed) in map.tiles.iter().zip(map.revealed_tiles.iter()) {
+            for byte in [*tile as u8, *revealed as u8].iter() {
+                map_hash ^= *byte as u64;
+                map_hash = map_hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+
+        ReplayDigest {
+            depth: map.depth,
+            player_x,
+            player_y,
+            player_hp,
+            entity_count: ecs.entities().join().count(),
+            map_hash,
+        }
+    }
+}
+
+/// One line of a replay file.
+#[derive(Serialize, Deserialize, Debug)]
+pub enum ReplayEntry {
+    Start {
+        seed: u64,
+        /// The settings the game was started with; map size and start depth change the run.
+        #[serde(default)]
+        settings: Settings,
+    },
+    Command(RecordedCommand),
+    End(ReplayDigest),
+}
+
+/// Appends the commands of the current game to a replay file as they are played.
+pub struct ReplayRecorder {
+    writer: BufWriter<File>,
+}
+
+impl ReplayRecorder {
+    pub fn create(path: &str, seed: u64, settings: Settings) -> io::Result<ReplayRecorder> {
+        let file = File::create(path)?;
+        let mut recorder = ReplayRecorder {
+            writer: BufWriter::new(file),
+        };
+        recorder.write(&ReplayEntry::Start { seed, settings })?;
+        Ok(recorder)
+    }
+
+    pub fn record(&mut self, command: Command) -> io::Result<()> {
+        self.write(&ReplayEntry::Command(RecordedCommand::record(command)))
+    }
+
+    pub fn finish(mut self, ecs: &World) -> io::Result<()> {
+        self.write(&ReplayEntry::End(ReplayDigest::of(ecs)))
+    }
+
+    fn write(&mut self, entry: &ReplayEntry) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, entry)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+}
+
+/// Why a replay file couldn't be loaded.
+#[derive(Debug)]
+pub enum ReplayError {
+    /// The file couldn't be opened or read.
+    Io(io::Error),
+    /// A line of the file isn't a replay entry.
+    Parse { line: usize, reason: String },
+    /// The file has no start entry, so there is no seed to replay from.
+    MissingStart,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "Unable to read the replay file: {}", e),
+            ReplayError::Parse { line, reason } => {
+                write!(f, "The replay file is damaged at line {}: {}", line, reason)
+            }
+            ReplayError::MissingStart => write!(f, "The replay file has no start entry."),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReplayError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(e: io::Error) -> Self {
+        ReplayError::Io(e)
+    }
+}
+
+/// A recorded game, ready to be fed back through `State::play`.
+pub struct Replay {
+    pub seed: u64,
+    pub settings: Settings,
+    pub commands: Vec<RecordedCommand>,
+    pub expected: Option<ReplayDigest>,
+}
+
+impl Replay {
+    pub fn load(path: &str) -> Result<Replay, ReplayError> {
+        let file = File::open(path)?;
+        let mut seed = None;
+        let mut settings = Settings::default();
+        let mut commands = Vec::new();
+        let mut expected = None;
+
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: ReplayEntry =
+                serde_json::from_str(&line).map_err(|e| ReplayError::Parse {
+                    line: number + 1,
+                    reason: e.to_string(),
+                })?;
+            match entry {
+                ReplayEntry::Start {
+                    seed: start_seed,
+                    settings: start_settings,
+                } => {
+                    seed = Some(start_seed);
+                    settings = start_settings;
+                }
+                ReplayEntry::Command(command) => commands.push(command),
+                ReplayEntry::End(digest) => expected = Some(digest),
+            }
+        }
+
+        Ok(Replay {
+            seed: seed.ok_or(ReplayError::MissingStart)?,
+            settings,
+            commands,
+            expected,
+        })
+    }
+
+    /// Plays the whole replay without a window, returning the digest of the state it ends in.
+    /// Fails if the raws the replay was recorded with can't be loaded.
+    pub fn run(&self) -> Result<ReplayDigest, RawsError> {
+        let mut gs = State::with_settings(self.start_settings())?;
+        gs.run_until_input();
+        for recorded in self.commands.iter() {
+            if gs.runstate() == RunState::GameOver {
+                break;
+            }
+            let command = recorded.resolve(&gs.ecs);
+            gs.play(command);
+        }
+        Ok(ReplayDigest::of(&gs.ecs))
+    }
+
+    /// The settings to start a game with so that it plays out as recorded.
+    pub fn start_settings(&self) -> Settings {
+        Settings {
+            seed: Some(self.seed),
+            ..self.settings.clone()
+        }
+    }
+
+    /// True if `actual` matches the recorded final state, or if nothing was recorded to compare against.
+    pub fn verify(&self, actual: &ReplayDigest) -> bool {
+        match &self.expected {
+            None => true,
+            Some(expected) => expected == actual,
+        }
+    }
+}
+
+impl State {
+    /// Starts writing every submitted command to a replay file at `path`.
+    pub fn start_recording(&mut self, path: &str) -> io::Result<()> {
+        self.recorder = None;
+        let seed = self.ecs.fetch::<GameSeed>().seed;
+        let settings = (*self.ecs.fetch::<Settings>()).clone();
+        self.recorder = Some(ReplayRecorder::create(path, seed, settings)?);
+        Ok(())
+    }
+
+    /// Writes `command` to the replay file. If that fails the game carries on, unrecorded.
+    pub(crate) fn record(&mut self, command: Command) {
+        let result = match &mut self.recorder {
+            Some(recorder) => recorder.record(command),
+            None => return,
+        };
+        if let Err(e) = result {
+            self.recording_failed(e);
+        }
+    }
+
+    /// Closes the replay file, recording the current state so a replay can be verified against it.
+    pub fn finish_recording(&mut self) {
+        if let Some(recorder) = self.recorder.take() {
+            if let Err(e) = recorder.finish(&self.ecs) {
+                self.recording_failed(e);
+            }
+        }
+    }
+
+    /// Stops recording without marking an end state, for when the game stops being reproducible.
+    pub fn stop_recording(&mut self) {
+        self.recorder = None;
+    }
+
+    fn recording_failed(&mut self, e: io::Error) {
+        self.recorder = None;
+        self.ecs
+            .fetch_mut::<GameLog>()
+            .entry(LogCategory::System)
+            .colored(
+                rltk::RGB::named(rltk::RED),
+                format!("Stopped recording the replay: {}", e),
+            )
+            .log();
+    }
+}
@@ -5,6 +5,12 @@ rltk::embedded_resource!(WFC_POPULATED, "../resources/wfc-populated.xp");
 
 pub struct RexAssets {}
 
+impl Default for RexAssets {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RexAssets {
     pub fn new() -> RexAssets {
         rltk::link_resource!(WFC_DEMO_IMAGE1, "../resources/wfc-demo1.xp");
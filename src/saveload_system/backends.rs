@@ -0,0 +1,292 @@
+use super::super::settings::SaveFormat;
+use super::SaveError;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde_json::Value;
+use std::io::{Read, Write};
+
+/// Turns a save, already converted to a JSON value tree, into the bytes of a save file and
+/// back. Every backend must decode exactly what it encoded.
+pub trait SaveBackend: Sync {
+    /// The file extension of saves written by this backend, without the dot.
+    fn extension(&self) -> &'static str;
+    fn encode(&self, save: &Value) -> Result<Vec<u8>, SaveError>;
+    fn decode(&self, data: &[u8]) -> Result<Value, SaveError>;
+}
+
+pub fn backend(format: SaveFormat) -> &'static dyn SaveBackend {
+    match format {
+        SaveFormat::Json => &JsonBackend,
+        SaveFormat::Binary => &BinaryBackend,
+    }
+}
+
+pub static BACKENDS: [&dyn SaveBackend; 2] = [&JsonBackend, &BinaryBackend];
+
+/// The backend that writes files with `extension`, if any does.
+pub fn backend_for_extension(extension: &str) -> Option<&'static dyn SaveBackend> {
+    BACKENDS
+        .iter()
+        .find(|backend| backend.extension() == extension)
+        .copied()
+}
+
+/// Plain JSON, readable and diffable.
+pub struct JsonBackend;
+
+impl SaveBackend for JsonBackend {
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn encode(&self, save: &Value) -> Result<Vec<u8>, SaveError> {
+        serde_json::to_vec(save).map_err(|e| SaveError::Encode(e.to_string()))
+    }
+
+    /// Saves from before the header was added are a bare run of component lists rather than
+    /// a single value; those come back as an array of the lists.
+    fn decode(&self, data: &[u8]) -> Result<Value, SaveError> {
+        serde_json::from_slice(data).or_else(|_| {
+            serde_json::Deserializer::from_slice(data)
+                .into_iter::<Value>()
+                .collect::<Result<Vec<Value>, _>>()
+                .map(Value::Array)
+                .map_err(|e| SaveError::Parse(format!("This is not a Rogue save file: {}", e)))
+        })
+    }
+}
+
+/// A compact tagged binary encoding of the value tree, deflate-compressed. Arrays with long
+/// runs of the same value, like the map's tile vectors, are run-length encoded.
+pub struct BinaryBackend;
+
+const MAGIC: &[u8; 4] = b"RGSV";
+
+const TAG_NULL: u8 = 0;
+const TAG_FALSE: u8 = 1;
+const TAG_TRUE: u8 = 2;
+const TAG_UINT: u8 = 3;
+const TAG_NEG_INT: u8 = 4;
+const TAG_FLOAT: u8 = 5;
+const TAG_STRING: u8 = 6;
+const TAG_ARRAY: u8 = 7;
+const TAG_OBJECT: u8 = 8;
+const TAG_RLE_ARRAY: u8 = 9;
+
+impl SaveBackend for BinaryBackend {
+    fn extension(&self) -> &'static str {
+        "sav"
+    }
+
+    fn encode(&self, save: &Value) -> Result<Vec<u8>, SaveError> {
+        let mut raw = Vec::new();
+        write_value(&mut raw, save);
+
+        let mut encoder = DeflateEncoder::new(MAGIC.to_vec(), Compression::default());
+        encoder.write_all(&raw)?;
+        Ok(encoder.finish()?)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Value, SaveError> {
+        if !data.starts_with(MAGIC) {
+            return Err(SaveError::not_a_save());
+        }
+        let mut raw = Vec::new();
+        DeflateDecoder::new(&data[MAGIC.len()..])
+            .read_to_end(&mut raw)
+            .map_err(SaveError::damaged)?;
+
+        let mut reader = Reader { data: &raw, pos: 0 };
+        let value = reader.value(0)?;
+        if reader.pos != raw.len() {
+            return Err(SaveError::damaged("unexpected data after the save"));
+        }
+        Ok(value)
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn write_string(out: &mut Vec<u8>, s: &str) {
+    write_varint(out, s.len() as u64);
+    out.extend_from_slice(s.as_bytes());
+}
+
+/// Groups an array into runs of equal values.
+fn runs(items: &[Value]) -> Vec<(usize, &Value)> {
+    let mut runs: Vec<(usize, &Value)> = Vec::new();
+    for item in items {
+        match runs.last_mut() {
+            Some((count, value)) if *value == item => *count += 1,
+            _ => runs.push((1, item)),
+        }
+    }
+    runs
+}
+
+fn write_value(out: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Null => out.push(TAG_NULL),
+        Value::Bool(false) => out.push(TAG_FALSE),
+        Value::Bool(true) => out.push(TAG_TRUE),
+        Value::Number(n) => {
+            if let Some(n) = n.as_u64() {
+                out.push(TAG_UINT);
+                write_varint(out, n);
+            } else if let Some(n) = n.as_i64() {
+                out.push(TAG_NEG_INT);
+                write_varint(out, !(n as u64));
+            } else {
+                out.push(TAG_FLOAT);
+                out.extend_from_slice(&n.as_f64().unwrap().to_le_bytes());
+            }
+        }
+        Value::String(s) => {
+            out.push(TAG_STRING);
+            write_string(out, s);
+        }
+        Value::Array(items) => {
+            let runs = runs(items);
+            if runs.len() * 2 <= items.len() {
+                out.push(TAG_RLE_ARRAY);
+                write_varint(out, runs.len() as u64);
+                for (count, item) in runs {
+                    write_varint(out, count as u64);
+                    write_value(out, item);
+                }
+            } else {
+                out.push(TAG_ARRAY);
+                write_varint(out, items.len() as u64);
+                for item in items {
+                    write_value(out, item);
+                }
+            }
+        }
+        Value::Object(fields) => {
+            out.push(TAG_OBJECT);
+            write_varint(out, fields.len() as u64);
+            for (key, field) in fields {
+                write_string(out, key);
+                write_value(out, field);
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn damaged() -> SaveError {
+        SaveError::damaged("it ends too early")
+    }
+
+    fn byte(&mut self) -> Result<u8, SaveError> {
+        let byte = *self.data.get(self.pos).ok_or_else(Reader::damaged)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], SaveError> {
+        let end = self.pos.checked_add(len).ok_or_else(Reader::damaged)?;
+        let bytes = self.data.get(self.pos..end).ok_or_else(Reader::damaged)?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn varint(&mut self) -> Result<u64, SaveError> {
+        let mut n: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            n |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(SaveError::damaged("a number is too long"))
+    }
+
+    /// A length prefix, checked against what is left so a damaged file can't ask for a
+    /// huge allocation.
+    fn len(&mut self) -> Result<usize, SaveError> {
+        let len = self.varint()? as usize;
+        if len > self.data.len() - self.pos {
+            return Err(Reader::damaged());
+        }
+        Ok(len)
+    }
+
+    fn string(&mut self) -> Result<String, SaveError> {
+        let len = self.len()?;
+        String::from_utf8(self.bytes(len)?.to_vec())
+            .map_err(|_| SaveError::damaged("a string is not UTF-8"))
+    }
+
+    /// The next value, nested `depth` arrays and objects deep.
+    fn value(&mut self, depth: usize) -> Result<Value, SaveError> {
+        if depth > MAX_DEPTH {
+            return Err(SaveError::damaged("values are nested too deeply"));
+        }
+        Ok(match self.byte()? {
+            TAG_NULL => Value::Null,
+            TAG_FALSE => Value::Bool(false),
+            TAG_TRUE => Value::Bool(true),
+            TAG_UINT => Value::from(self.varint()?),
+            TAG_NEG_INT => Value::from(!self.varint()? as i64),
+            TAG_FLOAT => {
+                let mut bytes = [0; 8];
+                bytes.copy_from_slice(self.bytes(8)?);
+                serde_json::Number::from_f64(f64::from_le_bytes(bytes))
+                    .map_or(Value::Null, Value::Number)
+            }
+            TAG_STRING => Value::String(self.string()?),
+            TAG_ARRAY => {
+                let len = self.len()?;
+                let mut items = Vec::with_capacity(len);
+                for _ in 0..len {
+                    items.push(self.value(depth + 1)?);
+                }
+                Value::Array(items)
+            }
+            TAG_RLE_ARRAY => {
+                let run_count = self.len()?;
+                let mut items = Vec::new();
+                for _ in 0..run_count {
+                    let count = self.varint()? as usize;
+                    let item = self.value(depth + 1)?;
+                    if count > MAX_RLE_ITEMS - items.len() {
+                        return Err(SaveError::damaged("an array is too long"));
+                    }
+                    items.extend(std::iter::repeat(item).take(count));
+                }
+                Value::Array(items)
+            }
+            TAG_OBJECT => {
+                let len = self.len()?;
+                let mut fields = serde_json::Map::new();
+                for _ in 0..len {
+                    let key = self.string()?;
+                    fields.insert(key, self.value(depth + 1)?);
+                }
+                Value::Object(fields)
+            }
+            tag => return Err(SaveError::damaged(format!("unknown tag {}", tag))),
+        })
+    }
+}
+
+/// Deeper than any save nests, so a damaged file can't overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+/// More items than any map could have tiles, to stop a damaged run length from exhausting
+/// memory.
+const MAX_RLE_ITEMS: usize = 1 << 24;
@@ -0,0 +1,91 @@
+use super::{
+    delete_save, load_game, read_metadata, save_dir, slot_files, write_game, ActiveSlot, SaveError,
+    SaveSlot,
+};
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+
+/// Left in the save directory while a game is running, naming the slot it checkpoints to.
+/// It is removed when the game is left cleanly, so finding it at startup means the last
+/// session ended abnormally.
+const SESSION_FILE: &str = "session.lock";
+
+/// The slot autosaves go to. It is kept apart from the player's own slots, so a checkpoint
+/// never replaces a save they made, and is left out of the load menu.
+pub const CHECKPOINT_SLOT: &str = "session.checkpoint";
+
+/// What `SESSION_FILE` records about the running game.
+#[derive(Serialize, Deserialize)]
+struct Session {
+    checkpoint: String,
+    /// The player's own slot for the game, if it has been saved there.
+    slot: Option<String>,
+}
+
+fn session_path(ecs: &World) -> PathBuf {
+    save_dir(ecs).join(SESSION_FILE)
+}
+
+fn read_session(ecs: &World) -> Option<Session> {
+    let session = fs::read_to_string(session_path(ecs)).ok()?;
+    serde_json::from_str(&session).ok()
+}
+
+/// Saves the game to the checkpoint slot without leaving it, and notes the checkpoint to
+/// recover from should the game not shut down cleanly. The game's own slot is untouched.
+pub fn autosave(ecs: &mut World) -> Result<(), SaveError> {
+    write_game(ecs, CHECKPOINT_SLOT)?;
+    let session = Session {
+        checkpoint: CHECKPOINT_SLOT.to_string(),
+        slot: ecs.fetch::<ActiveSlot>().name.clone(),
+    };
+    let session = serde_json::to_string(&session).map_err(|e| SaveError::Encode(e.to_string()))?;
+    fs::write(session_path(ecs), session)?;
+    Ok(())
+}
+
+/// Records that the game was left cleanly, so there is nothing to recover next time, and
+/// throws away its checkpoint.
+pub fn end_session(ecs: &World) {
+    let checkpoint = read_session(ecs).map_or(CHECKPOINT_SLOT.to_string(), |s| s.checkpoint);
+    if let Err(e) = delete_save(ecs, &checkpoint) {
+        println!("WARNING: Unable to remove the checkpoint: {}", e);
+    }
+    let path = session_path(ecs);
+    if path.exists() {
+        if let Err(e) = fs::remove_file(&path) {
+            println!("WARNING: Unable to remove {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// The last checkpoint of a session that didn't end cleanly, if it is still there. It is
+/// named after the slot the game was being saved to.
+pub fn interrupted_session(ecs: &World) -> Option<SaveSlot> {
+    let session = match read_session(ecs) {
+        Some(session) if !slot_files(ecs, &session.checkpoint).is_empty() => session,
+        _ => {
+            end_session(ecs);
+            return None;
+        }
+    };
+    let metadata = read_metadata(ecs, &session.checkpoint);
+    let name = session.slot.unwrap_or_else(|| "Unsaved game".to_string());
+    Some(SaveSlot { name, metadata })
+}
+
+/// Picks the interrupted game up from its checkpoint. It goes on saving to the slot it was
+/// being saved to, if it had one.
+pub fn recover_session(ecs: &mut World) -> Result<(), SaveError> {
+    let session = read_session(ecs).ok_or_else(|| {
+        SaveError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "there is no interrupted session",
+        ))
+    })?;
+    load_game(ecs, &session.checkpoint)?;
+    ecs.write_resource::<ActiveSlot>().name = session.slot;
+    Ok(())
+}
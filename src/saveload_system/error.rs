@@ -0,0 +1,72 @@
+use std::fmt;
+use std::io;
+
+/// Why a game couldn't be saved or loaded. Shown to the player as is, so each variant reads
+/// as a sentence.
+#[derive(Debug)]
+pub enum SaveError {
+    /// The save file or directory couldn't be read, written or removed.
+    Io(io::Error),
+    /// The file isn't a save, or is damaged; the message says what was wrong with it.
+    Parse(String),
+    /// The game couldn't be turned into save data.
+    Encode(String),
+    /// The save has no `SerializationHelper`, so there is no map or game state to restore.
+    MissingHelper,
+    /// The save was written by a newer build with a format this one can't read.
+    VersionMismatch {
+        format_version: u32,
+        game_version: String,
+    },
+    /// The name picked for a new save can't be used; the message says why.
+    BadName(String),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "Unable to access the save file: {}", e),
+            SaveError::Parse(reason) => write!(f, "{}", reason),
+            SaveError::Encode(reason) => write!(f, "Unable to encode the save: {}", reason),
+            SaveError::MissingHelper => write!(f, "The save file has no game state in it."),
+            SaveError::VersionMismatch {
+                format_version,
+                game_version,
+            } => write!(
+                f,
+                "This save was made by Rogue {} (save format {}); this is Rogue {}, which reads save formats up to {}.",
+                game_version,
+                format_version,
+                super::GAME_VERSION,
+                super::SAVE_FORMAT_VERSION
+            ),
+            SaveError::BadName(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SaveError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
+
+impl SaveError {
+    /// A damaged save, with `detail` saying how.
+    pub(crate) fn damaged(detail: impl fmt::Display) -> SaveError {
+        SaveError::Parse(format!("The save file is damaged: {}", detail))
+    }
+
+    pub(crate) fn not_a_save() -> SaveError {
+        SaveError::Parse("This is not a Rogue save file.".to_string())
+    }
+}
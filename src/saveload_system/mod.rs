@@ -0,0 +1,696 @@
+use super::{
+    components::{saved_components, *},
+    damage_system::CORPSE_LIFETIME,
+    initiative_system::NORMAL_SPEED,
+    DungeonMaster, GameClock, GameLog, GameSeed, Map, Settings,
+};
+use rltk::RandomNumberGenerator;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specs::error::NoError;
+use specs::prelude::*;
+use specs::saveload::{
+    DeserializeComponents, MarkedBuilder, SerializeComponents, SimpleMarker, SimpleMarkerAllocator,
+};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+mod backends;
+mod checkpoint;
+mod error;
+pub use backends::{BinaryBackend, JsonBackend, SaveBackend};
+pub use checkpoint::{
+    autosave, end_session, interrupted_session, recover_session, CHECKPOINT_SLOT,
+};
+pub use error::SaveError;
+
+/// The layout of save files written by this build. Bump it whenever a saved component is
+/// removed or changes shape, and add a step to `migrate` that upgrades the old layout. Newly
+/// saved components don't need a bump, since a list missing from an older file loads empty.
+pub const SAVE_FORMAT_VERSION: u32 = 7;
+pub const GAME_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Where builds from before save slots kept their one save.
+pub const LEGACY_SAVE_PATH: &str = "./savegame.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SaveHeader {
+    pub format_version: u32,
+    pub game_version: String,
+}
+
+/// A summary of a saved game, shown in the load menu.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SaveMetadata {
+    pub character_name: String,
+    pub depth: i32,
+    pub turn: i32,
+    /// Seconds since the Unix epoch when the game was saved, or 0 if unknown.
+    pub timestamp: u64,
+    pub seed: u64,
+}
+
+/// What the load menu needs to know about a slot, kept in a small file beside the save so
+/// listing the slots doesn't mean reading every save in full.
+#[derive(Serialize, Deserialize)]
+struct SlotSummary {
+    header: SaveHeader,
+    metadata: SaveMetadata,
+}
+
+/// Everything in a save file: the header, then each component storage keyed by its type
+/// name, so storages can be added or reordered without breaking older files.
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    header: SaveHeader,
+    metadata: SaveMetadata,
+    components: serde_json::Map<String, Value>,
+}
+
+/// The save slot the current game writes to. `None` until the game is first saved or
+/// loaded, at which point it is given a slot of its own.
+#[derive(Clone, Debug, Default)]
+pub struct ActiveSlot {
+    pub name: Option<String>,
+}
+
+/// A save file in the save directory, with its metadata or the reason it can't be read.
+pub struct SaveSlot {
+    pub name: String,
+    pub metadata: Result<SaveMetadata, SaveError>,
+}
+
+macro_rules! serialize_individually {
+	($ecs:expr, $components:expr, $data:expr; $( $type:ty ),*) => {
+		$(
+		$components.insert(
+			stringify!($type).to_string(),
+			SerializeComponents::<NoError, SimpleMarker<SerializeMe>>::serialize(
+				&( $ecs.read_storage::<$type>(), ),
+				&$data.0,
+				&$data.1,
+				serde_json::value::Serializer,
+			)
+			.map_err(|e| SaveError::Encode(format!("bad {} data: {}", stringify!($type), e)))?,
+		);
+		)*
+	};
+}
+
+macro_rules! deserialize_individually {
+	($ecs:expr, $components:expr, $data:expr; $( $type:ty ),*) => {
+		$(
+		DeserializeComponents::<NoError, _>::deserialize(
+			&mut ( &mut $ecs.write_storage::<$type>(), ),
+			&mut $data.0, // entities
+			&mut $data.1, // marker
+			&mut $data.2, // allocater
+			$components
+				.remove(stringify!($type))
+				.unwrap_or_else(|| Value::Array(Vec::new())),
+		)
+		.map_err(|e| SaveError::Parse(format!("The save file has bad {} data: {}", stringify!($type), e)))?;
+		)*
+	};
+}
+
+/// Saves the game to its active slot, claiming a new slot if it doesn't have one yet. The
+/// slot's previous save is only replaced once the new one is completely written.
+pub fn save_game(ecs: &mut World) -> Result<(), SaveError> {
+    let slot = {
+        let mut active = ecs.write_resource::<ActiveSlot>();
+        active
+            .name
+            .get_or_insert_with(|| unused_slot_name(ecs))
+            .clone()
+    };
+    write_game(ecs, &slot)
+}
+
+/// Saves the game to `slot`, whatever slot the game itself is saved in.
+fn write_game(ecs: &mut World, slot: &str) -> Result<(), SaveError> {
+    fs::create_dir_all(save_dir(ecs))?;
+
+    // Create helper
+    let mapcopy = (*ecs.fetch::<Map>()).clone();
+    let seed = ecs.fetch::<GameSeed>().seed;
+    let rng = (*ecs.fetch::<RandomNumberGenerator>()).clone();
+    let turn = ecs.fetch::<GameClock>().turn;
+    let log = (*ecs.fetch::<GameLog>()).clone();
+    let dungeon = (*ecs.fetch::<DungeonMaster>()).clone();
+    let metadata = SaveMetadata {
+        character_name: ecs
+            .read_storage::<Name>()
+            .get(*ecs.fetch::<Entity>())
+            .map_or("Unknown".to_string(), |name| name.name.clone()),
+        depth: mapcopy.depth,
+        turn,
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs()),
+        seed,
+    };
+    let savehelper = ecs
+        .create_entity()
+        .with(SerializationHelper {
+            map: mapcopy,
+            seed,
+            rng,
+            turn,
+            log,
+            dungeon,
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+    let result = write_slot(ecs, slot, metadata);
+    ecs.delete_entity(savehelper).expect("Crash on cleanup");
+    result
+}
+
+fn write_slot(ecs: &World, slot: &str, metadata: SaveMetadata) -> Result<(), SaveError> {
+    {
+        let data = (
+            ecs.entities(),
+            ecs.read_storage::<SimpleMarker<SerializeMe>>(),
+        );
+
+        let header = SaveHeader {
+            format_version: SAVE_FORMAT_VERSION,
+            game_version: GAME_VERSION.to_string(),
+        };
+        let summary = SlotSummary {
+            header: header.clone(),
+            metadata: metadata.clone(),
+        };
+        let mut save = SaveFile {
+            header,
+            metadata,
+            components: serde_json::Map::new(),
+        };
+        saved_components!(serialize_individually!(ecs, save.components, data));
+
+        let backend = backends::backend(ecs.fetch::<Settings>().save_format);
+        let value = serde_json::to_value(&save).map_err(|e| SaveError::Encode(e.to_string()))?;
+        let data = backend.encode(&value)?;
+        let path = slot_path(ecs, slot, backend);
+        // Write beside the slot and rename over it, so a crash part way through leaves the
+        // old save intact
+        let temp = path.with_extension(format!("{}.tmp", backend.extension()));
+        fs::write(&temp, data)?;
+        fs::rename(&temp, &path)?;
+        // Switching formats shouldn't leave the slot's old file behind
+        for old in slot_files(ecs, slot).into_iter().filter(|old| *old != path) {
+            fs::remove_file(old)?;
+        }
+        let summary = serde_json::to_vec(&summary).map_err(|e| SaveError::Encode(e.to_string()))?;
+        fs::write(summary_path(ecs, slot), summary)?;
+    }
+    Ok(())
+}
+
+/// Replaces the world with the game saved in `slot`, upgrading older save formats first.
+/// A file that can't be parsed or upgraded, or has no game state, is rejected before the
+/// world is touched. If a component list turns out to be bad after that, the world is left
+/// half loaded and the caller should start over.
+pub fn load_game(ecs: &mut World, slot: &str) -> Result<(), SaveError> {
+    let mut save = read_slot(ecs, slot)?;
+    if present_components(&save.components, "SerializationHelper").is_empty() {
+        return Err(SaveError::MissingHelper);
+    }
+    ecs.write_resource::<ActiveSlot>().name = Some(slot.to_string());
+
+    {
+        // Delete everything
+        let mut to_delete = Vec::new();
+        for e in ecs.entities().join() {
+            to_delete.push(e);
+        }
+        for del in to_delete.iter() {
+            ecs.delete_entity(*del).expect("Deletion failed");
+        }
+    }
+
+    {
+        let mut d = (
+            &mut ecs.entities(),
+            &mut ecs.write_storage::<SimpleMarker<SerializeMe>>(),
+            &mut ecs.write_resource::<SimpleMarkerAllocator<SerializeMe>>(),
+        );
+
+        saved_components!(deserialize_individually!(ecs, save.components, d));
+    }
+
+    let mut deleteme: Option<Entity> = None;
+    {
+        let entities = ecs.entities();
+        let helper = ecs.read_storage::<SerializationHelper>();
+        let player = ecs.read_storage::<Player>();
+        let position = ecs.read_storage::<Position>();
+        for (e, h) in (&entities, &helper).join() {
+            let mut worldmap = ecs.write_resource::<super::map::Map>();
+            *worldmap = h.map.clone();
+            worldmap.tile_content = vec![Vec::new(); (worldmap.height * worldmap.width) as usize];
+            *ecs.write_resource::<GameSeed>() = GameSeed { seed: h.seed };
+            *ecs.write_resource::<RandomNumberGenerator>() = h.rng.clone();
+            *ecs.write_resource::<GameClock>() = GameClock { turn: h.turn };
+            *ecs.write_resource::<GameLog>() = h.log.clone();
+            *ecs.write_resource::<DungeonMaster>() = h.dungeon.clone();
+            deleteme = Some(e);
+        }
+        for (e, _p, pos) in (&entities, &player, &position).join() {
+            let mut ppos = ecs.write_resource::<rltk::Point>();
+            *ppos = rltk::Point::new(pos.x, pos.y);
+            let mut player_resource = ecs.write_resource::<Entity>();
+            *player_resource = e;
+        }
+    }
+    ecs.delete_entity(deleteme.ok_or(SaveError::MissingHelper)?)
+        .expect("Unable to delete helper");
+    Ok(())
+}
+
+/// Reads the save in `slot` with whichever backend wrote it.
+fn read_slot(ecs: &World, slot: &str) -> Result<SaveFile, SaveError> {
+    let path = slot_files(ecs, slot).into_iter().next().ok_or_else(|| {
+        SaveError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("there is no save called {}", slot),
+        ))
+    })?;
+    let backend = path
+        .extension()
+        .and_then(|ext| backends::backend_for_extension(ext.to_str()?))
+        .unwrap();
+    let data = fs::read(&path)?;
+    read_save(backend.decode(&data)?)
+}
+
+/// The metadata of the save in `slot`, from the summary beside it. Saves from before there
+/// were summaries are read in full instead.
+fn read_metadata(ecs: &World, slot: &str) -> Result<SaveMetadata, SaveError> {
+    let summary = fs::read(summary_path(ecs, slot))
+        .ok()
+        .and_then(|data| serde_json::from_slice::<SlotSummary>(&data).ok());
+    match summary {
+        Some(summary) if summary.header.format_version > SAVE_FORMAT_VERSION => {
+            Err(SaveError::VersionMismatch {
+                format_version: summary.header.format_version,
+                game_version: summary.header.game_version,
+            })
+        }
+        Some(summary) => Ok(summary.metadata),
+        None => read_slot(ecs, slot).map(|save| save.metadata),
+    }
+}
+
+/// Upgrades a decoded save of any supported format version to the current one.
+fn read_save(save: Value) -> Result<SaveFile, SaveError> {
+    let (version, save) = match save {
+        // Saves from before the header was added are a bare run of component lists
+        Value::Array(_) => (1, save),
+        save => {
+            let header: SaveHeader = save
+                .get("header")
+                .cloned()
+                .and_then(|header| serde_json::from_value(header).ok())
+                .ok_or_else(SaveError::not_a_save)?;
+            if header.format_version > SAVE_FORMAT_VERSION {
+                return Err(SaveError::VersionMismatch {
+                    format_version: header.format_version,
+                    game_version: header.game_version,
+                });
+            }
+            (header.format_version, save)
+        }
+    };
+
+    serde_json::from_value(migrate(save, version)?).map_err(SaveError::damaged)
+}
+
+/// Upgrades a save one format version at a time until it matches `SAVE_FORMAT_VERSION`.
+fn migrate(mut save: Value, mut version: u32) -> Result<Value, SaveError> {
+    while version < SAVE_FORMAT_VERSION {
+        save = match version {
+            1 => migrate_v1(save)?,
+            2 => migrate_v2(save)?,
+            3 => migrate_v3(save)?,
+            4 => migrate_v4(save)?,
+            5 => migrate_v5(save)?,
+            6 => migrate_v6(save)?,
+            _ => {
+                return Err(SaveError::Parse(format!(
+                    "Save format {} is not supported.",
+                    version
+                )))
+            }
+        };
+        version += 1;
+    }
+    Ok(save)
+}
+
+/// Component lists in the order the first, headerless save format wrote them.
+const V1_COMPONENTS: [&str; 35] = [
+    "Position",
+    "Renderable",
+    "Player",
+    "Viewshed",
+    "Monster",
+    "Name",
+    "BlocksTile",
+    "CombatStats",
+    "SufferDamage",
+    "WantsToMelee",
+    "Item",
+    "Consumable",
+    "Ranged",
+    "InflictsDamage",
+    "AreaOfEffect",
+    "Confusion",
+    "ProvidesHealing",
+    "InBackpack",
+    "WantsToPickupItem",
+    "WantsToUseItem",
+    "WantsToDropItem",
+    "SerializationHelper",
+    "Equippable",
+    "Equipped",
+    "MeleePowerBonus",
+    "DefenseBonus",
+    "WantsToRemoveItem",
+    "MagicMapper",
+    "EntityMoved",
+    "EntryTrigger",
+    "Hidden",
+    "SingleActivation",
+    "ParticleLifetime",
+    "Door",
+    "BlocksVisibility",
+];
+
+/// Names the positional component lists, drops the pending-damage queue that the effects
+/// system replaced, gives creatures the energy they now need to take turns, and fills in
+/// the game state the helper didn't used to carry.
+fn migrate_v1(save: Value) -> Result<Value, SaveError> {
+    let lists = match save {
+        Value::Array(lists) if lists.len() == V1_COMPONENTS.len() => lists,
+        _ => return Err(SaveError::not_a_save()),
+    };
+    let mut components: serde_json::Map<String, Value> = V1_COMPONENTS
+        .iter()
+        .map(|name| name.to_string())
+        .zip(lists)
+        .collect();
+    components.remove("SufferDamage");
+
+    let mut energy = Vec::new();
+    for name in ["Player", "Monster"].iter() {
+        for (marker, _) in present_components(&components, name) {
+            energy.push(serde_json::json!({
+                "marker": marker,
+                "components": [Energy { current: 0, speed: NORMAL_SPEED }],
+            }));
+        }
+    }
+    components.insert("Energy".to_string(), Value::Array(energy));
+
+    let rng = serde_json::to_value(RandomNumberGenerator::seeded(0)).unwrap();
+    let log = serde_json::to_value(GameLog::new()).unwrap();
+    if let Some(Value::Array(helpers)) = components.get_mut("SerializationHelper") {
+        for helper in helpers.iter_mut() {
+            if let Some(Value::Object(fields)) = helper.pointer_mut("/components/0") {
+                fields.insert("seed".to_string(), Value::from(0));
+                fields.insert("rng".to_string(), rng.clone());
+                fields.insert("turn".to_string(), Value::from(0));
+                fields.insert("log".to_string(), log.clone());
+            }
+        }
+    }
+
+    Ok(serde_json::json!({
+        "header": SaveHeader {
+            format_version: 2,
+            game_version: "unknown".to_string(),
+        },
+        "components": components,
+    }))
+}
+
+/// Adds the metadata the load menu shows, recovered from the saved player and helper. The
+/// time the game was saved wasn't recorded, so it is left unknown.
+fn migrate_v2(mut save: Value) -> Result<Value, SaveError> {
+    let components = save
+        .get("components")
+        .and_then(|components| components.as_object())
+        .ok_or_else(|| SaveError::Parse("The save file has no components.".to_string()))?;
+    let player_name = present_components(components, "Player")
+        .first()
+        .and_then(|(player, _)| {
+            present_components(components, "Name")
+                .into_iter()
+                .find(|(marker, _)| marker == player)
+        })
+        .and_then(|(_, name)| name.get("name")?.as_str().map(|name| name.to_string()));
+    let helper = present_components(components, "SerializationHelper")
+        .first()
+        .map(|(_, helper)| (*helper).clone())
+        .ok_or(SaveError::MissingHelper)?;
+
+    let metadata = SaveMetadata {
+        character_name: player_name.unwrap_or_else(|| "Unknown".to_string()),
+        depth: helper
+            .pointer("/map/depth")
+            .and_then(|d| d.as_i64())
+            .unwrap_or(0) as i32,
+        turn: helper.get("turn").and_then(|t| t.as_i64()).unwrap_or(0) as i32,
+        timestamp: 0,
+        seed: helper.get("seed").and_then(|s| s.as_u64()).unwrap_or(0),
+    };
+    save["metadata"] = serde_json::to_value(metadata).unwrap();
+    save["header"]["format_version"] = Value::from(3);
+    Ok(save)
+}
+
+/// Gives the helper an empty `DungeonMaster`: levels weren't kept before this format, so
+/// there are none to return to.
+fn migrate_v3(mut save: Value) -> Result<Value, SaveError> {
+    let dungeon = serde_json::to_value(DungeonMaster::default())
+        .map_err(|e| SaveError::Encode(e.to_string()))?;
+    if let Some(Value::Array(helpers)) = save.pointer_mut("/components/SerializationHelper") {
+        for helper in helpers.iter_mut() {
+            if let Some(Value::Object(fields)) = helper.pointer_mut("/components/0") {
+                fields.insert("dungeon".to_string(), dungeon.clone());
+            }
+        }
+    }
+    save["header"]["format_version"] = Value::from(4);
+    Ok(save)
+}
+
+/// Monsters used to share one chase-and-melee behaviour, which is now a component of its own,
+/// so every saved monster is given it.
+fn migrate_v4(mut save: Value) -> Result<Value, SaveError> {
+    let components = save
+        .get_mut("components")
+        .and_then(|components| components.as_object_mut())
+        .ok_or_else(|| SaveError::Parse("The save file has no components.".to_string()))?;
+    let melee: Vec<Value> = present_components(components, "Monster")
+        .into_iter()
+        .map(|(marker, _)| {
+            serde_json::json!({
+                "marker": marker,
+                "components": [MeleeAi {}],
+            })
+        })
+        .collect();
+    components.insert("MeleeAi".to_string(), Value::Array(melee));
+    save["header"]["format_version"] = Value::from(5);
+    Ok(save)
+}
+
+/// Corpses now rot away, so the ones already lying around are given a full lifetime from the
+/// turn the game was saved.
+fn migrate_v5(mut save: Value) -> Result<Value, SaveError> {
+    let turn = save
+        .pointer("/components/SerializationHelper/0/components/0/turn")
+        .and_then(|turn| turn.as_i64())
+        .unwrap_or(0) as i32;
+    let rots_at = Value::from(turn + CORPSE_LIFETIME);
+    if let Some(Value::Array(corpses)) = save.pointer_mut("/components/Corpse") {
+        for corpse in corpses.iter_mut() {
+            if let Some(Value::Object(fields)) = corpse.pointer_mut("/components/0") {
+                fields.insert("rots_at".to_string(), rots_at.clone());
+            }
+        }
+    }
+    save["header"]["format_version"] = Value::from(6);
+    Ok(save)
+}
+
+/// Intents and particles are no longer saved, since they don't outlast the turn.
+fn migrate_v6(mut save: Value) -> Result<Value, SaveError> {
+    let components = save
+        .get_mut("components")
+        .and_then(|components| components.as_object_mut())
+        .ok_or_else(|| SaveError::Parse("The save file has no components.".to_string()))?;
+    for name in [
+        "WantsToMelee",
+        "WantsToPickupItem",
+        "WantsToUseItem",
+        "WantsToRemoveItem",
+        "WantsToDropItem",
+        "EntityMoved",
+        "ParticleLifetime",
+    ]
+    .iter()
+    {
+        components.remove(*name);
+    }
+    save["header"]["format_version"] = Value::from(7);
+    Ok(save)
+}
+
+/// The marker and component of each entity that has a `name` component in a saved list.
+fn present_components<'a>(
+    components: &'a serde_json::Map<String, Value>,
+    name: &str,
+) -> Vec<(&'a Value, &'a Value)> {
+    components
+        .get(name)
+        .and_then(|list| list.as_array())
+        .map(|list| {
+            list.iter()
+                .filter_map(|entry| Some((entry.get("marker")?, entry.pointer("/components/0")?)))
+                .filter(|(_, component)| !component.is_null())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn save_dir(ecs: &World) -> PathBuf {
+    PathBuf::from(&ecs.fetch::<Settings>().save_dir)
+}
+
+fn slot_path(ecs: &World, slot: &str, backend: &dyn SaveBackend) -> PathBuf {
+    save_dir(ecs).join(format!("{}.{}", slot, backend.extension()))
+}
+
+fn summary_path(ecs: &World, slot: &str) -> PathBuf {
+    save_dir(ecs).join(format!("{}.meta", slot))
+}
+
+/// The files saved under `slot`, in any format.
+fn slot_files(ecs: &World, slot: &str) -> Vec<PathBuf> {
+    backends::BACKENDS
+        .iter()
+        .map(|backend| slot_path(ecs, slot, *backend))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// The first of "Save 1", "Save 2" and so on that isn't taken, offered as the name of a new
+/// save.
+pub fn unused_slot_name(ecs: &World) -> String {
+    (1..)
+        .map(|n| format!("Save {}", n))
+        .find(|name| slot_files(ecs, name).is_empty())
+        .unwrap()
+}
+
+/// Gives the game a slot of its own, called `name`, for `save_game` to write to. The name
+/// becomes a file name, so it is kept to letters, digits, spaces, `-` and `_`, and mustn't
+/// belong to another save.
+pub fn name_slot(ecs: &mut World, name: &str) -> Result<(), SaveError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(SaveError::BadName("The save needs a name.".to_string()));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-' || c == '_')
+    {
+        return Err(SaveError::BadName(
+            "Save names can only use letters, digits, spaces, '-' and '_'.".to_string(),
+        ));
+    }
+    if !slot_files(ecs, name).is_empty() {
+        return Err(SaveError::BadName(format!(
+            "There is already a save called {}.",
+            name
+        )));
+    }
+    ecs.write_resource::<ActiveSlot>().name = Some(name.to_string());
+    Ok(())
+}
+
+fn slot_names(ecs: &World) -> BTreeSet<String> {
+    let entries = match fs::read_dir(save_dir(ecs)) {
+        Ok(entries) => entries,
+        Err(_) => return BTreeSet::new(),
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(backends::backend_for_extension)
+                .is_some()
+        })
+        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+        .filter(|name| name != CHECKPOINT_SLOT)
+        .collect()
+}
+
+/// Moves a save left at `path` by a build from before save slots into the save directory, as
+/// a slot of its own, so it shows up in the load menu and is upgraded when loaded. Returns
+/// the new slot's name, or `None` if there was no old save to move.
+pub fn import_legacy_save(ecs: &World, path: &Path) -> Result<Option<String>, SaveError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    fs::create_dir_all(save_dir(ecs))?;
+    let slot = std::iter::once("Legacy save".to_string())
+        .chain((2..).map(|n| format!("Legacy save {}", n)))
+        .find(|name| slot_files(ecs, name).is_empty())
+        .unwrap();
+    let target = slot_path(ecs, &slot, &JsonBackend);
+    // The save directory may be on another file system, where a rename can't reach
+    if fs::rename(path, &target).is_err() {
+        fs::copy(path, &target)?;
+        fs::remove_file(path)?;
+    }
+    Ok(Some(slot))
+}
+
+pub fn does_save_exist(ecs: &World) -> bool {
+    !slot_names(ecs).is_empty()
+}
+
+/// Every slot in the save directory, most recently saved first.
+pub fn list_slots(ecs: &World) -> Vec<SaveSlot> {
+    let mut slots: Vec<SaveSlot> = slot_names(ecs)
+        .into_iter()
+        .map(|name| {
+            let metadata = read_metadata(ecs, &name);
+            SaveSlot { name, metadata }
+        })
+        .collect();
+    slots.sort_by(|a, b| {
+        let time = |slot: &SaveSlot| slot.metadata.as_ref().map_or(0, |m| m.timestamp);
+        time(b).cmp(&time(a)).then_with(|| a.name.cmp(&b.name))
+    });
+    slots
+}
+
+pub fn delete_save(ecs: &World, slot: &str) -> Result<(), SaveError> {
+    for path in slot_files(ecs, slot) {
+        fs::remove_file(&path)?;
+    }
+    let summary = summary_path(ecs, slot);
+    if summary.exists() {
+        fs::remove_file(summary)?;
+    }
+    Ok(())
+}
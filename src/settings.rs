@@ -0,0 +1,247 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// How save files are encoded.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Copy, Clone, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum SaveFormat {
+    Json,
+    /// Compressed, with the map's tile vectors run-length encoded.
+    Binary,
+}
+
+/// Options chosen at startup, read from a settings file and then overridden by command-line
+/// flags. Stored in the ECS as a resource so map generation and saving can read it.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+#[serde(default)]
+pub struct Settings {
+    /// Plays back each step of map generation before a level starts.
+    pub show_mapgen_visualizer: bool,
+    /// Window size in console cells; the 80x50 console is scaled to fit.
+    pub window_width: u32,
+    pub window_height: u32,
+    pub scanlines: bool,
+    pub map_width: i32,
+    pub map_height: i32,
+    /// The seed of the first game; a random one is picked if unset.
+    pub seed: Option<u64>,
+    pub start_depth: i32,
+    /// Where save slots are kept, one file per slot.
+    pub save_dir: String,
+    pub save_format: SaveFormat,
+    /// Turns between autosaves; 0 only autosaves when changing level.
+    pub autosave_interval: i32,
+    /// Where a morgue file is written when the player dies.
+    pub morgue_dir: String,
+    /// Development mode: read the raws from this directory instead of the built-in copy, and
+    /// reload them whenever a file in it changes.
+    pub raws_dir: Option<String>,
+    /// Mod directories whose raws are merged over the game's own, in order.
+    pub mod_dirs: Vec<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            show_mapgen_visualizer: true,
+            window_width: 160,
+            window_height: 100,
+            scanlines: true,
+            map_width: 80,
+            map_height: 50,
+            seed: None,
+            start_depth: 1,
+            save_dir: "./saves".to_string(),
+            save_format: SaveFormat::Json,
+            autosave_interval: 100,
+            morgue_dir: "./morgue".to_string(),
+            raws_dir: None,
+            mod_dirs: Vec::new(),
+        }
+    }
+}
+
+pub const MIN_MAP_WIDTH: i32 = 80;
+pub const MIN_MAP_HEIGHT: i32 = 50;
+
+const USAGE: &str = "Options:
+  --settings <path>        Settings file to read (default ./settings.json)
+  --seed <number>          Seed for the first game
+  --depth <number>         Depth to start on
+  --map-size <w>x<h>       Size of generated maps
+  --window <w>x<h>         Window size in console cells
+  --save-dir <path>        Directory to keep save slots in
+  --save-format <format>   json or binary
+  --autosave <turns>       Turns between autosaves, or 0 for level changes only
+  --morgue-dir <path>      Directory to write morgue files to on death
+  --raws-dir <path>        Read raws from disk and reload them when they change
+  --mod <path>             Merge the raws in a mod directory; may be repeated
+  --visualizer / --no-visualizer
+  --scanlines / --no-scanlines
+  --replay <path>          Play back a recorded game
+  --replay-delay <ms>      Time between replayed commands (default 250)
+  --verify                 With --replay, check the replay without a window and exit";
+
+impl Settings {
+    /// Reads the settings file at `path`, falling back to the defaults if it is missing or
+    /// unreadable. Fields the file leaves out keep their defaults.
+    pub fn load(path: &str) -> Settings {
+        if !Path::new(path).exists() {
+            return Settings::default();
+        }
+        match fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| serde_json::from_str(&data).map_err(|e| e.to_string()))
+        {
+            Ok(settings) => settings,
+            Err(e) => {
+                println!(
+                    "WARNING: Unable to read {}, using default settings: {}",
+                    path, e
+                );
+                Settings::default()
+            }
+        }
+    }
+
+    /// Loads the settings file named by `--settings` (or `default_path`) and applies the
+    /// other command-line flags on top of it, then checks the result. Flags this doesn't
+    /// know about are left for the caller.
+    pub fn from_args(args: &[String], default_path: &str) -> Result<Settings, String> {
+        let value = |flag: &str| flag_value(args, flag);
+        let has = |flag: &str| args.iter().any(|a| a == flag);
+
+        let mut settings = Settings::load(value("--settings")?.unwrap_or(default_path));
+
+        if let Some(seed) = value("--seed")? {
+            settings.seed = Some(parse_number(seed, "--seed")?);
+        }
+        if let Some(depth) = value("--depth")? {
+            settings.start_depth = parse_number(depth, "--depth")?;
+        }
+        if let Some(size) = value("--map-size")? {
+            let (width, height) = parse_size(size, "--map-size")?;
+            settings.map_width = width;
+            settings.map_height = height;
+        }
+        if let Some(size) = value("--window")? {
+            let (width, height) = parse_size(size, "--window")?;
+            settings.window_width = width;
+            settings.window_height = height;
+        }
+        if let Some(path) = value("--save-dir")? {
+            settings.save_dir = path.to_string();
+        }
+        if let Some(format) = value("--save-format")? {
+            settings.save_format = match format {
+                "json" => SaveFormat::Json,
+                "binary" => SaveFormat::Binary,
+                _ => {
+                    return Err(format!(
+                        "--save-format expects json or binary, got {}\n{}",
+                        format, USAGE
+                    ))
+                }
+            };
+        }
+        if let Some(path) = value("--morgue-dir")? {
+            settings.morgue_dir = path.to_string();
+        }
+        if let Some(path) = value("--raws-dir")? {
+            settings.raws_dir = Some(path.to_string());
+        }
+        for (i, _) in args.iter().enumerate().filter(|(_, a)| *a == "--mod") {
+            settings.mod_dirs.push(value_at(args, i)?.to_string());
+        }
+        if let Some(turns) = value("--autosave")? {
+            settings.autosave_interval = parse_number(turns, "--autosave")?;
+        }
+        if has("--visualizer") {
+            settings.show_mapgen_visualizer = true;
+        }
+        if has("--no-visualizer") {
+            settings.show_mapgen_visualizer = false;
+        }
+        if has("--scanlines") {
+            settings.scanlines = true;
+        }
+        if has("--no-scanlines") {
+            settings.scanlines = false;
+        }
+
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    /// Checks settings that the game can't start with, wherever they came from.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.start_depth < 1 {
+            return Err(format!(
+                "The start depth must be at least 1, got {}",
+                self.start_depth
+            ));
+        }
+        if self.autosave_interval < 0 {
+            return Err(format!(
+                "The autosave interval can't be negative, got {}",
+                self.autosave_interval
+            ));
+        }
+        // The town layout needs at least the original map size to fit its buildings
+        if self.map_width < MIN_MAP_WIDTH || self.map_height < MIN_MAP_HEIGHT {
+            return Err(format!(
+                "Maps must be at least {}x{}, got {}x{}",
+                MIN_MAP_WIDTH, MIN_MAP_HEIGHT, self.map_width, self.map_height
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The value given after `flag` in `args`, if the flag is there at all. A flag with nothing
+/// after it, or followed by another flag, is an error.
+pub fn flag_value<'a>(args: &'a [String], flag: &str) -> Result<Option<&'a str>, String> {
+    match args.iter().position(|a| a == flag) {
+        Some(i) => value_at(args, i).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// The number given after `flag` in `args`, if the flag is there at all.
+pub fn number_flag<T: std::str::FromStr>(args: &[String], flag: &str) -> Result<Option<T>, String> {
+    flag_value(args, flag)?
+        .map(|value| parse_number(value, flag))
+        .transpose()
+}
+
+/// The value following the flag at `args[i]`.
+fn value_at(args: &[String], i: usize) -> Result<&str, String> {
+    match args.get(i + 1) {
+        Some(value) if !value.starts_with("--") => Ok(value),
+        _ => Err(format!("{} expects a value\n{}", args[i], USAGE)),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str, flag: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} expects a number, got {}\n{}", flag, value, USAGE))
+}
+
+fn parse_size<T: std::str::FromStr>(value: &str, flag: &str) -> Result<(T, T), String> {
+    let invalid = || {
+        format!(
+            "{} expects <width>x<height>, got {}\n{}",
+            flag, value, USAGE
+        )
+    };
+    let mut parts = value.split('x');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(width), Some(height), None) => Ok((
+            width.parse().map_err(|_| invalid())?,
+            height.parse().map_err(|_| invalid())?,
+        )),
+        _ => Err(invalid()),
+    }
+}
@@ -1,10 +1,10 @@
 use super::{
-    raws::*, CombatStats, Map, Name, Player, Position, RandomTable, Rect, Renderable, SerializeMe,
-    TileType, Viewshed,
+    initiative_system::NORMAL_SPEED, raws::*, CombatStats, Energy, Faction, Map, Name, Player,
+    Position, RandomTable, Rect, Renderable, SerializeMe, TileType, Viewshed,
 };
 use rltk::{RandomNumberGenerator, RGB};
 use specs::{prelude::*, saveload::MarkedBuilder, saveload::SimpleMarker};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 const MAX_MONSTERS: i32 = 4;
 
@@ -36,6 +36,13 @@ pub fn player(ecs: &mut World, player_x: i32, player_y: i32) -> Entity {
             defense: 2,
             power: 5,
         })
+        .with(Energy {
+            current: 0,
+            speed: NORMAL_SPEED,
+        })
+        .with(Faction {
+            name: PLAYER_FACTION.to_string(),
+        })
         .marked::<SimpleMarker<SerializeMe>>()
         .build()
 }
@@ -70,7 +77,7 @@ pub fn spawn_region(
     spawn_list: &mut Vec<(usize, String)>,
 ) {
     let spawn_table = room_table(map_depth);
-    let mut spawn_points: HashMap<usize, String> = HashMap::new();
+    let mut spawn_points: BTreeMap<usize, String> = BTreeMap::new();
     let mut areas: Vec<usize> = Vec::from(area);
 
     {
@@ -108,8 +115,8 @@ pub fn spawn_entity(ecs: &mut World, spawn: &(&usize, &String)) {
 
     let spawn_result = spawn_named_entity(
         &RAWS.lock().unwrap(),
-        ecs.create_entity(),
-        &spawn.1,
+        ecs.create_entity().marked::<SimpleMarker<SerializeMe>>(),
+        spawn.1,
         SpawnType::AtPosition { x, y },
     );
     if spawn_result.is_some() {
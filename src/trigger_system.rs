@@ -1,6 +1,7 @@
 use super::{
-    EntityMoved, EntryTrigger, GameLog, Hidden, InflictsDamage, Map, Name, Position,
-    SingleActivation, SufferDamage,
+    effects::{EffectQueue, EffectType, Targets},
+    EntityMoved, EntryTrigger, GameLog, Hidden, LogCategory, Map, Name, Position, Renderable,
+    SingleActivation,
 };
 use specs::prelude::*;
 
@@ -16,9 +17,9 @@ impl<'a> System<'a> for TriggerSystem {
         ReadStorage<'a, Name>,
         Entities<'a>,
         WriteExpect<'a, GameLog>,
-        WriteStorage<'a, SufferDamage>,
+        WriteExpect<'a, EffectQueue>,
         ReadStorage<'a, SingleActivation>,
-        ReadStorage<'a, InflictsDamage>,
+        ReadStorage<'a, Renderable>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -31,9 +32,9 @@ impl<'a> System<'a> for TriggerSystem {
             names,
             entities,
             mut log,
-            mut suffer_damage,
+            mut effects,
             single_activation,
-            inflicts_damage,
+            renderables,
         ) = data;
         let mut remove_entities = Vec::new();
 
@@ -48,18 +49,22 @@ impl<'a> System<'a> for TriggerSystem {
                             let name_trigger = names.get(*entity_id);
                             let name = names.get(entity);
                             if let Some(name) = name {
-                                log.entries.push(format!(
-                                    "{} triggers the {}!",
-                                    &name.name,
-                                    &name_trigger.unwrap().name
-                                ));
+                                log.entry(LogCategory::Combat)
+                                    .name(&name.name, renderables.get(entity))
+                                    .append(" triggers the ")
+                                    .name(&name_trigger.unwrap().name, renderables.get(*entity_id))
+                                    .append("!")
+                                    .log();
                             }
                             hidden.remove(*entity_id);
 
-                            let damage = inflicts_damage.get(*entity_id);
-                            if let Some(damage) = damage {
-                                SufferDamage::new_damage(&mut suffer_damage, entity, damage.damage)
-                            }
+                            effects.add(
+                                Some(*entity_id),
+                                EffectType::TriggerFire {
+                                    trigger: *entity_id,
+                                },
+                                Targets::Single { target: entity },
+                            );
 
                             let sa = single_activation.get(*entity_id);
                             if let Some(_sa) = sa {
@@ -1,4 +1,7 @@
-use super::{BlocksVisibility, GameLog, Hidden, Map, Name, Player, Position, Viewshed};
+use super::{
+    BlocksVisibility, GameLog, Hidden, LogCategory, Map, Name, Player, Position, Renderable,
+    Viewshed,
+};
 use rltk::{field_of_view, Point};
 use specs::prelude::*;
 
@@ -16,6 +19,7 @@ impl<'a> System<'a> for VisibilitySystem {
         WriteExpect<'a, GameLog>,
         ReadStorage<'a, Name>,
         ReadStorage<'a, BlocksVisibility>,
+        ReadStorage<'a, Renderable>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
@@ -30,6 +34,7 @@ impl<'a> System<'a> for VisibilitySystem {
             mut gamelog,
             names,
             blocks_visibility,
+            renderables,
         ) = data;
 
         map.view_blocked.clear();
@@ -67,8 +72,11 @@ impl<'a> System<'a> for VisibilitySystem {
                                     let name = names.get(*e);
                                     if let Some(name) = name {
                                         gamelog
-                                            .entries
-                                            .push(format!("You spotted a {}.", &name.name));
+                                            .entry(LogCategory::System)
+                                            .append("You spotted a ")
+                                            .name(&name.name, renderables.get(*e))
+                                            .append(".")
+                                            .log();
                                     }
 
                                     hidden.remove(*e);
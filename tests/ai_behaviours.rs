@@ -0,0 +1,286 @@
+use rltk::{DistanceAlg, Point, RandomNumberGenerator};
+use rogue::ai::{
+    BystanderAI, CarrionEaterAI, ConfusedAI, CowardlyAI, GuardAI, MeleeAI, RangedAI, VendorAI,
+};
+use rogue::effects::EffectQueue;
+use rogue::initiative_system::{ATTACK_COST, EAT_COST, MOVE_COST, WAIT_COST};
+use rogue::*;
+use specs::prelude::*;
+
+const PLAYER_AT: (i32, i32) = (10, 10);
+
+/// An open 20x20 floor with the player in the middle and nothing else in it.
+fn arena() -> World {
+    let mut ecs = World::new();
+    register(&mut ecs);
+    let mut map = Map::new(2, 20, 20);
+    for tile in map.tiles.iter_mut() {
+        *tile = TileType::Floor;
+    }
+    ecs.insert(map);
+    ecs.insert(RunState::Ticking);
+    ecs.insert(RandomNumberGenerator::seeded(1));
+    ecs.insert(EffectQueue::new());
+    ecs.insert(ParticleBuilder::new());
+    ecs.insert(GameLog::new());
+
+    let player = ecs
+        .create_entity()
+        .with(Player {})
+        .with(Position {
+            x: PLAYER_AT.0,
+            y: PLAYER_AT.1,
+        })
+        .with(CombatStats {
+            max_hp: 30,
+            hp: 30,
+            defense: 2,
+            power: 5,
+        })
+        .build();
+    ecs.insert(player);
+    block(&mut ecs, PLAYER_AT);
+    ecs
+}
+
+fn block(ecs: &mut World, (x, y): (i32, i32)) {
+    let mut map = ecs.write_resource::<Map>();
+    let idx = map.xy_idx(x, y);
+    map.blocked[idx] = true;
+}
+
+/// A monster at `at` whose turn it is, seeing the whole arena and behaving as `ai` says.
+fn mob<A: Component + Send + Sync>(ecs: &mut World, at: (i32, i32), ai: A) -> Entity {
+    let visible_tiles = (0..20)
+        .flat_map(|y| (0..20).map(move |x| Point::new(x, y)))
+        .collect();
+    block(ecs, at);
+    ecs.create_entity()
+        .with(Monster {})
+        .with(Name {
+            name: "Mob".to_string(),
+        })
+        .with(Position { x: at.0, y: at.1 })
+        .with(Viewshed {
+            visible_tiles,
+            range: 20,
+            dirty: false,
+        })
+        .with(CombatStats {
+            max_hp: 10,
+            hp: 10,
+            defense: 0,
+            power: 3,
+        })
+        .with(Energy {
+            current: 0,
+            speed: 100,
+        })
+        .with(MyTurn {})
+        .with(ai)
+        .build()
+}
+
+fn run<S: for<'a> RunNow<'a>>(ecs: &mut World, mut system: S) {
+    system.run_now(ecs);
+    ecs.maintain();
+}
+
+fn position(ecs: &World, entity: Entity) -> (i32, i32) {
+    let pos = ecs.read_storage::<Position>().get(entity).copied().unwrap();
+    (pos.x, pos.y)
+}
+
+fn distance_to_player(ecs: &World, entity: Entity) -> f32 {
+    let (x, y) = position(ecs, entity);
+    DistanceAlg::Pythagoras.distance2d(Point::new(x, y), Point::new(PLAYER_AT.0, PLAYER_AT.1))
+}
+
+fn attacking(ecs: &World, entity: Entity) -> Option<Entity> {
+    ecs.read_storage::<WantsToMelee>()
+        .get(entity)
+        .map(|wants| wants.target)
+}
+
+/// The energy the mob spent on its turn, checking that the turn is over.
+fn spent(ecs: &World, entity: Entity) -> i32 {
+    assert!(
+        !ecs.read_storage::<MyTurn>().contains(entity),
+        "turn not taken"
+    );
+    -ecs.read_storage::<Energy>().get(entity).unwrap().current
+}
+
+fn set_hp(ecs: &mut World, entity: Entity, hp: i32) {
+    ecs.write_storage::<CombatStats>()
+        .get_mut(entity)
+        .unwrap()
+        .hp = hp;
+}
+
+#[test]
+fn melee_mob_hits_what_is_next_to_it() {
+    let mut ecs = arena();
+    let goblin = mob(&mut ecs, (10, 11), MeleeAi {});
+    run(&mut ecs, MeleeAI {});
+    assert_eq!(attacking(&ecs, goblin), Some(*ecs.fetch::<Entity>()));
+    assert_eq!(spent(&ecs, goblin), ATTACK_COST);
+}
+
+#[test]
+fn melee_mob_closes_in() {
+    let mut ecs = arena();
+    let goblin = mob(&mut ecs, (10, 15), MeleeAi {});
+    run(&mut ecs, MeleeAI {});
+    assert_eq!(position(&ecs, goblin), (10, 14));
+    assert_eq!(spent(&ecs, goblin), MOVE_COST);
+}
+
+#[test]
+fn ranged_mob_backs_off_inside_min_distance() {
+    let mut ecs = arena();
+    let ai = RangedAi {
+        range: 6,
+        damage: 2,
+        min_distance: 3,
+    };
+    let archer = mob(&mut ecs, (10, 12), ai);
+    run(&mut ecs, RangedAI {});
+    assert!(distance_to_player(&ecs, archer) > 2.0);
+    assert_eq!(attacking(&ecs, archer), None);
+    assert_eq!(spent(&ecs, archer), MOVE_COST);
+}
+
+#[test]
+fn ranged_mob_shoots_from_range() {
+    let mut ecs = arena();
+    let ai = RangedAi {
+        range: 6,
+        damage: 2,
+        min_distance: 3,
+    };
+    let archer = mob(&mut ecs, (10, 15), ai);
+    run(&mut ecs, RangedAI {});
+    assert_eq!(position(&ecs, archer), (10, 15));
+    assert_eq!(attacking(&ecs, archer), None);
+    assert_eq!(spent(&ecs, archer), ATTACK_COST);
+}
+
+#[test]
+fn cowardly_mob_fights_until_hurt_then_flees() {
+    let mut ecs = arena();
+    let kobold = mob(&mut ecs, (10, 11), CowardlyAi { flee_below: 50 });
+    set_hp(&mut ecs, kobold, 5);
+    run(&mut ecs, CowardlyAI {});
+    assert_eq!(attacking(&ecs, kobold), Some(*ecs.fetch::<Entity>()));
+
+    let mut ecs = arena();
+    let kobold = mob(&mut ecs, (10, 11), CowardlyAi { flee_below: 50 });
+    set_hp(&mut ecs, kobold, 4);
+    run(&mut ecs, CowardlyAI {});
+    assert_eq!(attacking(&ecs, kobold), None);
+    assert!(distance_to_player(&ecs, kobold) > 1.5);
+    assert_eq!(spent(&ecs, kobold), MOVE_COST);
+}
+
+#[test]
+fn guard_holds_its_post() {
+    let mut ecs = arena();
+    let guard = mob(&mut ecs, (10, 13), GuardAi {});
+    run(&mut ecs, GuardAI {});
+    assert_eq!(position(&ecs, guard), (10, 13));
+    assert_eq!(spent(&ecs, guard), WAIT_COST);
+
+    let mut ecs = arena();
+    let guard = mob(&mut ecs, (11, 11), GuardAi {});
+    run(&mut ecs, GuardAI {});
+    assert_eq!(attacking(&ecs, guard), Some(*ecs.fetch::<Entity>()));
+}
+
+#[test]
+fn bystander_keeps_clear_of_trouble() {
+    let mut ecs = arena();
+    let bystander = mob(&mut ecs, (10, 12), BystanderAi {});
+    run(&mut ecs, BystanderAI {});
+    assert_eq!(attacking(&ecs, bystander), None);
+    assert!(distance_to_player(&ecs, bystander) > 2.0);
+    assert_eq!(spent(&ecs, bystander), MOVE_COST);
+}
+
+#[test]
+fn vendor_minds_its_stall() {
+    let mut ecs = arena();
+    let vendor = mob(&mut ecs, (10, 11), VendorAi {});
+    run(&mut ecs, VendorAI {});
+    assert_eq!(position(&ecs, vendor), (10, 11));
+    assert_eq!(attacking(&ecs, vendor), None);
+    assert_eq!(spent(&ecs, vendor), WAIT_COST);
+}
+
+#[test]
+fn carrion_eater_goes_for_remains_and_eats_them() {
+    let mut ecs = arena();
+    let rat = mob(&mut ecs, (3, 3), CarrionEaterAi {});
+    let corpse = ecs
+        .create_entity()
+        .with(Position { x: 3, y: 6 })
+        .with(Corpse { rots_at: 100 })
+        .build();
+    run(&mut ecs, CarrionEaterAI {});
+    assert_eq!(position(&ecs, rat), (3, 4));
+    assert!(ecs.is_alive(corpse));
+
+    ecs.write_storage::<Position>()
+        .insert(corpse, Position { x: 3, y: 4 })
+        .unwrap();
+    ecs.write_storage::<MyTurn>()
+        .insert(rat, MyTurn {})
+        .unwrap();
+    ecs.write_storage::<Energy>().get_mut(rat).unwrap().current = 0;
+    set_hp(&mut ecs, rat, 2);
+    run(&mut ecs, CarrionEaterAI {});
+    assert!(!ecs.is_alive(corpse));
+    assert_eq!(ecs.read_storage::<CombatStats>().get(rat).unwrap().hp, 10);
+    assert_eq!(spent(&ecs, rat), EAT_COST);
+}
+
+#[test]
+fn confused_mob_loses_its_turns_until_it_recovers() {
+    let mut ecs = arena();
+    let goblin = mob(&mut ecs, (10, 11), MeleeAi {});
+    ecs.write_storage::<Confusion>()
+        .insert(goblin, Confusion { duration: 2 })
+        .unwrap();
+    run(&mut ecs, ConfusedAI {});
+    run(&mut ecs, MeleeAI {});
+    assert_eq!(attacking(&ecs, goblin), None);
+    assert_eq!(spent(&ecs, goblin), WAIT_COST);
+    assert_eq!(
+        ecs.read_storage::<Confusion>()
+            .get(goblin)
+            .unwrap()
+            .duration,
+        1
+    );
+
+    ecs.write_storage::<MyTurn>()
+        .insert(goblin, MyTurn {})
+        .unwrap();
+    run(&mut ecs, ConfusedAI {});
+    assert!(!ecs.read_storage::<Confusion>().contains(goblin));
+}
+
+#[test]
+fn carrion_eater_waits_while_its_meal_is_stood_on() {
+    let mut ecs = arena();
+    let rat = mob(&mut ecs, (3, 3), CarrionEaterAi {});
+    ecs.create_entity()
+        .with(Position { x: 3, y: 4 })
+        .with(Corpse { rots_at: 100 })
+        .build();
+    let goblin = mob(&mut ecs, (3, 4), MeleeAi {});
+    run(&mut ecs, CarrionEaterAI {});
+    assert_eq!(position(&ecs, rat), (3, 3));
+    assert_eq!(position(&ecs, goblin), (3, 4));
+    assert_eq!(spent(&ecs, rat), WAIT_COST);
+}
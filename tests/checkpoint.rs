@@ -0,0 +1,88 @@
+use rogue::saveload_system::{
+    autosave, end_session, interrupted_session, list_slots, recover_session, save_game, ActiveSlot,
+    CHECKPOINT_SLOT,
+};
+use rogue::{GameClock, Settings, State};
+use std::fs;
+use std::path::PathBuf;
+
+fn game(name: &str) -> (State, PathBuf) {
+    let root = std::env::temp_dir().join(format!("rogue-{}-{}", name, std::process::id()));
+    let mut gs = State::with_settings(Settings {
+        seed: Some(5),
+        show_mapgen_visualizer: false,
+        save_dir: root.to_string_lossy().to_string(),
+        ..Settings::default()
+    })
+    .unwrap();
+    gs.run_until_input();
+    (gs, root)
+}
+
+fn turn(gs: &State) -> i32 {
+    gs.ecs.fetch::<GameClock>().turn
+}
+
+#[test]
+fn autosave_writes_a_checkpoint_beside_the_manual_save() {
+    let (mut gs, root) = game("checkpoint-autosave");
+    save_game(&mut gs.ecs).unwrap();
+    let slot = gs.ecs.fetch::<ActiveSlot>().name.clone().unwrap();
+    gs.ecs.fetch_mut::<GameClock>().turn += 50;
+    autosave(&mut gs.ecs).unwrap();
+
+    let slots = list_slots(&gs.ecs);
+    let checkpoint_written = root.join(format!("{}.json", CHECKPOINT_SLOT)).exists();
+    let lock_written = root.join("session.lock").exists();
+    fs::remove_dir_all(&root).unwrap();
+
+    assert!(checkpoint_written);
+    assert!(lock_written);
+    // The manual save is untouched and the checkpoint isn't offered as a slot
+    assert_eq!(slots.len(), 1);
+    assert_eq!(slots[0].name, slot);
+    assert_eq!(slots[0].metadata.as_ref().unwrap().turn, turn(&gs) - 50);
+}
+
+#[test]
+fn interrupted_session_recovers_from_the_checkpoint() {
+    let (mut gs, root) = game("checkpoint-interrupted");
+    assert!(interrupted_session(&gs.ecs).is_none());
+    save_game(&mut gs.ecs).unwrap();
+    let slot = gs.ecs.fetch::<ActiveSlot>().name.clone();
+    gs.ecs.fetch_mut::<GameClock>().turn += 50;
+    autosave(&mut gs.ecs).unwrap();
+    let checkpoint_turn = turn(&gs);
+
+    // The game is killed without ending its session, and started up again
+    let (mut restarted, _) = game("checkpoint-interrupted");
+    let found = interrupted_session(&restarted.ecs);
+    let recovered = recover_session(&mut restarted.ecs);
+    fs::remove_dir_all(&root).unwrap();
+
+    let found = found.expect("the checkpoint was not found");
+    assert_eq!(Some(found.name), slot);
+    assert_eq!(found.metadata.unwrap().turn, checkpoint_turn);
+    recovered.unwrap();
+    assert_eq!(turn(&restarted), checkpoint_turn);
+    assert_eq!(restarted.ecs.fetch::<ActiveSlot>().name, slot);
+}
+
+#[test]
+fn ending_the_session_clears_the_checkpoint() {
+    let (mut gs, root) = game("checkpoint-ended");
+    autosave(&mut gs.ecs).unwrap();
+    assert!(interrupted_session(&gs.ecs).is_some());
+    end_session(&gs.ecs);
+
+    let found = interrupted_session(&gs.ecs);
+    let checkpoint_left = root.join(format!("{}.json", CHECKPOINT_SLOT)).exists();
+    let lock_left = root.join("session.lock").exists();
+    fs::remove_dir_all(&root).unwrap();
+
+    assert!(found.is_none());
+    assert!(!checkpoint_left);
+    assert!(!lock_left);
+    // An autosave alone never claims a slot for the game
+    assert_eq!(gs.ecs.fetch::<ActiveSlot>().name, None);
+}
@@ -0,0 +1,155 @@
+use rogue::saveload_system::{load_game, save_game};
+use rogue::{
+    register, DefenseBonus, EquipmentSlot, Equipped, Grudge, InBackpack, Item, Name, SerializeMe,
+    Settings, SpeedModifier, State, SAVED_COMPONENTS, TRANSIENT_COMPONENTS,
+};
+use serde_json::Value;
+use specs::prelude::*;
+use specs::saveload::{MarkedBuilder, SimpleMarker};
+use specs::shred::MetaTable;
+use specs::storage::AnyStorage;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+/// How many component storages `ecs` has.
+fn storages(ecs: &World) -> usize {
+    ecs.fetch::<MetaTable<dyn AnyStorage>>().iter(ecs).count()
+}
+
+fn game(save_dir: &Path) -> State {
+    let mut gs = State::with_settings(Settings {
+        seed: Some(1),
+        show_mapgen_visualizer: false,
+        save_dir: save_dir.to_string_lossy().to_string(),
+        ..Settings::default()
+    })
+    .unwrap();
+    gs.run_until_input();
+    gs
+}
+
+/// Each component list in the save, as the components it holds by the marker of the entity
+/// holding them. The save helper is given a fresh marker every save, so it is keyed by `-`.
+fn components(save_dir: &Path) -> BTreeMap<String, BTreeMap<String, Value>> {
+    let data = fs::read(save_dir.join("Save 1.json")).unwrap();
+    let save: Value = serde_json::from_slice(&data).unwrap();
+    save["components"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .map(|(name, list)| {
+            let present = list
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|entry| !entry["components"][0].is_null())
+                .map(|entry| {
+                    let marker = match name.as_str() {
+                        "SerializationHelper" => "-".to_string(),
+                        _ => entry["marker"].to_string(),
+                    };
+                    (marker, entry["components"][0].clone())
+                })
+                .collect();
+            (name.clone(), present)
+        })
+        .collect()
+}
+
+/// Gives the player things a new game doesn't have yet, which point at other entities.
+fn add_entity_references(ecs: &mut World) {
+    let player = *ecs.fetch::<Entity>();
+    let rival = ecs
+        .create_entity()
+        .with(Name {
+            name: "Rival".to_string(),
+        })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+    ecs.create_entity()
+        .with(Item {})
+        .with(InBackpack { owner: player })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+    ecs.create_entity()
+        .with(Item {})
+        .with(Equipped {
+            owner: player,
+            slot: EquipmentSlot::Shield,
+        })
+        .with(DefenseBonus { defense: 1 })
+        .marked::<SimpleMarker<SerializeMe>>()
+        .build();
+    ecs.write_storage::<Grudge>()
+        .insert(player, Grudge { against: rival })
+        .unwrap();
+    ecs.write_storage::<SpeedModifier>()
+        .insert(
+            player,
+            SpeedModifier {
+                percent: 50,
+                duration: 3,
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn every_storage_in_use_is_saved_or_transient() {
+    let saved: BTreeSet<&str> = SAVED_COMPONENTS.iter().copied().collect();
+    let transient: BTreeSet<&str> = TRANSIENT_COMPONENTS.iter().copied().collect();
+    assert_eq!(
+        saved.len(),
+        SAVED_COMPONENTS.len(),
+        "a saved component is listed twice"
+    );
+    let both: Vec<&&str> = saved.intersection(&transient).collect();
+    assert!(
+        both.is_empty(),
+        "components both saved and transient: {:?}",
+        both
+    );
+
+    // One storage per listed component, plus the save markers
+    let mut ecs = World::new();
+    register(&mut ecs);
+    let registered = storages(&ecs);
+    assert_eq!(registered, saved.len() + transient.len() + 1);
+
+    // Setting up the systems adds a storage for any component they use that isn't listed
+    let save_dir =
+        std::env::temp_dir().join(format!("rogue-registry-in-use-{}", std::process::id()));
+    let gs = game(&save_dir);
+    assert_eq!(
+        storages(&gs.ecs),
+        registered,
+        "a component is used without being registered as saved or transient"
+    );
+}
+
+#[test]
+fn every_saved_storage_round_trips() {
+    let save_dir = std::env::temp_dir().join(format!("rogue-registry-{}", std::process::id()));
+    let mut gs = game(&save_dir);
+    add_entity_references(&mut gs.ecs);
+    save_game(&mut gs.ecs).unwrap();
+    let written = components(&save_dir);
+
+    let lists: BTreeSet<&str> = written.keys().map(|k| k.as_str()).collect();
+    let expected: BTreeSet<&str> = SAVED_COMPONENTS.iter().copied().collect();
+    assert_eq!(lists, expected);
+
+    // Whatever is loaded saves out the same again
+    load_game(&mut gs.ecs, "Save 1").unwrap();
+    save_game(&mut gs.ecs).unwrap();
+    let rewritten = components(&save_dir);
+    fs::remove_dir_all(&save_dir).unwrap();
+    for name in SAVED_COMPONENTS.iter() {
+        assert_eq!(
+            written[*name], rewritten[*name],
+            "{} changed on loading",
+            name
+        );
+    }
+}
@@ -0,0 +1,29 @@
+use rogue::{Corpse, GameClock, Position, RunState, State};
+use specs::prelude::*;
+
+#[test]
+fn corpses_rot_away_on_time() {
+    let mut gs = State::with_seed(11);
+    gs.run_until_input();
+    let turn = gs.ecs.fetch::<GameClock>().turn;
+    let fresh = gs
+        .ecs
+        .create_entity()
+        .with(Position { x: 1, y: 1 })
+        .with(Corpse { rots_at: turn + 1 })
+        .build();
+    // Corpses left on another level rot too, so none are kept in the save forever
+    let elsewhere = gs
+        .ecs
+        .create_entity()
+        .with(Corpse { rots_at: turn })
+        .build();
+
+    gs.set_runstate(RunState::AwaitingInput);
+    assert!(gs.ecs.is_alive(fresh));
+    assert!(!gs.ecs.is_alive(elsewhere));
+
+    gs.ecs.write_resource::<GameClock>().turn = turn + 1;
+    gs.set_runstate(RunState::AwaitingInput);
+    assert!(!gs.ecs.is_alive(fresh));
+}
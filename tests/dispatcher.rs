@@ -0,0 +1,73 @@
+use rogue::dispatcher::{game_systems, RegisteredSystem, AI_SYSTEMS};
+use specs::prelude::*;
+
+/// Whether `later` waits for `earlier`, directly or through the systems it depends on.
+fn runs_after(registered: &[RegisteredSystem], later: &str, earlier: &str) -> bool {
+    let system = registered
+        .iter()
+        .find(|s| s.name == later)
+        .unwrap_or_else(|| panic!("{} isn't registered", later));
+    system
+        .dependencies
+        .iter()
+        .any(|d| *d == earlier || runs_after(registered, d, earlier))
+}
+
+#[test]
+fn turns_are_handed_out_before_anyone_acts_and_damage_is_dealt_after() {
+    let systems = game_systems().build(&mut World::new());
+    let registered = systems.registered();
+    let mut ai_systems = AI_SYSTEMS.to_vec();
+    ai_systems.push("confused_ai");
+
+    for ai in ai_systems.iter() {
+        assert!(
+            runs_after(registered, ai, "initiative"),
+            "{} can run before initiative",
+            ai
+        );
+        assert!(
+            runs_after(registered, "melee_combat", ai),
+            "melee_combat can run before {}",
+            ai
+        );
+    }
+    // Melee queues its damage as effects, which are dealt in the same turn
+    assert!(runs_after(registered, "effects", "melee_combat"));
+}
+
+#[test]
+fn systems_come_after_their_dependencies() {
+    let systems = game_systems().build(&mut World::new());
+    let registered = systems.registered();
+    for (i, system) in registered.iter().enumerate() {
+        for dependency in system.dependencies.iter() {
+            assert!(
+                registered[..i].iter().any(|s| s.name == *dependency),
+                "{} depends on {}, which isn't registered before it",
+                system.name,
+                dependency
+            );
+        }
+    }
+}
+
+#[test]
+fn monster_actions_wait_for_every_ai_system() {
+    let systems = game_systems().build(&mut World::new());
+    let registered = systems.registered();
+    let dependencies_of = |name: &str| {
+        registered
+            .iter()
+            .find(|s| s.name == name)
+            .unwrap_or_else(|| panic!("{} isn't registered", name))
+            .dependencies
+            .clone()
+    };
+
+    for ai in AI_SYSTEMS.iter() {
+        assert_eq!(dependencies_of(ai), vec!["confused_ai"], "{}", ai);
+    }
+    assert_eq!(dependencies_of("triggers"), AI_SYSTEMS.to_vec());
+    assert_eq!(dependencies_of("melee_combat"), AI_SYSTEMS.to_vec());
+}
@@ -0,0 +1,147 @@
+use rogue::effects::{EffectQueue, EffectSystem, EffectType, Targets};
+use rogue::*;
+use specs::prelude::*;
+
+/// A 10x10 level with the player in the corner, waiting for effects.
+fn world() -> World {
+    let mut ecs = World::new();
+    register(&mut ecs);
+    ecs.insert(Map::new(1, 10, 10));
+    ecs.insert(RunState::Ticking);
+    ecs.insert(GameLog::new());
+    ecs.insert(ParticleBuilder::new());
+    ecs.insert(EffectQueue::new());
+    let player = creature(&mut ecs, "Player", (0, 0));
+    ecs.insert(player);
+    ecs
+}
+
+/// A creature with 10 of 20 hit points standing at `at`, indexed on the map.
+fn creature(ecs: &mut World, name: &str, at: (i32, i32)) -> Entity {
+    let entity = ecs
+        .create_entity()
+        .with(Name {
+            name: name.to_string(),
+        })
+        .with(Position { x: at.0, y: at.1 })
+        .with(CombatStats {
+            max_hp: 20,
+            hp: 10,
+            defense: 0,
+            power: 1,
+        })
+        .build();
+    let mut map = ecs.write_resource::<Map>();
+    let idx = map.xy_idx(at.0, at.1);
+    map.tile_content[idx].push(entity);
+    entity
+}
+
+fn tile(ecs: &World, at: (i32, i32)) -> usize {
+    ecs.fetch::<Map>().xy_idx(at.0, at.1)
+}
+
+fn apply(ecs: &mut World, creator: Option<Entity>, effect_type: EffectType, targets: Targets) {
+    ecs.write_resource::<EffectQueue>()
+        .add(creator, effect_type, targets);
+    EffectSystem {}.run_now(ecs);
+    ecs.maintain();
+}
+
+fn hp(ecs: &World, entity: Entity) -> i32 {
+    ecs.read_storage::<CombatStats>().get(entity).unwrap().hp
+}
+
+fn last_message(ecs: &World) -> String {
+    ecs.fetch::<GameLog>().entries.last().unwrap().text()
+}
+
+#[test]
+fn used_item_applies_each_of_its_effects_over_its_area() {
+    let mut ecs = world();
+    let player = *ecs.fetch::<Entity>();
+    let near = creature(&mut ecs, "Orc", (4, 4));
+    let far = creature(&mut ecs, "Goblin", (5, 4));
+    let outside = creature(&mut ecs, "Kobold", (8, 8));
+    let grenade = ecs
+        .create_entity()
+        .with(Item {})
+        .with(InflictsDamage { damage: 3 })
+        .with(Confusion { duration: 2 })
+        .build();
+
+    let tiles = vec![tile(&ecs, (4, 4)), tile(&ecs, (5, 4)), tile(&ecs, (6, 4))];
+    apply(
+        &mut ecs,
+        Some(player),
+        EffectType::ItemUse { item: grenade },
+        Targets::Tiles { tiles },
+    );
+
+    for hit in [near, far].iter() {
+        assert_eq!(hp(&ecs, *hit), 7);
+        assert_eq!(
+            ecs.read_storage::<Confusion>().get(*hit).unwrap().duration,
+            2
+        );
+        let last_damage = ecs.read_storage::<LastDamage>().get(*hit).cloned();
+        assert_eq!(last_damage.unwrap().source.as_deref(), Some("Player"));
+    }
+    assert_eq!(hp(&ecs, outside), 10);
+    assert!(!ecs.read_storage::<Confusion>().contains(outside));
+    assert_eq!(last_message(&ecs), "Goblin is confused.");
+}
+
+#[test]
+fn healing_item_heals_up_to_full() {
+    let mut ecs = world();
+    let player = *ecs.fetch::<Entity>();
+    let potion = ecs
+        .create_entity()
+        .with(Item {})
+        .with(ProvidesHealing { heal_amount: 8 })
+        .build();
+
+    apply(
+        &mut ecs,
+        Some(player),
+        EffectType::ItemUse { item: potion },
+        Targets::Single { target: player },
+    );
+    assert_eq!(hp(&ecs, player), 18);
+    apply(
+        &mut ecs,
+        Some(player),
+        EffectType::ItemUse { item: potion },
+        Targets::Single { target: player },
+    );
+    assert_eq!(hp(&ecs, player), 20);
+    assert_eq!(last_message(&ecs), "Player recovers 8 hp.");
+}
+
+#[test]
+fn trap_fires_on_whoever_stands_on_it() {
+    let mut ecs = world();
+    let victim = creature(&mut ecs, "Orc", (2, 3));
+    let trap = ecs
+        .create_entity()
+        .with(Name {
+            name: "Bear Trap".to_string(),
+        })
+        .with(InflictsDamage { damage: 6 })
+        .build();
+
+    let tile_idx = tile(&ecs, (2, 3));
+    apply(
+        &mut ecs,
+        None,
+        EffectType::TriggerFire { trigger: trap },
+        Targets::Tile { tile_idx },
+    );
+    assert_eq!(hp(&ecs, victim), 4);
+    assert_eq!(last_message(&ecs), "Orc takes 6 damage.");
+    let last_damage = ecs.read_storage::<LastDamage>().get(victim).cloned();
+    assert_eq!(last_damage.unwrap().source, None);
+    // Nobody dealt the damage, so nobody is held to blame
+    assert!(!ecs.read_storage::<Grudge>().contains(victim));
+}
@@ -0,0 +1,125 @@
+use rltk::Point;
+use rogue::raws::{faction_reaction, merge_raws, RawMaster, Reaction};
+use rogue::*;
+use specs::prelude::*;
+
+#[test]
+fn factions_react_as_configured() {
+    let raws = merge_raws(&[(
+        "factions.json".to_string(),
+        r#"{
+            "factions": [
+                { "name": "Townsfolk", "responses": { "Default": "ignore", "Goblinoid": "attack" } },
+                { "name": "Vermin", "responses": { "Default": "flee", "Player": "attack" } },
+                { "name": "Player", "responses": {} },
+                { "name": "Goblinoid", "responses": { "Default": "attack" } }
+            ]
+        }"#
+        .to_string(),
+    )])
+    .unwrap();
+    let mut master = RawMaster::empty();
+    master.load(raws);
+
+    assert_eq!(
+        faction_reaction(&master, "Townsfolk", "Goblinoid"),
+        Reaction::Attack
+    );
+    assert_eq!(
+        faction_reaction(&master, "Townsfolk", "Vermin"),
+        Reaction::Ignore
+    );
+    assert_eq!(
+        faction_reaction(&master, "Vermin", "Player"),
+        Reaction::Attack
+    );
+    assert_eq!(
+        faction_reaction(&master, "Vermin", "Townsfolk"),
+        Reaction::Flee
+    );
+    // Without a response or a default, and for factions nobody defined, mobs ignore each other
+    assert_eq!(
+        faction_reaction(&master, "Statues", "Player"),
+        Reaction::Ignore
+    );
+    assert_eq!(
+        faction_reaction(&master, "Nobody", "Player"),
+        Reaction::Ignore
+    );
+}
+
+/// The first Town Guard in a seeded town, with the player standing just east of it, on the
+/// road in from the gate.
+fn town_with_player_beside_guard() -> (State, Entity) {
+    let mut gs = State::with_seed(5);
+    gs.run_until_input();
+    let (guard, pos) = {
+        let entities = gs.ecs.entities();
+        let names = gs.ecs.read_storage::<Name>();
+        let positions = gs.ecs.read_storage::<Position>();
+        (&entities, &names, &positions)
+            .join()
+            .find(|(_, name, _)| name.name == "Town Guard")
+            .map(|(entity, _, pos)| (entity, *pos))
+            .unwrap()
+    };
+    let player = *gs.ecs.fetch::<Entity>();
+    let (x, y) = (pos.x + 1, pos.y);
+    *gs.ecs.write_resource::<Point>() = Point::new(x, y);
+    gs.ecs
+        .write_storage::<Position>()
+        .insert(player, Position { x, y })
+        .unwrap();
+    (gs, guard)
+}
+
+#[test]
+fn only_hostile_factions_are_monsters() {
+    let mut gs = State::with_seed(5);
+    gs.run_until_input();
+    let factions = gs.ecs.read_storage::<Faction>();
+    let monsters = gs.ecs.read_storage::<Monster>();
+    let townsfolk: Vec<Entity> = (&gs.ecs.entities(), &factions)
+        .join()
+        .filter(|(_, faction)| faction.name == "Townsfolk")
+        .map(|(entity, _)| entity)
+        .collect();
+    assert!(!townsfolk.is_empty());
+    assert!(townsfolk.iter().all(|entity| !monsters.contains(*entity)));
+}
+
+#[test]
+fn guard_fights_back_when_attacked() {
+    let (mut gs, guard) = town_with_player_beside_guard();
+    let player = *gs.ecs.fetch::<Entity>();
+
+    // Left alone, the guard has no quarrel with the player
+    gs.play(Command::Wait);
+    assert!(!gs.ecs.read_storage::<Grudge>().contains(guard));
+    let hit_back = |gs: &State| {
+        gs.ecs
+            .fetch::<GameLog>()
+            .entries
+            .iter()
+            .any(|entry| entry.text().starts_with("Town Guard"))
+    };
+    assert!(!hit_back(&gs));
+
+    gs.play(Command::Move {
+        delta_x: -1,
+        delta_y: 0,
+    });
+    let grudge = gs
+        .ecs
+        .read_storage::<Grudge>()
+        .get(guard)
+        .map(|g| g.against);
+    assert_eq!(grudge, Some(player));
+    for _ in 0..3 {
+        if hit_back(&gs) {
+            break;
+        }
+        gs.play(Command::Wait);
+    }
+    assert!(hit_back(&gs));
+}
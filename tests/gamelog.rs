@@ -0,0 +1,182 @@
+use rltk::RGB;
+use rogue::initiative_system::{spend_energy, InitiativeSystem, MOVE_COST, NORMAL_SPEED};
+use rogue::*;
+use specs::prelude::*;
+
+fn white() -> RGB {
+    RGB::named(rltk::WHITE)
+}
+
+#[test]
+fn entry_is_built_from_coloured_fragments() {
+    let mut log = GameLog::new();
+    let goblin = Renderable {
+        glyph: rltk::to_cp437('g'),
+        fg: RGB::named(rltk::CHOCOLATE),
+        bg: RGB::named(rltk::BLACK),
+        render_order: 1,
+    };
+    log.entry(LogCategory::Combat)
+        .name("Goblin", Some(&goblin))
+        .append(" hits ")
+        .name("Player", None)
+        .append(", for ")
+        .damage(4)
+        .append(" hp, and drinks a potion, for ")
+        .healing(2)
+        .colored(RGB::named(rltk::CYAN), " hp.")
+        .log();
+
+    assert_eq!(log.entries.len(), 1);
+    let entry = &log.entries[0];
+    assert_eq!(
+        entry.text(),
+        "Goblin hits Player, for 4 hp, and drinks a potion, for 2 hp."
+    );
+    let colors: Vec<RGB> = entry.fragments.iter().map(|f| f.color).collect();
+    assert_eq!(
+        colors,
+        vec![
+            RGB::named(rltk::CHOCOLATE),
+            white(),
+            white(),
+            white(),
+            RGB::named(rltk::RED),
+            white(),
+            RGB::named(rltk::GREEN),
+            RGB::named(rltk::CYAN),
+        ]
+    );
+}
+
+#[test]
+fn nothing_is_logged_until_the_entry_is_finished() {
+    let mut log = GameLog::new();
+    let _ = log.entry(LogCategory::Item).append("You pick up a dagger.");
+    assert!(log.entries.is_empty());
+
+    log.entry(LogCategory::Item)
+        .append("You pick up a dagger.")
+        .log();
+    log.entry(LogCategory::System).append("Welcome!").log();
+    let categories: Vec<LogCategory> = log.entries.iter().map(|e| e.category).collect();
+    assert_eq!(categories, vec![LogCategory::Item, LogCategory::System]);
+}
+
+#[test]
+fn entries_are_stamped_with_the_current_turn() {
+    let mut log = GameLog::new();
+    log.entry(LogCategory::System).append("Welcome!").log();
+    log.turn = 7;
+    log.entry(LogCategory::Combat).append("Ouch.").log();
+    let turns: Vec<i32> = log.entries.iter().map(|e| e.turn).collect();
+    assert_eq!(turns, vec![0, 7]);
+}
+
+#[test]
+fn log_turn_follows_the_game_clock() {
+    let mut ecs = World::new();
+    register(&mut ecs);
+    ecs.insert(RunState::Ticking);
+    ecs.insert(GameLog::new());
+    ecs.insert(GameClock { turn: 0 });
+    let player = ecs
+        .create_entity()
+        .with(Player {})
+        .with(Position { x: 1, y: 1 })
+        .with(Energy {
+            current: 0,
+            speed: NORMAL_SPEED,
+        })
+        .build();
+    ecs.insert(player);
+
+    for _ in 0..3 {
+        InitiativeSystem {}.run_now(&ecs);
+        spend_energy(
+            &mut ecs.write_storage::<Energy>(),
+            &mut ecs.write_storage::<MyTurn>(),
+            player,
+            MOVE_COST,
+        );
+    }
+    assert_eq!(ecs.fetch::<GameClock>().turn, 3);
+    assert_eq!(ecs.fetch::<GameLog>().turn, 3);
+}
+
+/// A log with one entry of each category, as the log viewer would be showing it.
+fn history() -> GameLog {
+    let mut log = GameLog::new();
+    log.entry(LogCategory::System)
+        .append("Welcome to Rusty Roguelike")
+        .log();
+    log.entry(LogCategory::Combat)
+        .name("Goblin", None)
+        .append(" hits you, for ")
+        .damage(3)
+        .append(" hp.")
+        .log();
+    log.entry(LogCategory::Item)
+        .append("You pick up the Health Potion.")
+        .log();
+    log.entry(LogCategory::Combat)
+        .append("You hit the Goblin, for ")
+        .damage(5)
+        .append(" hp.")
+        .log();
+    log
+}
+
+fn texts(entries: Vec<&LogEntry>) -> Vec<String> {
+    entries.iter().map(|e| e.text()).collect()
+}
+
+#[test]
+fn viewer_shows_everything_without_a_filter() {
+    let log = history();
+    assert_eq!(log.filtered(None, "").len(), 4);
+}
+
+#[test]
+fn viewer_filters_by_category() {
+    let log = history();
+    assert_eq!(
+        texts(log.filtered(Some(LogCategory::Combat), "")),
+        vec![
+            "Goblin hits you, for 3 hp.",
+            "You hit the Goblin, for 5 hp."
+        ]
+    );
+    assert_eq!(
+        texts(log.filtered(Some(LogCategory::Item), "")),
+        vec!["You pick up the Health Potion."]
+    );
+}
+
+#[test]
+fn viewer_search_ignores_case_and_spans_fragments() {
+    let log = history();
+    assert_eq!(
+        texts(log.filtered(None, "GOBLIN")),
+        vec![
+            "Goblin hits you, for 3 hp.",
+            "You hit the Goblin, for 5 hp."
+        ]
+    );
+    // "for 3 hp" is split over three fragments of different colours
+    assert_eq!(
+        texts(log.filtered(None, "for 3 hp")),
+        vec!["Goblin hits you, for 3 hp."]
+    );
+    assert!(log.filtered(None, "dragon").is_empty());
+}
+
+#[test]
+fn viewer_combines_category_and_search() {
+    let log = history();
+    assert_eq!(
+        texts(log.filtered(Some(LogCategory::Combat), "you hit")),
+        vec!["You hit the Goblin, for 5 hp."]
+    );
+    assert!(log.filtered(Some(LogCategory::Item), "goblin").is_empty());
+}
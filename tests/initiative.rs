@@ -0,0 +1,119 @@
+use rogue::initiative_system::{
+    spend_energy, InitiativeSystem, MOVE_COST, NORMAL_SPEED, TURN_ENERGY,
+};
+use rogue::*;
+use specs::prelude::*;
+
+/// A world with a player of normal speed, ticking.
+fn world() -> World {
+    let mut ecs = World::new();
+    register(&mut ecs);
+    ecs.insert(RunState::Ticking);
+    ecs.insert(GameLog::new());
+    ecs.insert(GameClock { turn: 0 });
+    let player = creature(&mut ecs, NORMAL_SPEED);
+    ecs.write_storage::<Player>()
+        .insert(player, Player {})
+        .unwrap();
+    ecs.insert(player);
+    ecs
+}
+
+fn creature(ecs: &mut World, speed: i32) -> Entity {
+    ecs.create_entity()
+        .with(Position { x: 1, y: 1 })
+        .with(Energy { current: 0, speed })
+        .build()
+}
+
+fn has_turn(ecs: &World, entity: Entity) -> bool {
+    ecs.read_storage::<MyTurn>().contains(entity)
+}
+
+/// Runs the scheduler until the player's turn comes around, having each of `movers` move
+/// whenever its turn comes, and returns how many turns each of them got along the way.
+fn until_player_turn(ecs: &mut World, movers: &[Entity]) -> Vec<usize> {
+    let player = *ecs.fetch::<Entity>();
+    let mut turns = vec![0; movers.len()];
+    for _ in 0..100 {
+        InitiativeSystem {}.run_now(ecs);
+        for (i, mover) in movers.iter().enumerate() {
+            if has_turn(ecs, *mover) {
+                turns[i] += 1;
+                spend_energy(
+                    &mut ecs.write_storage::<Energy>(),
+                    &mut ecs.write_storage::<MyTurn>(),
+                    *mover,
+                    MOVE_COST,
+                );
+            }
+        }
+        if has_turn(ecs, player) {
+            return turns;
+        }
+    }
+    panic!("the player never got a turn");
+}
+
+fn end_player_turn(ecs: &mut World) {
+    let player = *ecs.fetch::<Entity>();
+    spend_energy(
+        &mut ecs.write_storage::<Energy>(),
+        &mut ecs.write_storage::<MyTurn>(),
+        player,
+        MOVE_COST,
+    );
+}
+
+#[test]
+fn double_speed_acts_twice_per_normal_turn() {
+    let mut ecs = world();
+    let fast = creature(&mut ecs, NORMAL_SPEED * 2);
+    let mut fast_turns = 0;
+    for _ in 0..5 {
+        fast_turns += until_player_turn(&mut ecs, &[fast])[0];
+        end_player_turn(&mut ecs);
+    }
+    assert_eq!(fast_turns, 10);
+    assert_eq!(ecs.fetch::<GameClock>().turn, 5);
+}
+
+#[test]
+fn idle_entity_does_not_hold_up_the_player() {
+    let mut ecs = world();
+    // Nothing ever acts for this one, so it never spends its energy itself
+    let idle = creature(&mut ecs, NORMAL_SPEED * 3);
+    for _ in 0..3 {
+        until_player_turn(&mut ecs, &[]);
+        end_player_turn(&mut ecs);
+    }
+    assert!(ecs.read_storage::<Energy>().get(idle).unwrap().current < TURN_ENERGY * 2);
+}
+
+#[test]
+fn speed_modifier_wears_off_after_its_duration() {
+    let mut ecs = world();
+    let player = *ecs.fetch::<Entity>();
+    ecs.write_storage::<SpeedModifier>()
+        .insert(
+            player,
+            SpeedModifier {
+                percent: 200,
+                duration: 2,
+            },
+        )
+        .unwrap();
+
+    until_player_turn(&mut ecs, &[]);
+    assert!(ecs.read_storage::<SpeedModifier>().contains(player));
+    end_player_turn(&mut ecs);
+    until_player_turn(&mut ecs, &[]);
+    assert!(!ecs.read_storage::<SpeedModifier>().contains(player));
+    let message = ecs.fetch::<GameLog>().entries.last().unwrap().text();
+    assert_eq!(message, "You feel your pace return to normal.");
+
+    // Back at normal speed, the player waits ten ticks for each turn again
+    end_player_turn(&mut ecs);
+    let normal = creature(&mut ecs, NORMAL_SPEED);
+    assert_eq!(until_player_turn(&mut ecs, &[normal]), vec![1]);
+}
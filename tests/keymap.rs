@@ -0,0 +1,99 @@
+use rltk::VirtualKeyCode;
+use rogue::keymap::{Action, Keymap};
+use std::fs;
+use std::path::PathBuf;
+
+/// A keymap file in a scratch directory of its own, holding `contents`.
+fn keymap_file(name: &str, contents: &str) -> String {
+    let dir: PathBuf =
+        std::env::temp_dir().join(format!("rogue-keymap-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("keybindings.json");
+    fs::write(&path, contents).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+fn assert_defaults(keymap: &Keymap) {
+    let defaults = Keymap::default();
+    for action in Action::ALL.iter() {
+        assert_eq!(keymap.keys(*action), defaults.keys(*action), "{:?}", action);
+    }
+}
+
+#[test]
+fn missing_file_loads_the_defaults() {
+    let keymap = Keymap::load("./no-such-dir/keybindings.json");
+    assert_defaults(&keymap);
+    assert_eq!(keymap.action(VirtualKeyCode::G), Some(Action::PickUp));
+}
+
+#[test]
+fn unreadable_file_loads_the_defaults() {
+    let path = keymap_file("bad", "{ \"PickUp\": [\"G\"");
+    assert_defaults(&Keymap::load(&path));
+
+    let path = keymap_file("unknown-action", "{ \"Dance\": [\"G\"] }");
+    assert_defaults(&Keymap::load(&path));
+}
+
+#[test]
+fn file_overrides_only_the_actions_it_names() {
+    let path = keymap_file("partial", "{ \"PickUp\": [\"P\", \"NoSuchKey\"] }");
+    let keymap = Keymap::load(&path);
+    assert_eq!(keymap.keys(Action::PickUp), &[VirtualKeyCode::P]);
+    assert_eq!(keymap.action(VirtualKeyCode::G), None);
+    assert_eq!(keymap.action(VirtualKeyCode::I), Some(Action::Inventory));
+}
+
+#[test]
+fn file_cannot_leave_an_essential_action_unbound() {
+    let path = keymap_file("essential", "{ \"SaveAndQuit\": [] }");
+    let keymap = Keymap::load(&path);
+    assert_eq!(keymap.keys(Action::SaveAndQuit), &[VirtualKeyCode::Escape]);
+}
+
+#[test]
+fn saved_keymap_loads_back_the_same() {
+    let mut keymap = Keymap::default();
+    assert!(keymap.rebind(Action::Wait, VirtualKeyCode::W));
+    let path = keymap_file("roundtrip", "");
+    keymap.save(&path).unwrap();
+    let loaded = Keymap::load(&path);
+    for action in Action::ALL.iter() {
+        assert_eq!(loaded.keys(*action), keymap.keys(*action), "{:?}", action);
+    }
+}
+
+#[test]
+fn rebinding_moves_the_key_to_the_new_action() {
+    let mut keymap = Keymap::default();
+    assert!(keymap.rebind(Action::Wait, VirtualKeyCode::G));
+    assert_eq!(keymap.action(VirtualKeyCode::G), Some(Action::Wait));
+    assert!(keymap.keys(Action::PickUp).is_empty());
+}
+
+#[test]
+fn rebind_refuses_to_take_the_last_key_of_an_essential_action() {
+    let mut keymap = Keymap::default();
+    assert!(!keymap.rebind(Action::Wait, VirtualKeyCode::Escape));
+    assert_eq!(
+        keymap.action(VirtualKeyCode::Escape),
+        Some(Action::SaveAndQuit)
+    );
+    assert!(!keymap.rebind(Action::PickUp, VirtualKeyCode::F1));
+    assert_eq!(keymap.keys(Action::Keybindings), &[VirtualKeyCode::F1]);
+
+    // Once it has a second key, the first may go
+    assert!(keymap.rebind(Action::SaveAndQuit, VirtualKeyCode::Q));
+    assert!(keymap.rebind(Action::Wait, VirtualKeyCode::Escape));
+    assert_eq!(keymap.keys(Action::SaveAndQuit), &[VirtualKeyCode::Q]);
+}
+
+#[test]
+fn essential_actions_cannot_be_unbound() {
+    let mut keymap = Keymap::default();
+    assert!(!keymap.unbind(Action::SaveAndQuit));
+    assert!(!keymap.unbind(Action::Keybindings));
+    assert!(keymap.unbind(Action::PickUp));
+    assert!(keymap.keys(Action::PickUp).is_empty());
+}
@@ -0,0 +1,99 @@
+use rogue::saveload_system::{
+    import_legacy_save, list_slots, load_game, save_game, SAVE_FORMAT_VERSION,
+};
+use rogue::{
+    Command, Energy, Map, MeleeAi, Player, Position, RunState, Settings, State, WantsToMelee,
+};
+use specs::prelude::*;
+use std::fs;
+
+const V1_FIXTURE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/savegame_v1.json"
+);
+
+#[test]
+fn legacy_save_is_imported_and_migrated_from_v1() {
+    assert_eq!(
+        SAVE_FORMAT_VERSION, 7,
+        "extend this test for the new format"
+    );
+    let root = std::env::temp_dir().join(format!("rogue-legacy-{}", std::process::id()));
+    let save_dir = root.join("saves");
+    let legacy = root.join("savegame.json");
+    fs::create_dir_all(&root).unwrap();
+    fs::copy(V1_FIXTURE, &legacy).unwrap();
+
+    let mut gs = State::with_settings(Settings {
+        seed: Some(1),
+        show_mapgen_visualizer: false,
+        save_dir: save_dir.to_string_lossy().to_string(),
+        ..Settings::default()
+    })
+    .unwrap();
+    gs.run_until_input();
+
+    let slot = import_legacy_save(&gs.ecs, &legacy).unwrap();
+    assert_eq!(slot.as_deref(), Some("Legacy save"));
+    assert!(!legacy.exists(), "the old save was left behind");
+    assert_eq!(import_legacy_save(&gs.ecs, &legacy).unwrap(), None);
+
+    let slots = list_slots(&gs.ecs);
+    assert_eq!(slots.len(), 1);
+    assert_eq!(slots[0].name, "Legacy save");
+    let metadata = slots[0].metadata.as_ref().unwrap();
+    assert_eq!(metadata.depth, 2);
+    assert_eq!(metadata.timestamp, 0);
+
+    let loaded = load_game(&mut gs.ecs, "Legacy save");
+    fs::remove_dir_all(&root).unwrap();
+    loaded.unwrap();
+
+    assert_eq!(gs.ecs.fetch::<Map>().depth, 2);
+    let player = *gs.ecs.fetch::<Entity>();
+    assert!(gs.ecs.read_storage::<Player>().contains(player));
+    let position = gs
+        .ecs
+        .read_storage::<Position>()
+        .get(player)
+        .cloned()
+        .unwrap();
+    assert_eq!((position.x, position.y), (6, 38));
+    assert!(gs.ecs.read_storage::<Energy>().contains(player));
+    assert!(!gs.ecs.read_storage::<MeleeAi>().contains(player));
+
+    // The upgraded game plays on
+    gs.set_runstate(RunState::AwaitingInput);
+    assert!(gs.play(Command::Wait) == RunState::AwaitingInput);
+}
+
+#[test]
+fn v6_saves_lose_their_leftover_intents() {
+    let save_dir = std::env::temp_dir().join(format!("rogue-legacy-v6-{}", std::process::id()));
+    let mut gs = State::with_settings(Settings {
+        seed: Some(1),
+        show_mapgen_visualizer: false,
+        save_dir: save_dir.to_string_lossy().to_string(),
+        ..Settings::default()
+    })
+    .unwrap();
+    gs.run_until_input();
+    save_game(&mut gs.ecs).unwrap();
+
+    // Turn the save back into a format 6 one, with the player still meaning to hit itself
+    let path = save_dir.join("Save 1.json");
+    let mut save: serde_json::Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+    save["header"]["format_version"] = 6.into();
+    let player = save["components"]["Player"][0]["marker"].clone();
+    save["components"]["WantsToMelee"] = serde_json::json!([
+        { "marker": player, "components": [{ "target": player }] }
+    ]);
+    fs::write(&path, serde_json::to_vec(&save).unwrap()).unwrap();
+
+    let loaded = load_game(&mut gs.ecs, "Save 1");
+    fs::remove_dir_all(&save_dir).unwrap();
+    loaded.unwrap();
+    let player = *gs.ecs.fetch::<Entity>();
+    assert!(gs.ecs.read_storage::<Player>().contains(player));
+    assert!(!gs.ecs.read_storage::<WantsToMelee>().contains(player));
+}
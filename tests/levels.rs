@@ -0,0 +1,135 @@
+use rltk::Point;
+use rogue::*;
+use specs::prelude::*;
+use std::collections::BTreeSet;
+
+/// What of the current level should survive the player leaving it and coming back.
+#[derive(PartialEq, Debug)]
+struct Level {
+    depth: i32,
+    revealed: Vec<bool>,
+    items: BTreeSet<(u32, i32, i32)>,
+    monsters: BTreeSet<(u32, i32, i32)>,
+}
+
+fn level(gs: &State) -> Level {
+    let entities = gs.ecs.entities();
+    let positions = gs.ecs.read_storage::<Position>();
+    let placed = |with: &dyn Fn(Entity) -> bool| {
+        (&entities, &positions)
+            .join()
+            .filter(|(entity, _)| with(*entity))
+            .map(|(entity, pos)| (entity.id(), pos.x, pos.y))
+            .collect()
+    };
+    let items = gs.ecs.read_storage::<Item>();
+    let monsters = gs.ecs.read_storage::<Monster>();
+    let map = gs.ecs.fetch::<Map>();
+    Level {
+        depth: map.depth,
+        revealed: map.revealed_tiles.clone(),
+        items: placed(&|entity| items.contains(entity)),
+        monsters: placed(&|entity| monsters.contains(entity)),
+    }
+}
+
+fn tile_position(gs: &State, tile: TileType) -> (i32, i32) {
+    let map = gs.ecs.fetch::<Map>();
+    let idx = map.tiles.iter().position(|t| *t == tile).unwrap();
+    map.idx_xy(idx)
+}
+
+fn player_position(gs: &State) -> (i32, i32) {
+    let player = *gs.ecs.fetch::<Entity>();
+    let pos = gs
+        .ecs
+        .read_storage::<Position>()
+        .get(player)
+        .copied()
+        .unwrap();
+    (pos.x, pos.y)
+}
+
+fn teleport_player(gs: &mut State, (x, y): (i32, i32)) {
+    let player = *gs.ecs.fetch::<Entity>();
+    *gs.ecs.write_resource::<Point>() = Point::new(x, y);
+    gs.ecs
+        .write_storage::<Position>()
+        .insert(player, Position { x, y })
+        .unwrap();
+}
+
+/// Takes the stairs under the player, stopping as soon as the next level is in place, before
+/// anything on it gets to move.
+fn take_stairs(gs: &mut State, command: Command) {
+    let runstate = gs.submit(command);
+    assert!(matches!(
+        runstate,
+        RunState::NextLevel | RunState::PreviousLevel
+    ));
+    gs.set_runstate(runstate);
+    let runstate = gs.advance(runstate);
+    gs.set_runstate(runstate);
+}
+
+fn last_message(gs: &State) -> String {
+    gs.ecs.fetch::<GameLog>().entries.last().unwrap().text()
+}
+
+#[test]
+fn level_is_restored_after_going_down_and_back_up() {
+    let mut gs = State::with_settings(Settings {
+        seed: Some(21),
+        show_mapgen_visualizer: false,
+        start_depth: 2,
+        ..Settings::default()
+    })
+    .unwrap();
+    gs.run_until_input();
+
+    // Leave a mark on the level: a dead monster, and an item dropped in the open
+    let victim = {
+        let entities = gs.ecs.entities();
+        let monsters = gs.ecs.read_storage::<Monster>();
+        (&entities, &monsters).join().next().unwrap().0
+    };
+    gs.ecs
+        .write_storage::<CombatStats>()
+        .get_mut(victim)
+        .unwrap()
+        .hp = 0;
+    let (x, y) = player_position(&gs);
+    gs.ecs
+        .create_entity()
+        .with(Item {})
+        .with(Position { x, y })
+        .build();
+    gs.set_runstate(RunState::AwaitingInput);
+    assert!(!gs.ecs.is_alive(victim));
+
+    let stairs = tile_position(&gs, TileType::DownStairs);
+    teleport_player(&mut gs, stairs);
+    let before = level(&gs);
+    assert!(!before.monsters.is_empty() && !before.items.is_empty());
+
+    take_stairs(&mut gs, Command::Descend);
+    assert_eq!(gs.ecs.fetch::<Map>().depth, 3);
+    assert!(!last_message(&gs).contains("rest"));
+    gs.run_until_input();
+    let way_up = player_position(&gs);
+    let map = gs.ecs.fetch::<Map>();
+    assert!(map.tiles[map.xy_idx(way_up.0, way_up.1)] == TileType::UpStairs);
+    std::mem::drop(map);
+
+    take_stairs(&mut gs, Command::Ascend);
+    assert_eq!(level(&gs), before);
+    assert_eq!(player_position(&gs), stairs);
+    assert!(last_message(&gs).contains("take a moment to rest"));
+    // Whatever lives on the level below is frozen until the player goes back
+    assert!(
+        (&gs.ecs.read_storage::<OtherLevelPosition>())
+            .join()
+            .count()
+            > 0
+    );
+}
@@ -0,0 +1,74 @@
+use rltk::RGB;
+use rogue::{
+    CombatStats, Corpse, Item, LootTable, Name, Position, Renderable, RunState, Settings, State,
+};
+use specs::prelude::*;
+use std::fs;
+
+#[test]
+fn dead_mob_drops_its_loot_where_it_fell() {
+    // A mod whose loot table always drops the same thing
+    let mod_dir = std::env::temp_dir().join(format!("rogue-loot-{}", std::process::id()));
+    fs::create_dir_all(&mod_dir).unwrap();
+    fs::write(
+        mod_dir.join("loot.json"),
+        r#"{ "loot_tables": [{ "name": "Sure Thing", "drops": [{ "name": "Health Potion", "weight": 1 }] }] }"#,
+    )
+    .unwrap();
+    let mut gs = State::with_settings(Settings {
+        seed: Some(9),
+        show_mapgen_visualizer: false,
+        mod_dirs: vec![mod_dir.to_string_lossy().to_string()],
+        ..Settings::default()
+    })
+    .unwrap();
+    fs::remove_dir_all(&mod_dir).unwrap();
+    gs.run_until_input();
+
+    let at = Position { x: 3, y: 4 };
+    let victim = gs
+        .ecs
+        .create_entity()
+        .with(at)
+        .with(Name {
+            name: "Victim".to_string(),
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437('v'),
+            fg: RGB::named(rltk::RED),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 1,
+        })
+        .with(CombatStats {
+            max_hp: 5,
+            hp: 0,
+            defense: 0,
+            power: 1,
+        })
+        .with(LootTable {
+            table: "Sure Thing".to_string(),
+        })
+        .build();
+    gs.set_runstate(RunState::AwaitingInput);
+    gs.ecs.maintain();
+
+    assert!(!gs.ecs.is_alive(victim));
+    let positions = gs.ecs.read_storage::<Position>();
+    let names = gs.ecs.read_storage::<Name>();
+    let items = gs.ecs.read_storage::<Item>();
+    let corpses = gs.ecs.read_storage::<Corpse>();
+    let here = |pos: &Position| pos.x == at.x && pos.y == at.y;
+    let dropped: Vec<&str> = (&positions, &names, &items)
+        .join()
+        .filter(|(pos, _, _)| here(pos))
+        .map(|(_, name, _)| name.name.as_str())
+        .collect();
+    assert_eq!(dropped, vec!["Health Potion"]);
+    assert_eq!(
+        (&positions, &corpses)
+            .join()
+            .filter(|(pos, _)| here(pos))
+            .count(),
+        1
+    );
+}
@@ -0,0 +1,121 @@
+use rogue::effects::{EffectQueue, EffectType, Targets};
+use rogue::morgue::{morgue_text, write_morgue, LOG_LINES};
+use rogue::*;
+use specs::prelude::*;
+use std::fs;
+
+/// The lines of the morgue section that starts with `heading`, up to the blank line after it.
+fn section<'a>(text: &'a str, heading: &str) -> Vec<&'a str> {
+    text.lines()
+        .skip_while(|line| !line.starts_with(heading))
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .collect()
+}
+
+fn item<'a>(ecs: &'a mut World, name: &str) -> EntityBuilder<'a> {
+    ecs.create_entity().with(Item {}).with(Name {
+        name: name.to_string(),
+    })
+}
+
+/// A seeded game whose player, carrying a few things, has just been killed by a goblin.
+fn dead_player(morgue_dir: &str) -> State {
+    let mut gs = State::with_settings(Settings {
+        seed: Some(13),
+        show_mapgen_visualizer: false,
+        morgue_dir: morgue_dir.to_string(),
+        ..Settings::default()
+    })
+    .unwrap();
+    gs.run_until_input();
+    let player = *gs.ecs.fetch::<Entity>();
+
+    item(&mut gs.ecs, "Longsword")
+        .with(Equipped {
+            owner: player,
+            slot: EquipmentSlot::Melee,
+        })
+        .with(MeleePowerBonus { power: 4 })
+        .build();
+    item(&mut gs.ecs, "Health Potion")
+        .with(InBackpack { owner: player })
+        .build();
+    item(&mut gs.ecs, "Magic Mapping Scroll")
+        .with(InBackpack { owner: player })
+        .build();
+    for n in 0..LOG_LINES {
+        gs.ecs
+            .fetch_mut::<GameLog>()
+            .entry(LogCategory::System)
+            .append(format!("Filler message {}.", n))
+            .log();
+    }
+
+    let goblin = gs
+        .ecs
+        .create_entity()
+        .with(Name {
+            name: "Goblin".to_string(),
+        })
+        .build();
+    gs.ecs.fetch_mut::<EffectQueue>().add(
+        Some(goblin),
+        EffectType::Damage { amount: 1000 },
+        Targets::Single { target: player },
+    );
+    gs.run_systems();
+    gs.set_runstate(RunState::AwaitingInput);
+    assert!(gs.runstate() == RunState::GameOver);
+    gs
+}
+
+#[test]
+fn morgue_describes_the_death() {
+    let gs = dead_player("unused");
+    let text = morgue_text(&gs.ecs);
+
+    assert!(text.contains("Killed by Goblin, who dealt the final 1000 hp.\n"));
+    assert!(text.contains("Seed: 13\n"));
+    assert_eq!(
+        section(&text, "Equipment"),
+        ["  Melee: Longsword (+4 power)"]
+    );
+    assert_eq!(
+        section(&text, "Inventory"),
+        ["  Health Potion", "  Magic Mapping Scroll"]
+    );
+
+    let messages = section(&text, &format!("Last {} messages", LOG_LINES));
+    assert_eq!(messages.len(), LOG_LINES);
+    let log = gs.ecs.fetch::<GameLog>();
+    assert!(messages
+        .last()
+        .unwrap()
+        .ends_with(&log.entries.last().unwrap().text()));
+    assert!(!messages.iter().any(|m| m.ends_with("Filler message 0.")));
+
+    let level = section(&text, "Final level");
+    assert!(!level.is_empty());
+    assert!(level.iter().any(|row| row.contains('@')));
+    assert!(level
+        .iter()
+        .all(|row| row.starts_with("  ") && row.chars().all(|c| c.is_ascii_graphic() || c == ' ')));
+}
+
+#[test]
+fn morgue_files_of_different_games_do_not_collide() {
+    let dir = std::env::temp_dir().join(format!("rogue-morgue-{}", std::process::id()));
+    let dir_name = dir.to_string_lossy().to_string();
+    let gs = dead_player(&dir_name);
+    let expected = morgue_text(&gs.ecs);
+    let first = write_morgue(&gs.ecs).unwrap();
+    gs.ecs.fetch_mut::<GameClock>().turn += 1;
+    let second = write_morgue(&gs.ecs).unwrap();
+    let written = fs::read_to_string(&first).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_ne!(first, second);
+    assert!(first.to_string_lossy().contains("seed13"));
+    assert_eq!(written, expected);
+}
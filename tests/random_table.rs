@@ -0,0 +1,33 @@
+use rltk::RandomNumberGenerator;
+use rogue::RandomTable;
+use std::collections::HashMap;
+
+fn roll_many(table: &RandomTable, rolls: usize) -> HashMap<String, usize> {
+    let mut rng = RandomNumberGenerator::seeded(3);
+    let mut counts = HashMap::new();
+    for _ in 0..rolls {
+        *counts.entry(table.roll(&mut rng)).or_insert(0) += 1;
+    }
+    counts
+}
+
+#[test]
+fn single_entry_always_comes_up() {
+    let table = RandomTable::new().add("Goblin", 1);
+    assert_eq!(roll_many(&table, 100).get("Goblin"), Some(&100));
+}
+
+#[test]
+fn empty_table_rolls_none() {
+    let table = RandomTable::new().add("Goblin", 0);
+    assert_eq!(roll_many(&table, 10).get("None"), Some(&10));
+}
+
+#[test]
+fn entries_come_up_in_proportion_to_their_weight() {
+    let table = RandomTable::new().add("Rations", 1).add("Health Potion", 3);
+    let counts = roll_many(&table, 10_000);
+    assert_eq!(counts.get("None"), None);
+    let rations = counts["Rations"] as f64 / 10_000.0;
+    assert!((rations - 0.25).abs() < 0.02, "rations came up {}", rations);
+}
@@ -0,0 +1,112 @@
+use rogue::raws::{merge_raws, Raws, RawsError};
+
+fn merge(json: &str) -> Result<Raws, RawsError> {
+    merge_raws(&[("test.json".to_string(), json.to_string())])
+}
+
+fn problems(json: &str) -> Vec<String> {
+    match merge(json) {
+        Err(RawsError::Invalid(problems)) => problems.iter().map(|p| p.to_string()).collect(),
+        Err(e) => panic!("expected inheritance problems, got {}", e),
+        Ok(_) => panic!("bad inheritance was accepted"),
+    }
+}
+
+const CREATURES: &str = r##"{
+    "mobs": [
+        {
+            "name": "Creature",
+            "abstract": true,
+            "blocks_tile": true,
+            "vision_range": 8,
+            "renderable": { "glyph": "c", "fg": "#FFFFFF", "bg": "#000000", "order": 1 },
+            "stats": { "max_hp": 10, "hp": 10, "power": 2, "defense": 0 }
+        },
+        {
+            "name": "Goblinoid",
+            "abstract": true,
+            "extends": "Creature",
+            "faction": "Goblins",
+            "stats": { "power": 3 }
+        },
+        {
+            "name": "Goblin Archer",
+            "extends": "Goblinoid",
+            "vision_range": 10,
+            "renderable": { "glyph": "g" },
+            "stats": { "max_hp": 6, "hp": 6 }
+        }
+    ],
+    "spawn_table": [{ "name": "Goblin Archer", "weight": 1, "min_depth": 1, "max_depth": 5 }],
+    "factions": [{ "name": "Goblins", "responses": { "Default": "attack" } }]
+}"##;
+
+#[test]
+fn fields_are_inherited_through_every_level() {
+    let raws = merge(CREATURES).unwrap();
+    let archer = &raws.mobs[0];
+    assert_eq!(archer.name, "Goblin Archer");
+    // From the grandparent
+    assert!(archer.blocks_tile);
+    assert_eq!(archer.stats.defense, 0);
+    let renderable = archer.renderable.as_ref().unwrap();
+    assert_eq!(renderable.fg, "#FFFFFF");
+    assert_eq!(renderable.order, 1);
+    // From the parent
+    assert_eq!(archer.faction.as_deref(), Some("Goblins"));
+    assert_eq!(archer.stats.power, 3);
+}
+
+#[test]
+fn child_fields_override_parent_fields() {
+    let raws = merge(CREATURES).unwrap();
+    let archer = &raws.mobs[0];
+    assert_eq!(archer.vision_range, 10);
+    assert_eq!(archer.stats.max_hp, 6);
+    assert_eq!(archer.stats.hp, 6);
+    assert_eq!(archer.renderable.as_ref().unwrap().glyph, "g");
+}
+
+#[test]
+fn abstract_templates_are_left_out() {
+    let raws = merge(CREATURES).unwrap();
+    let names: Vec<&str> = raws.mobs.iter().map(|mob| mob.name.as_str()).collect();
+    assert_eq!(names, vec!["Goblin Archer"]);
+
+    let spawning_template = CREATURES.replace(
+        r#""spawn_table": [{ "name": "Goblin Archer""#,
+        r#""spawn_table": [{ "name": "Goblinoid""#,
+    );
+    assert_eq!(
+        problems(&spawning_template),
+        vec![
+            "test.json: Spawn table entry \"Goblinoid\" (name): is an abstract template, which can't be spawned",
+        ]
+    );
+}
+
+#[test]
+fn missing_parent_is_reported() {
+    let json = r#"{ "items": [{ "name": "Sword", "extends": "Blade" }] }"#;
+    assert_eq!(
+        problems(json),
+        vec!["test.json: Item \"Sword\" (extends): there is no item called \"Blade\" to extend"]
+    );
+}
+
+#[test]
+fn inheritance_cycle_is_reported() {
+    let json = r#"{ "props": [
+        { "name": "Trap", "extends": "Snare" },
+        { "name": "Snare", "extends": "Pit" },
+        { "name": "Pit", "extends": "Trap" }
+    ] }"#;
+    assert_eq!(
+        problems(json),
+        vec![
+            "test.json: Prop \"Trap\" (extends): inheritance cycle: Trap -> Snare -> Pit -> Trap",
+            "test.json: Prop \"Snare\" (extends): inheritance cycle: Snare -> Pit -> Trap -> Snare",
+            "test.json: Prop \"Pit\" (extends): inheritance cycle: Pit -> Trap -> Snare -> Pit",
+        ]
+    );
+}
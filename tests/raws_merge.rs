@@ -0,0 +1,172 @@
+use rogue::raws::{load_raws, merge_raws, RawSources, Raws, RawsError, RAWS};
+use std::fs;
+
+fn mob(name: &str, hp: i32) -> String {
+    format!(
+        r#"{{ "name": "{}", "blocks_tile": true, "vision_range": 8,
+            "stats": {{ "max_hp": {}, "hp": {}, "power": 2, "defense": 0 }} }}"#,
+        name, hp, hp
+    )
+}
+
+fn spawn(name: &str) -> String {
+    format!(
+        r#"{{ "name": "{}", "weight": 1, "min_depth": 1, "max_depth": 5 }}"#,
+        name
+    )
+}
+
+fn merge(files: &[(&str, String)]) -> Result<Raws, RawsError> {
+    let files: Vec<(String, String)> = files
+        .iter()
+        .map(|(source, json)| (source.to_string(), json.clone()))
+        .collect();
+    merge_raws(&files)
+}
+
+fn mob_names(raws: &Raws) -> Vec<&str> {
+    raws.mobs.iter().map(|mob| mob.name.as_str()).collect()
+}
+
+#[test]
+fn later_files_override_entries_by_name_in_place() {
+    let base = format!(
+        r#"{{ "mobs": [{}, {}], "spawn_table": [{}, {}] }}"#,
+        mob("Orc", 10),
+        mob("Goblin", 5),
+        spawn("Orc"),
+        spawn("Goblin")
+    );
+    let patch = format!(
+        r#"{{ "mobs": [{}, {}], "spawn_table": [{}] }}"#,
+        mob("Orc", 20),
+        mob("Kobold", 3),
+        spawn("Kobold")
+    );
+    let raws = merge(&[("base.json", base), ("patch.json", patch)]).unwrap();
+
+    assert_eq!(mob_names(&raws), vec!["Orc", "Goblin", "Kobold"]);
+    assert_eq!(raws.mobs[0].stats.max_hp, 20);
+    assert_eq!(raws.mobs[0].source, "patch.json");
+    assert_eq!(raws.mobs[1].source, "base.json");
+    let spawns: Vec<&str> = raws.spawn_table.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(spawns, vec!["Orc", "Goblin", "Kobold"]);
+}
+
+#[test]
+fn delete_removes_entries_of_every_kind() {
+    let base = format!(
+        r#"{{
+            "items": [{{ "name": "Potion" }}],
+            "mobs": [{}, {}],
+            "spawn_table": [{}, {}],
+            "loot_tables": [
+                {{ "name": "Orc Loot", "drops": [{{ "name": "Potion", "weight": 1 }}] }},
+                {{ "name": "Spare Loot", "drops": [{{ "name": "None", "weight": 1 }}] }}
+            ],
+            "factions": [{{ "name": "Orcs", "responses": {{}} }}]
+        }}"#,
+        mob("Orc", 10),
+        mob("Goblin", 5),
+        spawn("Orc"),
+        spawn("Goblin")
+    );
+    let patch = r#"{ "delete": ["Orc", "Spare Loot", "Orcs"] }"#.to_string();
+    let raws = merge(&[("base.json", base), ("patch.json", patch)]).unwrap();
+
+    assert_eq!(mob_names(&raws), vec!["Goblin"]);
+    let spawns: Vec<&str> = raws.spawn_table.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(spawns, vec!["Goblin"]);
+    let tables: Vec<&str> = raws.loot_tables.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(tables, vec!["Orc Loot"]);
+    assert!(raws.factions.is_empty());
+    assert_eq!(raws.items.len(), 1);
+}
+
+#[test]
+fn deleted_entries_can_be_defined_again() {
+    let base = format!(r#"{{ "mobs": [{}] }}"#, mob("Orc", 10));
+    let patch = format!(r#"{{ "delete": ["Orc"], "mobs": [{}] }}"#, mob("Orc", 30));
+    let raws = merge(&[("base.json", base), ("patch.json", patch)]).unwrap();
+    assert_eq!(mob_names(&raws), vec!["Orc"]);
+    assert_eq!(raws.mobs[0].stats.max_hp, 30);
+}
+
+#[test]
+fn deleting_an_unknown_name_is_a_problem() {
+    let problems = match merge(&[("patch.json", r#"{ "delete": ["Dragon"] }"#.to_string())]) {
+        Err(RawsError::Invalid(problems)) => problems,
+        _ => panic!("deleting a missing entry was accepted"),
+    };
+    assert_eq!(
+        problems.iter().map(|p| p.to_string()).collect::<Vec<_>>(),
+        vec!["patch.json: \"Dragon\" (delete): there is nothing by that name to delete"]
+    );
+}
+
+/// The only test in this file that loads into the shared `RAWS`.
+#[test]
+fn directories_and_mods_merge_in_order() {
+    let root = std::env::temp_dir().join(format!("rogue-raws-merge-{}", std::process::id()));
+    let write = |dir: &str, file: &str, json: String| {
+        let dir = root.join(dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(file), json).unwrap();
+    };
+    // Files within a directory apply in name order, whatever order they were written in
+    write(
+        "base",
+        "02-more.json",
+        format!(r#"{{ "mobs": [{}] }}"#, mob("Goblin", 6)),
+    );
+    write(
+        "base",
+        "01-start.json",
+        format!(
+            r#"{{ "mobs": [{}, {}] }}"#,
+            mob("Orc", 10),
+            mob("Goblin", 5)
+        ),
+    );
+    write("base", "notes.txt", "not raws".to_string());
+    write(
+        "first-mod",
+        "mod.json",
+        format!(
+            r#"{{ "mobs": [{}, {}] }}"#,
+            mob("Orc", 20),
+            mob("Troll", 30)
+        ),
+    );
+    write(
+        "second-mod",
+        "mod.json",
+        format!(r#"{{ "delete": ["Orc"], "mobs": [{}] }}"#, mob("Troll", 40)),
+    );
+
+    let sources = RawSources {
+        base_dir: Some(root.join("base")),
+        mod_dirs: vec![root.join("first-mod"), root.join("second-mod")],
+    };
+    let result = load_raws(&sources);
+    let raws = RAWS.lock().unwrap();
+    let sources = (
+        raws.source("Orc").map(|s| s.to_string()),
+        raws.source("Goblin").map(|s| s.to_string()),
+        raws.source("Troll").map(|s| s.to_string()),
+    );
+    let expected = (
+        None,
+        Some(root.join("base").join("02-more.json").display().to_string()),
+        Some(
+            root.join("second-mod")
+                .join("mod.json")
+                .display()
+                .to_string(),
+        ),
+    );
+    drop(raws);
+    fs::remove_dir_all(&root).unwrap();
+    result.unwrap();
+    assert_eq!(sources, expected);
+}
@@ -0,0 +1,104 @@
+use rogue::raws::{merge_raws, RawsError};
+
+/// Validates a single raws file, returning each problem as it is printed.
+fn problems(json: &str) -> Vec<String> {
+    match merge_raws(&[("test.json".to_string(), json.to_string())]) {
+        Ok(_) => Vec::new(),
+        Err(RawsError::Invalid(problems)) => problems.iter().map(|p| p.to_string()).collect(),
+        Err(e) => panic!("expected validation problems, got {}", e),
+    }
+}
+
+#[test]
+fn valid_raws_have_no_problems() {
+    let json = r##"{
+        "items": [{
+            "name": "Potion",
+            "renderable": { "glyph": "!", "fg": "#FF00FF", "bg": "#000000", "order": 2 },
+            "consumable": { "effects": { "provides_healing": "8", "magic_mapping": "" } }
+        }],
+        "mobs": [{
+            "name": "Archer",
+            "blocks_tile": true,
+            "stats": { "max_hp": 8, "hp": 8, "power": 2, "defense": 0 },
+            "vision_range": 8,
+            "faction": "Monsters",
+            "loot_table": "Drops",
+            "ai": { "type": "ranged", "range": 6, "damage": 2, "min_distance": 3 }
+        }],
+        "spawn_table": [{ "name": "Archer", "weight": 1, "min_depth": 1, "max_depth": 5 }],
+        "loot_tables": [{ "name": "Drops", "drops": [
+            { "name": "Potion", "weight": 1 }, { "name": "None", "weight": 1 }
+        ] }],
+        "factions": [{ "name": "Monsters", "responses": { "Default": "attack" } }]
+    }"##;
+    assert_eq!(problems(json), Vec::<String>::new());
+}
+
+#[test]
+fn every_problem_is_reported() {
+    let json = r##"{
+        "items": [{
+            "name": "Bad Potion",
+            "renderable": { "glyph": "!!", "fg": "pink", "bg": "#000000", "order": 2 },
+            "consumable": { "effects": { "provides_healing": "lots", "teleport": "3" } }
+        }],
+        "mobs": [{
+            "name": "Bad Archer",
+            "blocks_tile": true,
+            "stats": { "max_hp": 8, "hp": 8, "power": 2, "defense": 0 },
+            "vision_range": 8,
+            "faction": "Nobody",
+            "loot_table": "Nothing",
+            "ai": { "type": "ranged", "range": 4, "damage": 2, "min_distance": 5 }
+        }],
+        "spawn_table": [{ "name": "Goblin", "weight": 1, "min_depth": 1, "max_depth": 5 }]
+    }"##;
+    assert_eq!(
+        problems(json),
+        vec![
+            "test.json: Item \"Bad Potion\" (renderable.glyph): \"!!\" should be exactly one character",
+            "test.json: Item \"Bad Potion\" (renderable.fg): \"pink\" is not a hex colour like \"#FF00FF\"",
+            "test.json: Item \"Bad Potion\" (consumable.effects.provides_healing): \"lots\" is not a whole number",
+            "test.json: Item \"Bad Potion\" (consumable.effects.teleport): unknown effect",
+            "test.json: Mob \"Bad Archer\" (faction): there is no faction called \"Nobody\"",
+            "test.json: Mob \"Bad Archer\" (loot_table): there is no loot table called \"Nothing\"",
+            "test.json: Mob \"Bad Archer\" (ai.min_distance): 5 should be between 0 and the range, 4",
+            "test.json: Spawn table entry \"Goblin\" (name): there is no item, mob or prop by that name",
+        ]
+    );
+}
+
+#[test]
+fn dangling_loot_and_faction_names_are_reported() {
+    let json = r##"{
+        "loot_tables": [{ "name": "Drops", "drops": [{ "name": "Sword", "weight": 1 }] }],
+        "factions": [{ "name": "Monsters", "responses": { "Elves": "flee", "Default": "attack" } }]
+    }"##;
+    assert_eq!(
+        problems(json),
+        vec![
+            "test.json: Loot table \"Drops\" (drops.Sword): there is no item by that name",
+            "test.json: Faction \"Monsters\" (responses.Elves): there is no faction by that name",
+        ]
+    );
+}
+
+#[test]
+fn bad_prop_trigger_and_glyph_are_reported() {
+    let json = r##"{
+        "props": [{
+            "name": "Bad Trap",
+            "renderable": { "glyph": "", "fg": "#FF0000", "bg": "#00000G", "order": 2 },
+            "entry_trigger": { "effects": { "damage": "1d6" } }
+        }]
+    }"##;
+    assert_eq!(
+        problems(json),
+        vec![
+            "test.json: Prop \"Bad Trap\" (renderable.glyph): \"\" should be exactly one character",
+            "test.json: Prop \"Bad Trap\" (renderable.bg): \"#00000G\" is not a hex colour like \"#FF00FF\"",
+            "test.json: Prop \"Bad Trap\" (entry_trigger.effects.damage): \"1d6\" is not a whole number",
+        ]
+    );
+}
@@ -0,0 +1,58 @@
+use rogue::raws::{load_raws, RawSources, RawsWatcher, RAWS};
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+fn mob(name: &str) -> String {
+    format!(
+        r#"{{ "mobs": [{{ "name": "{}", "blocks_tile": true, "vision_range": 8,
+            "stats": {{ "max_hp": 5, "hp": 5, "power": 2, "defense": 0 }} }}] }}"#,
+        name
+    )
+}
+
+/// Writes `path`, dating it `secs` seconds from now so the change shows however coarse the
+/// file system's timestamps are.
+fn write(path: &Path, contents: &str, secs: u64) {
+    fs::write(path, contents).unwrap();
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(secs))
+        .unwrap();
+}
+
+fn defined(name: &str) -> bool {
+    RAWS.lock().unwrap().source(name).is_some()
+}
+
+/// The only test in this file, as it reloads the shared `RAWS`.
+#[test]
+fn watcher_reloads_the_raws_when_a_file_changes() {
+    let dir = std::env::temp_dir().join(format!("rogue-raws-watcher-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    write(&dir.join("01-orc.json"), &mob("Orc"), 0);
+    let sources = RawSources {
+        base_dir: Some(dir.clone()),
+        mod_dirs: Vec::new(),
+    };
+    load_raws(&sources).unwrap();
+    let mut watcher = RawsWatcher::new(&sources);
+
+    // Nothing has changed, and it is only checked once a second
+    assert!(watcher.poll(1000.0).is_none());
+    write(&dir.join("02-ogre.json"), &mob("Ogre"), 10);
+    assert!(watcher.poll(500.0).is_none());
+    assert!(!defined("Ogre"));
+    assert!(matches!(watcher.poll(500.0), Some(Ok(()))));
+    assert!(defined("Orc") && defined("Ogre"));
+
+    // A broken file is reported once, and the last good raws stay loaded
+    write(&dir.join("01-orc.json"), "{ \"mobs\": [", 20);
+    assert!(matches!(watcher.poll(1000.0), Some(Err(_))));
+    assert!(watcher.poll(1000.0).is_none());
+    assert!(defined("Orc") && defined("Ogre"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,54 @@
+use rogue::replay::{Replay, ReplayError};
+use std::fs;
+use std::path::PathBuf;
+
+/// Writes `contents` to a replay file of its own in the temp directory.
+fn replay_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rogue-replay-{}-{}", std::process::id(), name));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn missing_replay_is_an_io_error() {
+    let path = std::env::temp_dir().join("rogue-replay-that-does-not-exist.jsonl");
+    assert!(matches!(
+        Replay::load(path.to_str().unwrap()),
+        Err(ReplayError::Io(_))
+    ));
+}
+
+#[test]
+fn damaged_replay_reports_the_line() {
+    let path = replay_file(
+        "damaged.jsonl",
+        "{\"Start\":{\"seed\":1}}\n{\"Command\":\"Wait\"}\nnot json\n",
+    );
+    let result = Replay::load(path.to_str().unwrap());
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(result, Err(ReplayError::Parse { line: 3, .. })));
+}
+
+#[test]
+fn replay_without_start_is_rejected() {
+    let path = replay_file("no-start.jsonl", "{\"Command\":\"Wait\"}\n");
+    let result = Replay::load(path.to_str().unwrap());
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(result, Err(ReplayError::MissingStart)));
+}
+
+#[test]
+fn recorded_game_loads_back() {
+    let path = replay_file("good.jsonl", "");
+    let mut gs = rogue::State::with_seed(7);
+    gs.start_recording(path.to_str().unwrap()).unwrap();
+    gs.run_until_input();
+    gs.play(rogue::Command::Wait);
+    gs.finish_recording();
+
+    let replay = Replay::load(path.to_str().unwrap()).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(replay.seed, 7);
+    assert_eq!(replay.commands.len(), 1);
+    assert!(replay.verify(&replay.run().unwrap()));
+}
@@ -0,0 +1,94 @@
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use rogue::saveload_system::{load_game, BinaryBackend, SaveBackend, SaveError};
+use rogue::{Settings, State};
+use std::fs;
+use std::io::Write;
+
+/// A binary save file holding `raw`, the tagged encoding of a value, as it is before
+/// compression.
+fn binary_file(raw: &[u8]) -> Vec<u8> {
+    let mut encoder = DeflateEncoder::new(b"RGSV".to_vec(), Compression::default());
+    encoder.write_all(raw).unwrap();
+    encoder.finish().unwrap()
+}
+
+/// The reason `data` was rejected, which must be a parse error rather than a panic.
+fn rejection(data: &[u8]) -> String {
+    match BinaryBackend.decode(data) {
+        Err(SaveError::Parse(reason)) => reason,
+        Err(e) => panic!("expected a parse error, got {:?}", e),
+        Ok(value) => panic!("damaged save decoded to {}", value),
+    }
+}
+
+#[test]
+fn crafted_saves_are_reported_as_damaged() {
+    let mut overlong_run = vec![9, 2, 1, 0];
+    overlong_run.extend_from_slice(&[0xff; 9]);
+    overlong_run.extend_from_slice(&[0x01, 0]);
+    let mut deep = [7, 1].repeat(10_000);
+    deep.push(0);
+    let mut long_number = vec![3];
+    long_number.extend_from_slice(&[0x80; 11]);
+
+    let cases: Vec<(&str, Vec<u8>, &str)> = vec![
+        ("unknown tag", vec![42], "unknown tag 42"),
+        (
+            "string past the end",
+            vec![6, 10, b'a'],
+            "it ends too early",
+        ),
+        (
+            "string not UTF-8",
+            vec![6, 2, 0xc3, 0x28],
+            "a string is not UTF-8",
+        ),
+        ("array past the end", vec![7, 100, 0], "it ends too early"),
+        (
+            "trailing data",
+            vec![0, 0],
+            "unexpected data after the save",
+        ),
+        ("number too long", long_number, "a number is too long"),
+        ("overlong run", overlong_run, "an array is too long"),
+        ("deep nesting", deep, "values are nested too deeply"),
+    ];
+    for (case, raw, detail) in cases {
+        assert_eq!(
+            rejection(&binary_file(&raw)),
+            format!("The save file is damaged: {}", detail),
+            "{}",
+            case
+        );
+    }
+}
+
+#[test]
+fn files_that_are_not_saves_are_rejected() {
+    assert_eq!(rejection(b"PNG\x89"), "This is not a Rogue save file.");
+    assert!(rejection(b"RGSV\xff\xff\xff").starts_with("The save file is damaged"));
+}
+
+#[test]
+fn crafted_slot_fails_to_load_with_an_error() {
+    let root = std::env::temp_dir().join(format!("rogue-save-errors-{}", std::process::id()));
+    let mut gs = State::with_settings(Settings {
+        seed: Some(3),
+        show_mapgen_visualizer: false,
+        save_dir: root.to_string_lossy().to_string(),
+        ..Settings::default()
+    })
+    .unwrap();
+    gs.run_until_input();
+    fs::create_dir_all(&root).unwrap();
+    fs::write(
+        root.join("Crafted.sav"),
+        binary_file(&[7, 1].repeat(10_000)),
+    )
+    .unwrap();
+
+    let result = load_game(&mut gs.ecs, "Crafted");
+    fs::remove_dir_all(&root).unwrap();
+    assert!(matches!(result, Err(SaveError::Parse(_))));
+}
@@ -0,0 +1,73 @@
+use rogue::saveload_system::{
+    delete_save, list_slots, load_game, name_slot, save_game, unused_slot_name, ActiveSlot,
+    SaveError,
+};
+use rogue::{GameClock, Settings, State};
+use std::fs;
+use std::path::PathBuf;
+
+fn game(name: &str) -> (State, PathBuf) {
+    let root = std::env::temp_dir().join(format!("rogue-{}-{}", name, std::process::id()));
+    let mut gs = State::with_settings(Settings {
+        seed: Some(8),
+        show_mapgen_visualizer: false,
+        save_dir: root.to_string_lossy().to_string(),
+        ..Settings::default()
+    })
+    .unwrap();
+    gs.run_until_input();
+    (gs, root)
+}
+
+fn bad_name(gs: &mut State, name: &str) -> bool {
+    matches!(name_slot(&mut gs.ecs, name), Err(SaveError::BadName(_)))
+}
+
+#[test]
+fn named_slot_is_saved_under_its_name() {
+    let (mut gs, root) = game("slots-named");
+    assert_eq!(unused_slot_name(&gs.ecs), "Save 1");
+    name_slot(&mut gs.ecs, "  Last stand ").unwrap();
+    save_game(&mut gs.ecs).unwrap();
+    let slots = list_slots(&gs.ecs);
+
+    let (mut other, _) = game("slots-named");
+    let refused = [
+        "",
+        "   ",
+        "Last stand",
+        "../escape",
+        "a.b",
+        "Mira's",
+        "session.checkpoint",
+    ]
+    .iter()
+    .all(|name| bad_name(&mut other, name));
+    fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(slots.len(), 1);
+    assert_eq!(slots[0].name, "Last stand");
+    assert!(refused);
+    assert_eq!(other.ecs.fetch::<ActiveSlot>().name, None);
+}
+
+#[test]
+fn listing_slots_reads_only_their_summaries() {
+    let (mut gs, root) = game("slots-summary");
+    gs.ecs.fetch_mut::<GameClock>().turn = 77;
+    name_slot(&mut gs.ecs, "Quick").unwrap();
+    save_game(&mut gs.ecs).unwrap();
+
+    // Spoil the save itself: the menu still lists it from the summary, and loading finds out
+    fs::write(root.join("Quick.json"), "not a save").unwrap();
+    let slots = list_slots(&gs.ecs);
+    let loaded = load_game(&mut gs.ecs, "Quick");
+    delete_save(&gs.ecs, "Quick").unwrap();
+    let summary_left = root.join("Quick.meta").exists();
+    fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(slots.len(), 1);
+    assert_eq!(slots[0].metadata.as_ref().unwrap().turn, 77);
+    assert!(loaded.is_err());
+    assert!(!summary_left);
+}
@@ -0,0 +1,88 @@
+use rogue::{CombatStats, Command, GameSeed, Map, Position, RunState, State, TileType};
+use specs::prelude::*;
+
+/// A short walk around the first level, with a few turns spent waiting.
+const COMMANDS: &[Command] = &[
+    Command::Move {
+        delta_x: 1,
+        delta_y: 0,
+    },
+    Command::Move {
+        delta_x: 0,
+        delta_y: 1,
+    },
+    Command::Wait,
+    Command::Move {
+        delta_x: -1,
+        delta_y: 0,
+    },
+    Command::Move {
+        delta_x: 0,
+        delta_y: -1,
+    },
+    Command::Wait,
+    Command::Wait,
+    Command::Move {
+        delta_x: 1,
+        delta_y: 1,
+    },
+];
+
+/// What a run's randomness decides: the map, where everything stands and how hurt it is.
+#[derive(PartialEq)]
+struct Outcome {
+    tiles: Vec<TileType>,
+    positions: Vec<(i32, i32)>,
+    hit_points: Vec<i32>,
+}
+
+/// Plays `COMMANDS` through a new game started from `seed`.
+fn play_seeded(seed: u64) -> Outcome {
+    let mut gs = State::with_seed(seed);
+    gs.run_until_input();
+    for command in COMMANDS.iter() {
+        if gs.runstate() == RunState::GameOver {
+            break;
+        }
+        gs.play(*command);
+    }
+
+    let outcome = Outcome {
+        tiles: gs.ecs.fetch::<Map>().tiles.clone(),
+        positions: gs
+            .ecs
+            .read_storage::<Position>()
+            .join()
+            .map(|pos| (pos.x, pos.y))
+            .collect(),
+        hit_points: gs
+            .ecs
+            .read_storage::<CombatStats>()
+            .join()
+            .map(|stats| stats.hp)
+            .collect(),
+    };
+    outcome
+}
+
+#[test]
+fn same_seed_plays_the_same_game() {
+    assert!(play_seeded(42) == play_seeded(42));
+}
+
+#[test]
+fn different_seeds_play_different_games() {
+    assert!(play_seeded(42) != play_seeded(43));
+}
+
+#[test]
+fn new_game_after_game_over_keeps_the_seed() {
+    let fresh = State::with_seed(42);
+    let mut gs = State::with_seed(42);
+    gs.run_until_input();
+    gs.play(Command::Wait);
+    gs.game_over_cleanup();
+
+    assert_eq!(gs.ecs.fetch::<GameSeed>().seed, 42);
+    assert!(gs.ecs.fetch::<Map>().tiles == fresh.ecs.fetch::<Map>().tiles);
+}
@@ -0,0 +1,75 @@
+use rogue::settings;
+use rogue::{Settings, State};
+use std::fs;
+
+fn args(flags: &[&str]) -> Vec<String> {
+    std::iter::once("rogue")
+        .chain(flags.iter().copied())
+        .map(|a| a.to_string())
+        .collect()
+}
+
+/// A settings file path that doesn't exist, so only the defaults and flags apply.
+const NO_FILE: &str = "./no-such-settings.json";
+
+#[test]
+fn flags_override_the_defaults() {
+    let settings = Settings::from_args(
+        &args(&["--depth", "3", "--seed", "9", "--mod", "a", "--mod", "b"]),
+        NO_FILE,
+    )
+    .unwrap();
+    assert_eq!(settings.start_depth, 3);
+    assert_eq!(settings.seed, Some(9));
+    assert_eq!(settings.mod_dirs, vec!["a".to_string(), "b".to_string()]);
+}
+
+#[test]
+fn bad_depth_in_the_settings_file_is_rejected() {
+    for depth in ["0", "-2"].iter() {
+        let path = std::env::temp_dir().join(format!(
+            "rogue-settings-{}-{}.json",
+            std::process::id(),
+            depth
+        ));
+        fs::write(&path, format!("{{\"start_depth\": {}}}", depth)).unwrap();
+        let result = Settings::from_args(&args(&[]), path.to_str().unwrap());
+        fs::remove_file(&path).unwrap();
+        assert!(result.is_err(), "start_depth {} was accepted", depth);
+    }
+}
+
+#[test]
+fn bad_depth_flag_is_rejected() {
+    assert!(Settings::from_args(&args(&["--depth", "0"]), NO_FILE).is_err());
+}
+
+#[test]
+fn flag_without_a_value_is_rejected() {
+    assert!(Settings::from_args(&args(&["--depth"]), NO_FILE).is_err());
+    assert!(Settings::from_args(&args(&["--mod"]), NO_FILE).is_err());
+    assert!(Settings::from_args(&args(&["--seed", "--no-visualizer"]), NO_FILE).is_err());
+}
+
+#[test]
+fn replay_delay_must_be_a_number() {
+    let delay = |flags: &[&str]| settings::number_flag::<f32>(&args(flags), "--replay-delay");
+    assert_eq!(delay(&[]), Ok(None));
+    assert_eq!(delay(&["--replay-delay", "40"]), Ok(Some(40.0)));
+    assert!(delay(&["--replay-delay", "fast"]).is_err());
+    assert!(delay(&["--replay-delay"]).is_err());
+}
+
+#[test]
+fn invalid_raws_stop_the_game_from_starting() {
+    let dir = std::env::temp_dir().join(format!("rogue-bad-mod-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("broken.json"), "{ \"mobs\": [").unwrap();
+    let result = State::with_settings(Settings {
+        mod_dirs: vec![dir.to_string_lossy().to_string()],
+        ..Settings::default()
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    let report = result.err().expect("the game started with broken raws");
+    assert!(report.to_string().contains("broken.json"));
+}
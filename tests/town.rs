@@ -0,0 +1,15 @@
+use rogue::{Map, Name, State};
+use specs::prelude::*;
+
+#[test]
+fn town_is_populated_with_townsfolk() {
+    let mut gs = State::with_seed(5);
+    gs.run_until_input();
+    assert_eq!(gs.ecs.fetch::<Map>().depth, 1);
+
+    let names = gs.ecs.read_storage::<Name>();
+    let count = |wanted: &str| names.join().filter(|n| n.name == wanted).count();
+    assert_eq!(count("Shopkeeper"), 1);
+    assert_eq!(count("Town Guard"), 2);
+    assert!(count("Townsperson") > 0);
+}